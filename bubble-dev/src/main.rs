@@ -6,8 +6,8 @@ use inkwell::{
     OptimizationLevel,
 };
 use libbubble::{
-    ast::GlobalStatement,
-    codegen::build_module,
+    ast::{GlobalStatement, SourceFile},
+    codegen::{build_module, HardeningOptions},
     desugar::desugar_ast,
     parser::{
         grammar::GlobalStatementsParser,
@@ -35,12 +35,11 @@ fn main() {
     let mut binder = Binder::default();
     binder.bind_statements(&mut stmts).expect("Binder failed");
     run_type_checker(&mut stmts).expect("Type checker failed");
-    stmts = desugar_ast(stmts);
+    stmts = desugar_ast(stmts, &SourceFile::new("test.blb", &source_code));
 
     let context = Context::create();
     let module = context.create_module("module");
 
-    build_module(&context, &module, &stmts, true);
     Target::initialize_x86(&InitializationConfig::default());
     let target = Target::from_name("x86-64").unwrap();
     let target_machine = target
@@ -49,11 +48,20 @@ fn main() {
             "x86-64",
             "",
             OptimizationLevel::None,
-            RelocMode::Default,
+            RelocMode::PIC,
             CodeModel::Default,
         )
         .unwrap();
 
+    build_module(
+        &context,
+        &module,
+        &target_machine.get_target_data(),
+        &stmts,
+        HardeningOptions::default(),
+        true,
+    );
+
     target_machine
         .write_to_file(&module, FileType::Object, Path::new("/tmp/test.o"))
         .expect("Failed to build object file");
@@ -1,6 +1,7 @@
-use std::path::PathBuf;
+use std::{num::NonZeroUsize, path::PathBuf};
 
-use clap_derive::Parser;
+use clap_derive::{Parser, ValueEnum};
+use libbubble::type_system::DEFAULT_MAX_STACK_ALLOCATION;
 
 #[derive(Parser)]
 #[command(version, about)]
@@ -10,18 +11,76 @@ pub struct Cli {
     /// Show debug infos (for compiler debugging purposes)
     #[arg(short, long)]
     pub debug: bool,
+    /// Print the desugared AST of the targets instead of compiling them
+    #[arg(long)]
+    pub dump_ast: bool,
+    /// Print the type inferred for each declaration and expression of the
+    /// targets instead of compiling them
+    #[arg(long)]
+    pub dump_types: bool,
     /// Do not link the program (only produces object files)
     #[arg(short, long)]
     pub compile_only: bool,
     /// Emit llvm IR code
     #[arg(short, long)]
     pub emit_llvm: bool,
+    /// Optimize the generated code (promotes variables to registers)
+    #[arg(short = 'O', long)]
+    pub optimize: bool,
+    /// Protect every function against stack buffer overflows
+    #[arg(long)]
+    pub stack_protector: bool,
+    /// Keep the frame pointer in every function (for profilers and debuggers)
+    #[arg(long)]
+    pub frame_pointer: bool,
+    /// Write llvm IR code to this path (`-` for stdout). When several files
+    /// are compiled the path must be a directory
+    #[arg(long, value_name = "PATH")]
+    pub emit_llvm_to: Option<PathBuf>,
     /// Set an alternative ld path (the linker must support ld style arguments)
-    #[arg(short, long)]
+    #[arg(long)]
     pub ld_path: Option<PathBuf>,
+    /// Additional library to link with (e.g. `-l m` for libm)
+    #[arg(short = 'l', long = "library")]
+    pub libraries: Vec<String>,
+    /// Additional directory to search libraries in
+    #[arg(short = 'L', long = "library-path")]
+    pub lib_paths: Vec<PathBuf>,
+    /// Function called when the program starts instead of the C runtime
+    /// entry point. The function must never return (e.g. call `exit`)
+    #[arg(long, value_name = "NAME")]
+    pub entry: Option<String>,
+    /// Do not link the C runtime objects nor the C library (for freestanding
+    /// programs, usually with `--entry`)
+    #[arg(long)]
+    pub no_crt: bool,
     /// Executable output path
     #[arg(short, long)]
     pub output: Option<PathBuf>,
+    /// Enable an optional warning (e.g. `-Wshadow`)
+    #[arg(short = 'W', value_name = "WARNING")]
+    pub warnings: Vec<WarningFlag>,
+    /// Fail the compilation if any warning is reported (same as `-Werror`)
+    #[arg(long)]
+    pub warnings_as_errors: bool,
+    /// Maximum size in bytes of a local array, bigger arrays are rejected
+    /// (defaults to 1 MiB)
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_STACK_ALLOCATION)]
+    pub max_stack_allocation: u64,
+    /// Define a symbol enabling the functions annotated with `@cfg(SYMBOL)`
+    #[arg(short = 'D', long = "define", value_name = "SYMBOL")]
+    pub defines: Vec<String>,
+    /// Number of files compiled in parallel (defaults to the number of CPUs)
+    #[arg(short, long)]
+    pub jobs: Option<NonZeroUsize>,
     /// Targets to compile or link
     pub targets: Vec<PathBuf>,
 }
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WarningFlag {
+    /// A variable declaration shadows a previous one
+    Shadow,
+    /// Treat the reported warnings as errors
+    Error,
+}
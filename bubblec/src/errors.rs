@@ -10,14 +10,20 @@ pub type CompilerResult<T> = Result<T, CompilerError>;
 
 #[derive(Error, Debug)]
 pub enum CompilerError {
-    #[error("Parser error: {0:?}")]
+    #[error("Parser error: {0}")]
     Parser(ParserError),
-    #[error("Binding error: {0:?}")]
+    #[error("Binding error: {0}")]
     Binder(BinderError),
-    #[error("Type checking error: {0:?}")]
+    #[error("Type checking error: {0}")]
     TypeChecker(TypeCheckerError),
+    #[error("{0} warning(s) treated as errors")]
+    WarningsAsErrors(usize),
     #[error("IO error: {0:?}")]
     IOError(io::Error),
     #[error("Linker error: {0}")]
     Linker(String),
+    #[error("Can't write the llvm IR of {0} files to the same output, --emit-llvm-to should be a directory")]
+    SharedLlvmIrOutput(usize),
+    #[error("{}", .0.join("\n"))]
+    Build(Vec<String>),
 }
@@ -1,34 +1,98 @@
 use std::{
-    path::Path,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
 };
 
+use libbubble::codegen::find_crt_directory;
+
 use crate::errors::{CompilerError, CompilerResult};
 
 const LD_LOADER_PATH: &str = "/lib64/ld-linux-x86-64.so.2";
 const LD_PATH: &str = "/usr/bin/ld";
 
+/// Extra libraries to link with the program
+pub struct LinkLibraries<'a> {
+    /// Libraries names passed as `-l<name>`
+    pub libraries: &'a [String],
+    /// Libraries search paths passed as `-L <path>`
+    pub lib_paths: &'a [PathBuf],
+}
+
+/// How the program is started
+#[derive(Default)]
+pub struct EntryOptions<'a> {
+    /// Symbol called at startup (passed as `-e <name>`). The C runtime `_start`
+    /// is used if none is given.
+    pub entry: Option<&'a str>,
+    /// Do not link the C runtime objects and the C library
+    pub no_crt: bool,
+}
+
+fn build_linker_command(
+    objects: &[&Path],
+    executable_path: &Path,
+    linker_path: Option<&str>,
+    extra_libraries: &LinkLibraries,
+    entry_options: &EntryOptions,
+) -> Command {
+    let mut command = Command::new(linker_path.unwrap_or(LD_PATH));
+    command.arg("-m").arg("elf_x86_64");
+
+    if entry_options.no_crt {
+        // Nothing is loaded dynamically without the C library
+        command.arg("-static");
+    } else {
+        let crt_directory = find_crt_directory();
+        command
+            // Produce a position independent executable like most distributions
+            // do by default. Objects are built with the PIC relocation model.
+            .arg("-pie")
+            .arg(crt_directory.join("Scrt1.o")) // C runtime (PIE variant)
+            .arg(crt_directory.join("crti.o")) // C runtime
+            .arg(crt_directory.join("crtn.o")) // C runtime
+            .arg("-L")
+            .arg(crt_directory)
+            .arg("-lc") // Link Lib C
+            .arg("-dynamic-linker")
+            .arg(LD_LOADER_PATH);
+    }
+
+    if let Some(entry) = entry_options.entry {
+        command.arg("-e").arg(entry);
+    }
+
+    command.arg("-o").arg(executable_path).args(objects);
+
+    // Libraries must come after the objects using them
+    for lib_path in extra_libraries.lib_paths {
+        command.arg("-L").arg(lib_path);
+    }
+
+    for library in extra_libraries.libraries {
+        command.arg(format!("-l{library}"));
+    }
+
+    command
+}
+
 pub fn link_objects(
     objects: &[&Path],
     executable_path: &Path,
     linker_path: Option<&str>,
+    extra_libraries: &LinkLibraries,
+    entry_options: &EntryOptions,
 ) -> CompilerResult<()> {
-    let linker_child = Command::new(linker_path.unwrap_or(LD_PATH))
-        .arg("-m")
-        .arg("elf_x86_64")
-        .arg("/usr/lib64/crt1.o") // C runtime
-        .arg("/usr/lib64/crti.o") // C runtime
-        .arg("/usr/lib64/crtn.o") // C runtime
-        .arg("-lc") // Link Lib C
-        .arg("-dynamic-linker")
-        .arg(LD_LOADER_PATH)
-        .arg("-o")
-        .arg(executable_path)
-        .args(objects)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to spawn ld");
+    let linker_child = build_linker_command(
+        objects,
+        executable_path,
+        linker_path,
+        extra_libraries,
+        entry_options,
+    )
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .expect("Failed to spawn ld");
 
     let link_output = linker_child
         .wait_with_output()
@@ -42,3 +106,95 @@ pub fn link_objects(
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsStr;
+
+    use super::*;
+
+    #[test]
+    fn extra_libraries_are_passed_after_objects() {
+        let libraries = ["m".to_string(), "pthread".to_string()];
+        let lib_paths = [PathBuf::from("/opt/lib")];
+        let command = build_linker_command(
+            &[Path::new("main.blb.o")],
+            Path::new("program"),
+            None,
+            &LinkLibraries {
+                libraries: &libraries,
+                lib_paths: &lib_paths,
+            },
+            &EntryOptions::default(),
+        );
+
+        let args: Vec<&OsStr> = command.get_args().collect();
+        let object_position = args
+            .iter()
+            .position(|arg| *arg == "main.blb.o")
+            .expect("Object is not passed to the linker");
+
+        assert_eq!(
+            &args[object_position + 1..],
+            ["-L", "/opt/lib", "-lm", "-lpthread"].map(OsStr::new)
+        );
+    }
+
+    #[test]
+    fn freestanding_program_has_no_c_runtime() {
+        let command = build_linker_command(
+            &[Path::new("start.blb.o")],
+            Path::new("program"),
+            None,
+            &LinkLibraries {
+                libraries: &[],
+                lib_paths: &[],
+            },
+            &EntryOptions {
+                entry: Some("start"),
+                no_crt: true,
+            },
+        );
+
+        let args: Vec<&OsStr> = command.get_args().collect();
+        assert_eq!(
+            args,
+            [
+                "-m",
+                "elf_x86_64",
+                "-static",
+                "-e",
+                "start",
+                "-o",
+                "program",
+                "start.blb.o"
+            ]
+            .map(OsStr::new)
+        );
+    }
+
+    #[test]
+    fn custom_entry_with_c_runtime() {
+        let command = build_linker_command(
+            &[Path::new("main.blb.o")],
+            Path::new("program"),
+            None,
+            &LinkLibraries {
+                libraries: &[],
+                lib_paths: &[],
+            },
+            &EntryOptions {
+                entry: Some("start"),
+                no_crt: false,
+            },
+        );
+
+        let args: Vec<String> = command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+        assert!(args.iter().any(|arg| arg.ends_with("crti.o")));
+        assert!(args.contains(&"-lc".to_string()));
+        assert!(args.windows(2).any(|pair| pair == ["-e", "start"]));
+    }
+}
@@ -1,9 +1,13 @@
 use std::{
+    collections::HashSet,
+    num::NonZeroUsize,
     path::{Path, PathBuf},
     str::FromStr,
+    thread,
 };
 
 use clap::Parser;
+use libbubble::codegen::HardeningOptions;
 
 mod cli;
 mod errors;
@@ -11,8 +15,62 @@ mod io;
 mod linker;
 mod objects;
 
+fn llvm_ir_output(cli: &cli::Cli) -> Option<objects::LlvmIrOutput> {
+    match &cli.emit_llvm_to {
+        Some(path) if path.as_os_str() == "-" => Some(objects::LlvmIrOutput::Stdout),
+        Some(path) => Some(objects::LlvmIrOutput::Path(path.clone())),
+        None if cli.emit_llvm => Some(objects::LlvmIrOutput::NextToObject),
+        None => None,
+    }
+}
+
+fn warning_options(cli: &cli::Cli) -> objects::WarningOptions {
+    objects::WarningOptions {
+        shadow: cli.warnings.contains(&cli::WarningFlag::Shadow),
+        as_errors: cli.warnings_as_errors || cli.warnings.contains(&cli::WarningFlag::Error),
+    }
+}
+
+fn check_options(cli: &cli::Cli) -> objects::CheckOptions {
+    objects::CheckOptions {
+        warnings: warning_options(cli),
+        max_stack_allocation: cli.max_stack_allocation,
+    }
+}
+
+fn codegen_options(cli: &cli::Cli) -> objects::CodegenOptions {
+    objects::CodegenOptions {
+        optimize: cli.optimize,
+        hardening: HardeningOptions {
+            stack_protector: cli.stack_protector,
+            frame_pointer: cli.frame_pointer,
+        },
+    }
+}
+
 fn main() {
     let cli = cli::Cli::parse();
+    let defines: HashSet<String> = cli.defines.iter().cloned().collect();
+
+    if cli.dump_ast {
+        let targets: Vec<&Path> = cli.targets.iter().map(|p| p.as_path()).collect();
+        if let Err(e) = objects::dump_ast_targets(&targets, check_options(&cli), &defines) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+
+        return;
+    }
+
+    if cli.dump_types {
+        let targets: Vec<&Path> = cli.targets.iter().map(|p| p.as_path()).collect();
+        if let Err(e) = objects::dump_types_targets(&targets, check_options(&cli), &defines) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+
+        return;
+    }
 
     match objects::build_objects_targets(
         cli.targets
@@ -20,11 +78,21 @@ fn main() {
             .map(|p| p.as_path())
             .collect::<Vec<&Path>>()
             .as_slice(),
-        cli.debug,
-        cli.emit_llvm,
+        llvm_ir_output(&cli).as_ref(),
+        check_options(&cli),
+        &defines,
+        codegen_options(&cli),
+        cli.jobs
+            .unwrap_or_else(|| thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)),
     ) {
-        Ok(objs) => {
+        Ok((objs, extern_symbols)) => {
             if !cli.compile_only {
+                // Extern functions must be provided by a library
+                let unresolved_symbols = extern_symbols.unresolved().collect::<Vec<_>>().join(", ");
+                if !unresolved_symbols.is_empty() {
+                    eprintln!("note: extern functions resolved at link time: {unresolved_symbols}");
+                }
+
                 if let Err(e) = linker::link_objects(
                     objs.iter()
                         .map(|p| p.as_path())
@@ -35,13 +103,26 @@ fn main() {
                     cli.ld_path
                         .as_ref()
                         .map(|p| p.to_str().expect("failed to convert to path")),
+                    &linker::LinkLibraries {
+                        libraries: &cli.libraries,
+                        lib_paths: &cli.lib_paths,
+                    },
+                    &linker::EntryOptions {
+                        entry: cli.entry.as_deref(),
+                        no_crt: cli.no_crt,
+                    },
                 ) {
-                    eprintln!("{e:?}");
+                    eprintln!("{e}");
+                    if !unresolved_symbols.is_empty() {
+                        eprintln!(
+                            "note: make sure the libraries providing the extern functions are linked (see `-l`)"
+                        );
+                    }
                 }
             }
         }
         Err(e) => {
-            eprintln!("{:?}", e);
+            eprintln!("{e}");
             std::process::exit(1);
         }
     }
@@ -1,14 +1,21 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashSet,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
 
 use inkwell::{
     context::Context,
+    module::Module,
     targets::{CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine},
     OptimizationLevel,
 };
 use libbubble::{
     ast,
-    codegen::build_module,
-    desugar::desugar_ast,
+    codegen::{build_module, run_optimization_passes, HardeningOptions},
+    desugar::{desugar_ast, strip_cfg},
     parser::{grammar::GlobalStatementsParser, lexer::Lexer},
     type_system::{self, binder::Binder},
 };
@@ -18,31 +25,226 @@ use crate::{
     io::load_source_file,
 };
 
-fn parse_source_code(source_code: &str) -> CompilerResult<Vec<ast::GlobalStatement>> {
+/// Parses the source code and removes the functions disabled by a `@cfg`
+/// attribute whose symbol isn't in `defines`
+fn parse_source_code(
+    source_code: &str,
+    defines: &HashSet<String>,
+) -> CompilerResult<Vec<ast::GlobalStatement>> {
     let lexer = Lexer::new(source_code);
     let parser = GlobalStatementsParser::new();
 
-    parser.parse(lexer).map_err(CompilerError::Parser)
+    let stmts = parser.parse(lexer).map_err(CompilerError::Parser)?;
+    Ok(strip_cfg(stmts, defines))
+}
+
+/// Optional warnings enabled on the command line
+#[derive(Clone, Copy, Default)]
+pub struct WarningOptions {
+    pub shadow: bool,
+    /// Fail the compilation if any warning is reported
+    pub as_errors: bool,
 }
 
-fn run_type_checker(stmts: &mut [ast::GlobalStatement]) -> CompilerResult<()> {
-    let mut binder = Binder::default();
+/// Front end options set on the command line
+#[derive(Clone, Copy)]
+pub struct CheckOptions {
+    pub warnings: WarningOptions,
+    /// Maximum size in bytes of a local array
+    pub max_stack_allocation: u64,
+}
+
+/// Code generation options set on the command line
+#[derive(Clone, Copy, Default)]
+pub struct CodegenOptions {
+    pub optimize: bool,
+    pub hardening: HardeningOptions,
+}
+
+fn print_warnings(source_code_path: &Path, warnings: &[type_system::Warning]) {
+    for warning in warnings {
+        eprintln!("{}: warning: {warning}", source_code_path.display());
+    }
+}
+
+/// Creates the machine objects are compiled for
+fn create_target_machine() -> TargetMachine {
+    Target::initialize_x86(&InitializationConfig::default());
+    let target = Target::from_name("x86-64").unwrap();
+    target
+        .create_target_machine(
+            &TargetMachine::get_default_triple(),
+            "x86-64",
+            "",
+            OptimizationLevel::None,
+            RelocMode::PIC,
+            CodeModel::Default,
+        )
+        .unwrap()
+}
+
+/// Width in bits of the pointers of `target_machine`
+fn pointer_width(target_machine: &TargetMachine) -> u32 {
+    target_machine.get_target_data().get_pointer_byte_size(None) * 8
+}
+
+fn run_type_checker(
+    stmts: &mut [ast::GlobalStatement],
+    source_code_path: &Path,
+    check_options: CheckOptions,
+    pointer_width: u32,
+) -> CompilerResult<()> {
+    let mut binder = if check_options.warnings.shadow {
+        Binder::with_shadow_warnings()
+    } else {
+        Binder::default()
+    };
     binder
         .bind_statements(stmts)
         .map_err(CompilerError::Binder)?;
+    print_warnings(source_code_path, binder.warnings());
 
-    type_system::run_type_checker(stmts).map_err(CompilerError::TypeChecker)
+    type_system::run_type_checker_for_target(
+        stmts,
+        check_options.max_stack_allocation,
+        pointer_width,
+    )
+    .map_err(CompilerError::TypeChecker)?;
+
+    if check_options.warnings.as_errors && !binder.warnings().is_empty() {
+        return Err(CompilerError::WarningsAsErrors(binder.warnings().len()));
+    }
+
+    Ok(())
+}
+
+/// Runs the front end on every `.blb` target and prints their desugared AST
+pub fn dump_ast_targets(
+    targets: &[&Path],
+    check_options: CheckOptions,
+    defines: &HashSet<String>,
+) -> CompilerResult<()> {
+    for source_code_path in targets
+        .iter()
+        .filter(|p| p.extension().expect("Failed to extract extension") == "blb")
+    {
+        let source_code = load_source_file(source_code_path).map_err(CompilerError::IOError)?;
+        let mut stmts = parse_source_code(&source_code, defines)?;
+        run_type_checker(
+            &mut stmts,
+            source_code_path,
+            check_options,
+            pointer_width(&create_target_machine()),
+        )?;
+
+        println!("// {}", source_code_path.display());
+        let path = source_code_path.display().to_string();
+        ast::Printer::<std::io::Stdout>::default()
+            .print(desugar_ast(
+                stmts,
+                &ast::SourceFile::new(&path, &source_code),
+            ))
+            .map_err(CompilerError::IOError)?;
+    }
+
+    Ok(())
+}
+
+/// Runs the type checker on every `.blb` target and prints the type of each
+/// declaration and expression
+pub fn dump_types_targets(
+    targets: &[&Path],
+    check_options: CheckOptions,
+    defines: &HashSet<String>,
+) -> CompilerResult<()> {
+    for source_code_path in targets
+        .iter()
+        .filter(|p| p.extension().expect("Failed to extract extension") == "blb")
+    {
+        let source_code = load_source_file(source_code_path).map_err(CompilerError::IOError)?;
+        let mut stmts = parse_source_code(&source_code, defines)?;
+        run_type_checker(
+            &mut stmts,
+            source_code_path,
+            check_options,
+            pointer_width(&create_target_machine()),
+        )?;
+
+        println!("// {}", source_code_path.display());
+        type_system::TypeDumper::new(std::io::stdout(), &source_code)
+            .dump(&stmts)
+            .map_err(CompilerError::IOError)?;
+    }
+
+    Ok(())
+}
+
+/// Where the LLVM IR of a compiled file is written
+pub enum LlvmIrOutput {
+    /// `<file>.ll` in the current directory
+    NextToObject,
+    Stdout,
+    /// A file or, if it is a directory, `<file>.ll` inside this directory
+    Path(PathBuf),
+}
+
+impl LlvmIrOutput {
+    /// Whether the IR of every compiled file is written to the same place
+    fn is_shared(&self) -> bool {
+        match self {
+            LlvmIrOutput::NextToObject => false,
+            LlvmIrOutput::Stdout => true,
+            LlvmIrOutput::Path(path) => !path.is_dir(),
+        }
+    }
+}
+
+fn emit_llvm_ir(llvm_module: &Module, object_name: &Path, output: &LlvmIrOutput) {
+    let file_name = format!(
+        "{}.ll",
+        object_name
+            .file_stem()
+            .expect("Failed to extract stem")
+            .to_str()
+            .expect("Failed to convert to str")
+    );
+
+    let ir_path = match output {
+        LlvmIrOutput::Stdout => {
+            print!("{}", llvm_module.print_to_string().to_string());
+            return;
+        }
+        LlvmIrOutput::NextToObject => PathBuf::from(file_name),
+        LlvmIrOutput::Path(path) if path.is_dir() => path.join(file_name),
+        LlvmIrOutput::Path(path) => path.clone(),
+    };
+
+    llvm_module
+        .print_to_file(ir_path)
+        .expect("Failed to emit llvm ir");
 }
 
 fn build_object(
-    source_code: &str,
+    source: &ast::SourceFile,
+    mut stmts: Vec<ast::GlobalStatement>,
     object_name: &Path,
-    print_llvmir: bool,
-    emit_llvmir: bool,
+    llvm_ir_output: Option<&LlvmIrOutput>,
+    check_options: CheckOptions,
+    codegen_options: CodegenOptions,
+    extern_symbols: &mut ast::ExternSymbols,
 ) -> CompilerResult<()> {
-    let mut stmts = parse_source_code(source_code)?;
-    run_type_checker(&mut stmts)?;
-    let desugared_stmts = desugar_ast(stmts);
+    let target_machine = create_target_machine();
+    run_type_checker(
+        &mut stmts,
+        Path::new(source.path()),
+        check_options,
+        pointer_width(&target_machine),
+    )?;
+    // Desugaring needs the types so it can't run before the type checker. It
+    // binds the desugared statements again so codegen doesn't read
+    // definitions pointing to the statements it replaced.
+    let desugared_stmts = desugar_ast(stmts, source);
+    extern_symbols.add_statements(&desugared_stmts);
     let llvm_context = Context::create();
     let llvm_module = llvm_context.create_module(
         object_name
@@ -52,63 +254,135 @@ fn build_object(
             .expect("Failed to convert to str"),
     );
 
-    build_module(&llvm_context, &llvm_module, &desugared_stmts, print_llvmir);
-
-    Target::initialize_x86(&InitializationConfig::default());
-    let target = Target::from_name("x86-64").unwrap();
-    let target_machine = target
-        .create_target_machine(
-            &TargetMachine::get_default_triple(),
-            "x86-64",
-            "",
-            OptimizationLevel::None,
-            RelocMode::Default,
-            CodeModel::Default,
-        )
-        .unwrap();
+    build_module(
+        &llvm_context,
+        &llvm_module,
+        &target_machine.get_target_data(),
+        &desugared_stmts,
+        codegen_options.hardening,
+        false,
+    );
+    if codegen_options.optimize {
+        run_optimization_passes(&llvm_module);
+    }
 
     target_machine
         .write_to_file(&llvm_module, FileType::Object, Path::new(object_name))
         .expect("Failed to build object file");
 
-    if emit_llvmir {
-        llvm_module
-            .print_to_file(format!(
-                "{}.ll",
-                object_name
-                    .file_stem()
-                    .expect("Failed to extract stem")
-                    .to_str()
-                    .expect("Failed to convert to str")
-            ))
-            .expect("Failed to emit llvm ir");
+    if let Some(output) = llvm_ir_output {
+        emit_llvm_ir(&llvm_module, object_name, output);
     }
 
     Ok(())
 }
 
+fn object_path(source_code_path: &Path) -> PathBuf {
+    PathBuf::from(format!(
+        "{}.o",
+        source_code_path
+            .file_name()
+            .expect("Failed to extract file name")
+            .to_str()
+            .expect("Failed to convert to str")
+    ))
+}
+
+fn build_target(
+    source_code_path: &Path,
+    llvm_ir_output: Option<&LlvmIrOutput>,
+    check_options: CheckOptions,
+    defines: &HashSet<String>,
+    codegen_options: CodegenOptions,
+) -> CompilerResult<ast::ExternSymbols> {
+    let source_code = load_source_file(source_code_path).map_err(CompilerError::IOError)?;
+    let stmts = parse_source_code(&source_code, defines)?;
+    let mut extern_symbols = ast::ExternSymbols::default();
+    let path = source_code_path.display().to_string();
+    build_object(
+        &ast::SourceFile::new(&path, &source_code),
+        stmts,
+        &object_path(source_code_path),
+        llvm_ir_output,
+        check_options,
+        codegen_options,
+        &mut extern_symbols,
+    )?;
+
+    Ok(extern_symbols)
+}
+
+/// Builds every `.blb` target using up to `jobs` threads. Each thread owns the
+/// LLVM context of the file it is compiling. Errors of all the targets are
+/// reported together.
 pub fn build_objects_targets(
     targets: &[&Path],
-    print_llvmir: bool,
-    emit_llvmir: bool,
-) -> CompilerResult<Vec<PathBuf>> {
-    let mut built_objects = Vec::new();
-    for source_code_path in targets
+    llvm_ir_output: Option<&LlvmIrOutput>,
+    check_options: CheckOptions,
+    defines: &HashSet<String>,
+    codegen_options: CodegenOptions,
+    jobs: NonZeroUsize,
+) -> CompilerResult<(Vec<PathBuf>, ast::ExternSymbols)> {
+    let sources: Vec<&Path> = targets
         .iter()
+        .copied()
         .filter(|p| p.extension().expect("Failed to extract extension") == "blb")
-    {
-        let source_code = load_source_file(source_code_path).map_err(CompilerError::IOError)?;
-        let object_path = PathBuf::from(format!(
-            "{}.o",
-            source_code_path
-                .file_name()
-                .expect("Failed to extract file name")
-                .to_str()
-                .expect("Failed to convert to str")
-        ));
-        build_object(&source_code, &object_path, print_llvmir, emit_llvmir)?;
-        built_objects.push(object_path);
+        .collect();
+    // Workers would write their modules to the same output concurrently
+    if sources.len() > 1 && llvm_ir_output.is_some_and(LlvmIrOutput::is_shared) {
+        return Err(CompilerError::SharedLlvmIrOutput(sources.len()));
+    }
+    let next_target = AtomicUsize::new(0);
+
+    // Compiler errors can't be sent across threads, they are rendered in the
+    // thread that produced them.
+    let mut results: Vec<(usize, Result<ast::ExternSymbols, String>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.get().min(sources.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next_target.fetch_add(1, Ordering::Relaxed);
+                        let Some(source_code_path) = sources.get(index) else {
+                            break results;
+                        };
+
+                        let result = build_target(
+                            source_code_path,
+                            llvm_ir_output,
+                            check_options,
+                            defines,
+                            codegen_options,
+                        )
+                        .map_err(|e| format!("{}: {e}", source_code_path.display()));
+                        results.push((index, result));
+                    }
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("Build thread panicked"))
+            .collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+
+    let mut extern_symbols = ast::ExternSymbols::default();
+    let mut errors = Vec::new();
+    for (_, result) in results {
+        match result {
+            Ok(symbols) => extern_symbols.merge(symbols),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(CompilerError::Build(errors));
     }
 
-    Ok(built_objects)
+    Ok((
+        sources.into_iter().map(object_path).collect(),
+        extern_symbols,
+    ))
 }
@@ -0,0 +1,40 @@
+use std::{fs, process::Command};
+
+/// Compiles two files writing their llvm IR to `output` and returns whether it
+/// succeeded
+fn emit_llvm_to(output: &str, source_paths: [&str; 2]) -> bool {
+    for source_path in source_paths {
+        fs::write(source_path, "function f(): i32 { return 0; }")
+            .expect("Failed to write source file");
+    }
+
+    Command::new(env!("CARGO_BIN_EXE_bubblec"))
+        .args(["--compile-only", "--emit-llvm-to", output])
+        .args(source_paths)
+        .output()
+        .expect("Failed to invoke bubblec")
+        .status
+        .success()
+}
+
+#[test]
+fn test_emit_llvm_to_single_output_with_several_files() {
+    for output in ["-", "/tmp/emit_llvm_shared.ll"] {
+        assert!(!emit_llvm_to(
+            output,
+            ["/tmp/emit_llvm_shared_a.blb", "/tmp/emit_llvm_shared_b.blb"]
+        ));
+    }
+}
+
+#[test]
+fn test_emit_llvm_to_directory_with_several_files() {
+    fs::create_dir_all("/tmp/emit_llvm_dir").expect("Failed to create directory");
+
+    assert!(emit_llvm_to(
+        "/tmp/emit_llvm_dir",
+        ["/tmp/emit_llvm_dir_a.blb", "/tmp/emit_llvm_dir_b.blb"]
+    ));
+    assert!(fs::metadata("/tmp/emit_llvm_dir/emit_llvm_dir_a.blb.ll").is_ok());
+    assert!(fs::metadata("/tmp/emit_llvm_dir/emit_llvm_dir_b.blb.ll").is_ok());
+}
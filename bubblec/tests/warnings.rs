@@ -0,0 +1,58 @@
+use std::{fs, process::Command};
+
+const SHADOWING_CODE: &str = r#"
+function main(): i32 {
+    let a: i32 = 1;
+    if true {
+        let a: i32 = 2;
+    }
+    return a;
+}
+"#;
+
+/// Runs the front end on `code` with the given flags and returns whether it
+/// succeeded
+fn check_code(code: &str, source_path: &str, flags: &[&str]) -> bool {
+    fs::write(source_path, code).expect("Failed to write source file");
+
+    Command::new(env!("CARGO_BIN_EXE_bubblec"))
+        .arg("--dump-types")
+        .args(flags)
+        .arg(source_path)
+        .output()
+        .expect("Failed to invoke bubblec")
+        .status
+        .success()
+}
+
+#[test]
+fn test_shadow_warning_is_not_an_error() {
+    assert!(check_code(
+        SHADOWING_CODE,
+        "/tmp/shadow_warning.blb",
+        &["-Wshadow"]
+    ));
+}
+
+#[test]
+fn test_warnings_as_errors() {
+    for flags in [
+        ["-Wshadow", "-Werror"].as_slice(),
+        ["-Wshadow", "--warnings-as-errors"].as_slice(),
+    ] {
+        assert!(!check_code(
+            SHADOWING_CODE,
+            "/tmp/shadow_warning_as_error.blb",
+            flags
+        ));
+    }
+}
+
+#[test]
+fn test_warnings_as_errors_without_warning() {
+    assert!(check_code(
+        "function main(): i32 { return 0; }",
+        "/tmp/no_warning_as_error.blb",
+        &["-Wshadow", "-Werror"]
+    ));
+}
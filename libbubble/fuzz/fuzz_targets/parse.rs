@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Run with `cargo fuzz run parse` from the `libbubble` directory
+fuzz_target!(|data: &[u8]| {
+    if let Ok(source_code) = std::str::from_utf8(data) {
+        let _ = libbubble::try_parse(source_code);
+    }
+});
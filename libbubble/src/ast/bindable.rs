@@ -1,10 +1,15 @@
-use super::{ArrayAccess, Call, FunctionStatement, LetStatement, Literal, StructStatement, Type};
+use super::{
+    ArrayAccess, Builtin, Call, EnumStatement, FunctionStatement, LetStatement, Literal,
+    StructStatement, Type,
+};
 
 #[derive(Debug, Clone)]
 pub enum Definition {
     Struct(*const StructStatement),
+    Enum(*const EnumStatement),
     LocalVariable(*const LetStatement),
     Function(*const FunctionStatement),
+    Builtin(Builtin),
 }
 
 impl Definition {
@@ -19,12 +24,17 @@ impl Definition {
     pub fn is_function(&self) -> bool {
         matches!(self, Definition::Function(_))
     }
+
+    pub fn is_builtin(&self) -> bool {
+        matches!(self, Definition::Builtin(_))
+    }
 }
 
 pub trait Bindable {
     fn get_definition(&self) -> &Definition;
     fn set_definition(&mut self, definition: Definition);
     fn get_struct_def(&self) -> &StructStatement;
+    fn get_enum_def(&self) -> &EnumStatement;
     fn get_local_variable_def(&self) -> &LetStatement;
     fn get_function_def(&self) -> &FunctionStatement;
 }
@@ -48,6 +58,14 @@ macro_rules! impl_bindable {
                 }
             }
 
+            fn get_enum_def(&self) -> &EnumStatement {
+                if let Some(Definition::Enum(enm)) = self.definition {
+                    unsafe { &(*enm) }
+                } else {
+                    panic!("Get enum def but was {:?}", self);
+                }
+            }
+
             fn get_local_variable_def(&self) -> &LetStatement {
                 if let Some(Definition::LocalVariable(var)) = self.definition {
                     unsafe { &(*var) }
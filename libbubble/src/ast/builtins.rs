@@ -0,0 +1,36 @@
+/// Functions provided by the compiler. They are called like regular functions
+/// but don't have to be declared. A function declared with the same name takes
+/// precedence over the builtin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Builtin {
+    Min,
+    Max,
+    IsNull,
+    Len,
+}
+
+impl Builtin {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "min" => Some(Builtin::Min),
+            "max" => Some(Builtin::Max),
+            "is_null" => Some(Builtin::IsNull),
+            "len" => Some(Builtin::Len),
+            _ => None,
+        }
+    }
+
+    /// Whether the call has the type of its arguments, like `min` and `max`.
+    pub fn returns_argument_type(&self) -> bool {
+        matches!(self, Builtin::Min | Builtin::Max)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Builtin::Min => "min",
+            Builtin::Max => "max",
+            Builtin::IsNull => "is_null",
+            Builtin::Len => "len",
+        }
+    }
+}
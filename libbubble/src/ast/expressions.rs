@@ -4,18 +4,25 @@ use super::{
     bindable::Definition,
     impl_locatable,
     location::{Locatable, TokenLocation},
+    statements::Statement,
+    types::Type,
 };
 
 #[derive(Debug, Clone)]
 pub enum Expression {
-    Group(Box<Expression>),
+    Group(Group),
     BinaryOperation(BinaryOperation),
     Literal(Literal),
     Call(Call),
+    IndirectCall(IndirectCall),
     Assignment(Assignment),
     ArrayInitializer(ArrayInitializer),
     AddrOf(AddrOf),
     Deref(Deref),
+    Slice(Slice),
+    AlignOf(AlignOf),
+    Tuple(Tuple),
+    TryNull(TryNull),
 }
 
 impl Expression {
@@ -24,10 +31,30 @@ impl Expression {
     }
 }
 
+/// A parenthesized expression. Its type is the inner expression type, it is
+/// stored to avoid walking nested groups each time it is needed.
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub expr: Box<Expression>,
+    location: TokenLocation,
+    pub(crate) ty: Option<type_system::Type>,
+}
+
+impl Group {
+    pub fn new(tk_begin: usize, tk_end: usize, expr: Box<Expression>) -> Self {
+        Self {
+            expr,
+            location: TokenLocation::new(tk_begin, tk_end),
+            ty: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AddrOf {
     pub expr: Box<Expression>,
     location: TokenLocation,
+    pub(crate) ty: Option<type_system::Type>,
 }
 
 impl AddrOf {
@@ -35,6 +62,7 @@ impl AddrOf {
         Self {
             expr,
             location: TokenLocation::new(tk_begin, tk_end),
+            ty: None,
         }
     }
 }
@@ -54,6 +82,29 @@ impl Deref {
     }
 }
 
+/// `expr?` returns null from the enclosing function if the pointer `expr` is
+/// null, otherwise it yields the pointer.
+#[derive(Debug, Clone)]
+pub struct TryNull {
+    pub expr: Box<Expression>,
+    /// Deferred expressions to run before returning null, they are set when
+    /// desugaring `defer` statements
+    pub deferred: Vec<Statement>,
+    location: TokenLocation,
+    pub(crate) ty: Option<type_system::Type>,
+}
+
+impl TryNull {
+    pub fn new(tk_begin: usize, tk_end: usize, expr: Box<Expression>) -> Self {
+        Self {
+            expr,
+            deferred: Vec::new(),
+            location: TokenLocation::new(tk_begin, tk_end),
+            ty: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Null {
     location: TokenLocation,
@@ -97,6 +148,9 @@ impl Assignment {
 pub struct Call {
     pub callee: String,
     pub arguments: Vec<Expression>,
+    /// Method calls hold their receiver as first argument. They are turned
+    /// into plain calls to the method function by the binder.
+    pub(crate) is_method_call: bool,
     location: TokenLocation,
     pub(crate) ty: Option<type_system::Type>,
     pub(crate) definition: Option<Definition>,
@@ -107,11 +161,54 @@ impl Call {
         Self {
             callee,
             arguments,
+            is_method_call: false,
             location: TokenLocation::new(tk_begin, tk_end),
             ty: None,
             definition: None,
         }
     }
+
+    /// Creates a `receiver.method(arguments)` call.
+    pub fn new_method_call(
+        tk_begin: usize,
+        tk_end: usize,
+        receiver: Expression,
+        method: String,
+        mut arguments: Vec<Expression>,
+    ) -> Self {
+        arguments.insert(0, receiver);
+
+        Self {
+            is_method_call: true,
+            ..Self::new(tk_begin, tk_end, method, arguments)
+        }
+    }
+}
+
+/// A call through a function pointer computed by an expression, e.g. a
+/// dispatch table entry `table[i](arguments)`.
+#[derive(Debug, Clone)]
+pub struct IndirectCall {
+    pub callee: Box<Expression>,
+    pub arguments: Vec<Expression>,
+    location: TokenLocation,
+    pub(crate) ty: Option<type_system::Type>,
+}
+
+impl IndirectCall {
+    pub fn new(
+        tk_begin: usize,
+        tk_end: usize,
+        callee: Box<Expression>,
+        arguments: Vec<Expression>,
+    ) -> Self {
+        Self {
+            callee,
+            arguments,
+            location: TokenLocation::new(tk_begin, tk_end),
+            ty: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -158,18 +255,32 @@ impl Literal {
             ty: None,
         }
     }
+
+    /// Return the variable name if this literal is a variable that can be
+    /// assigned to directly.
+    pub fn as_lvalue(&self) -> Option<&str> {
+        match &self.literal_type {
+            LiteralType::Identifier(name) => Some(name),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum LiteralType {
     True,
     False,
-    Integer(i64),
+    Integer(i128),
     Float(f64),
     Identifier(String),
     ArrayAccess(ArrayAccess),
     String(String),
     Null(Null),
+    /// `Enum.Variant`
+    EnumVariant {
+        enum_name: String,
+        variant: String,
+    },
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -216,6 +327,70 @@ impl ArrayAccess {
     }
 }
 
+/// A view over the `[start, end[` elements of an array.
+#[derive(Debug, Clone)]
+pub struct Slice {
+    pub expr: Box<Expression>,
+    pub start: Box<Expression>,
+    pub end: Box<Expression>,
+    pub(crate) ty: Option<type_system::Type>,
+    location: TokenLocation,
+}
+
+impl Slice {
+    pub fn new(
+        tk_begin: usize,
+        tk_end: usize,
+        expr: Box<Expression>,
+        start: Box<Expression>,
+        end: Box<Expression>,
+    ) -> Self {
+        Self {
+            expr,
+            start,
+            end,
+            ty: None,
+            location: TokenLocation::new(tk_begin, tk_end),
+        }
+    }
+}
+
+/// The ABI alignment in bytes of a type, e.g. `alignof(i64)`.
+#[derive(Debug, Clone)]
+pub struct AlignOf {
+    pub aligned_type: Type,
+    pub(crate) ty: Option<type_system::Type>,
+    location: TokenLocation,
+}
+
+impl AlignOf {
+    pub fn new(tk_begin: usize, tk_end: usize, aligned_type: Type) -> Self {
+        Self {
+            aligned_type,
+            ty: None,
+            location: TokenLocation::new(tk_begin, tk_end),
+        }
+    }
+}
+
+/// `(a, b)`, a tuple holding the values of at least two expressions
+#[derive(Debug, Clone)]
+pub struct Tuple {
+    pub values: Vec<Expression>,
+    pub(crate) ty: Option<type_system::Type>,
+    location: TokenLocation,
+}
+
+impl Tuple {
+    pub fn new(tk_begin: usize, tk_end: usize, values: Vec<Expression>) -> Self {
+        Self {
+            values,
+            ty: None,
+            location: TokenLocation::new(tk_begin, tk_end),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ArrayInitializer {
     pub values: Vec<Box<Expression>>,
@@ -235,12 +410,38 @@ impl ArrayInitializer {
 
 impl_locatable!(
     AddrOf,
+    AlignOf,
     ArrayAccess,
     ArrayInitializer,
     Assignment,
     BinaryOperation,
     Call,
     Deref,
+    Group,
+    IndirectCall,
     Literal,
-    Null
+    Null,
+    Slice,
+    TryNull,
+    Tuple
 );
+
+impl Locatable for Expression {
+    fn get_location(&self) -> &TokenLocation {
+        match self {
+            Expression::Group(g) => g.get_location(),
+            Expression::BinaryOperation(bo) => bo.get_location(),
+            Expression::Literal(l) => l.get_location(),
+            Expression::Call(c) => c.get_location(),
+            Expression::IndirectCall(c) => c.get_location(),
+            Expression::Assignment(a) => a.get_location(),
+            Expression::ArrayInitializer(a) => a.get_location(),
+            Expression::AddrOf(a) => a.get_location(),
+            Expression::Deref(d) => d.get_location(),
+            Expression::Slice(s) => s.get_location(),
+            Expression::AlignOf(a) => a.get_location(),
+            Expression::Tuple(t) => t.get_location(),
+            Expression::TryNull(t) => t.get_location(),
+        }
+    }
+}
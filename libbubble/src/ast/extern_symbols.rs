@@ -0,0 +1,88 @@
+use std::{collections::BTreeSet, convert::Infallible};
+
+use super::{Call, Definition, FunctionStatement, GlobalStatement, Literal, LiteralType, Visitor};
+
+/// Functions a program calls without defining them. They must be provided by
+/// another object file or a library at link time.
+#[derive(Debug, Default)]
+pub struct ExternSymbols {
+    declared: BTreeSet<String>,
+    defined: BTreeSet<String>,
+    called: BTreeSet<String>,
+}
+
+impl ExternSymbols {
+    /// Collects symbols from a desugared module
+    pub fn collect(stmts: &[GlobalStatement]) -> Self {
+        let mut symbols = Self::default();
+        symbols.add_statements(stmts);
+        symbols
+    }
+
+    /// Adds the symbols of another module. A function declared as extern in
+    /// a module may be defined in another one.
+    pub fn add_statements(&mut self, stmts: &[GlobalStatement]) {
+        for stmt in stmts {
+            self.visit_global_statement(stmt).expect("Never fails");
+        }
+    }
+
+    /// Merges symbols collected separately, e.g. in another thread
+    pub fn merge(&mut self, other: ExternSymbols) {
+        self.declared.extend(other.declared);
+        self.defined.extend(other.defined);
+        self.called.extend(other.called);
+    }
+
+    /// Extern functions that are called but defined in none of the collected
+    /// modules.
+    pub fn unresolved(&self) -> impl Iterator<Item = &str> {
+        self.called
+            .iter()
+            .filter(|name| self.declared.contains(*name) && !self.defined.contains(*name))
+            .map(String::as_str)
+    }
+}
+
+impl<'ast> Visitor<'ast, Infallible> for ExternSymbols {
+    fn visit_function(&mut self, stmt: &'ast FunctionStatement) -> Result<(), Infallible> {
+        match &stmt.body {
+            Some(body) => {
+                self.defined.insert(stmt.name.clone());
+                self.visit_statements(body)
+            }
+            None => {
+                self.declared.insert(stmt.name.clone());
+                Ok(())
+            }
+        }
+    }
+
+    fn visit_call(&mut self, expr: &'ast Call) -> Result<(), Infallible> {
+        self.called.insert(expr.callee.clone());
+
+        for argument in &expr.arguments {
+            self.visit_expression(argument)?;
+        }
+
+        Ok(())
+    }
+
+    /// A function used as a value, e.g. in a dispatch table, is needed at
+    /// link time like a called one
+    fn visit_literal(&mut self, expr: &'ast Literal) -> Result<(), Infallible> {
+        match &expr.literal_type {
+            LiteralType::Identifier(name)
+                if matches!(expr.definition, Some(Definition::Function(_))) =>
+            {
+                self.called.insert(name.clone());
+                Ok(())
+            }
+            LiteralType::ArrayAccess(array_access) => {
+                self.visit_expression(&array_access.identifier)?;
+                self.visit_expression(&array_access.index)
+            }
+            _ => Ok(()),
+        }
+    }
+}
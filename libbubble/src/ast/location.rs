@@ -17,6 +17,33 @@ impl TokenLocation {
     }
 }
 
+/// A source file. Token locations are byte offsets in its code.
+pub struct SourceFile<'a> {
+    path: &'a str,
+    code: &'a str,
+}
+
+impl<'a> SourceFile<'a> {
+    pub fn new(path: &'a str, code: &'a str) -> Self {
+        Self { path, code }
+    }
+
+    pub fn path(&self) -> &str {
+        self.path
+    }
+
+    /// Line and column, both starting at 1, of the byte at `offset`
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let before = &self.code[..offset];
+        let line_begin = before.rfind('\n').map_or(0, |newline| newline + 1);
+
+        (
+            before.matches('\n').count() + 1,
+            before[line_begin..].chars().count() + 1,
+        )
+    }
+}
+
 pub trait Locatable {
     fn get_location(&self) -> &TokenLocation;
 }
@@ -1,5 +1,7 @@
 mod bindable;
+mod builtins;
 mod expressions;
+mod extern_symbols;
 mod location;
 mod printer;
 mod statements;
@@ -7,7 +9,9 @@ mod types;
 mod visitor;
 
 pub use bindable::*;
+pub use builtins::*;
 pub use expressions::*;
+pub use extern_symbols::*;
 pub use location::*;
 pub use printer::*;
 pub use statements::*;
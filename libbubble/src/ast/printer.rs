@@ -1,11 +1,33 @@
 use std::io;
 
 use super::{
-    visitor::Visitor, Assignment, BinaryOperation, BreakStatement, Call, ContinueStatement,
-    ForStatement, FunctionStatement, GlobalStatement, IfStatement, LetStatement, Literal,
-    ReturnStatement, StructStatement, Type, TypeKind, WhileStatement,
+    visitor::Visitor, AlignOf, AssertStatement, Assignment, BinaryOperation, BreakStatement, Call,
+    CallingConvention, ContinueStatement, DestructuringKind, EnumStatement, Expression,
+    ForStatement, FunctionStatement, GlobalStatement, IfStatement, IndirectCall, LetStatement,
+    LetTupleStatement, Literal, ReturnStatement, Slice, StructStatement, TryNull, Tuple, Type,
+    TypeKind, WhileStatement,
 };
 
+/// Escapes the characters of a string literal the lexer would not read back
+/// as is.
+fn escape_string(content: &str) -> String {
+    let mut escaped = String::with_capacity(content.len());
+
+    for chr in content.chars() {
+        match chr {
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\0' => escaped.push_str("\\0"),
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            _ => escaped.push(chr),
+        }
+    }
+
+    escaped
+}
+
 pub struct Printer<Writer: io::Write> {
     indent_level: usize,
     writer: Writer,
@@ -23,6 +45,13 @@ impl std::default::Default for Printer<io::Stdout> {
 }
 
 impl<T: io::Write> Printer<T> {
+    pub fn new(writer: T) -> Self {
+        Self {
+            indent_level: 0,
+            writer,
+        }
+    }
+
     pub fn print(&mut self, statements: Vec<GlobalStatement>) -> PrinterResult {
         for stmt in &statements {
             self.visit_global_statement(stmt)?;
@@ -59,6 +88,15 @@ impl<T: io::Write> Printer<T> {
 
 impl<'ast, T: io::Write> Visitor<'ast, io::Error> for Printer<T> {
     fn visit_function(&mut self, stmt: &FunctionStatement) -> PrinterResult {
+        for attribute in &stmt.attributes {
+            self.write(&format!("@{attribute}\n"))?;
+        }
+        if stmt.calling_convention != CallingConvention::C {
+            self.write(&format!("extern \"{}\" ", stmt.calling_convention.abi()))?;
+        }
+        if stmt.is_exported {
+            self.write("export ")?;
+        }
         self.write("function ")?;
         self.write(&stmt.name)?;
 
@@ -104,21 +142,63 @@ impl<'ast, T: io::Write> Visitor<'ast, io::Error> for Printer<T> {
         self.write("}\n")
     }
 
+    fn visit_enum(&mut self, stmt: &EnumStatement) -> PrinterResult {
+        self.write(&format!("enum {} {{", stmt.name))?;
+        self.indent_and_newline()?;
+
+        for variant in &stmt.variants {
+            self.write(&format!("{},\n", variant))?;
+        }
+
+        self.dec_indent_and_newline()?;
+        self.write("}\n")
+    }
+
     fn visit_let(&mut self, stmt: &LetStatement) -> PrinterResult {
-        self.write("let ")?;
+        if stmt.is_extern {
+            self.write("extern ")?;
+        }
+        self.write(match (stmt.is_const, stmt.is_static) {
+            (true, _) => "const ",
+            (false, true) => "static ",
+            (false, false) => "let ",
+        })?;
         self.write(&stmt.name)?;
 
         if let Some(ref ty) = stmt.declaration_type {
+            self.write(": ")?;
             self.visit_type_kind(ty)?;
         }
 
-        self.write(" = ")?;
-        self.visit_expression(stmt.init_exp.as_ref().unwrap())?;
+        if let Some(init_exp) = &stmt.init_exp {
+            self.write(" = ")?;
+            self.visit_expression(init_exp)?;
+        }
         self.write(";\n")?;
 
         Ok(())
     }
 
+    fn visit_let_tuple(&mut self, stmt: &LetTupleStatement) -> PrinterResult {
+        let (open, close) = match stmt.kind {
+            DestructuringKind::Tuple => ("(", ")"),
+            DestructuringKind::Array => ("[", "]"),
+        };
+
+        self.write("let ")?;
+        self.write(open)?;
+        for (i, declaration) in stmt.declarations.iter().enumerate() {
+            if i > 0 {
+                self.write(", ")?;
+            }
+            self.write(&declaration.name)?;
+        }
+        self.write(close)?;
+        self.write(" = ")?;
+        self.visit_expression(&stmt.init_exp)?;
+        self.write(";\n")
+    }
+
     fn visit_if(&mut self, stmt: &IfStatement) -> PrinterResult {
         self.write("if ")?;
         self.visit_expression(&stmt.condition)?;
@@ -149,6 +229,14 @@ impl<'ast, T: io::Write> Visitor<'ast, io::Error> for Printer<T> {
         self.write("}")?;
         self.dec_indent_and_newline()?;
 
+        if let Some(else_clause) = &stmt.else_clause {
+            self.write("else {")?;
+            self.indent_and_newline()?;
+            self.visit_statements(else_clause)?;
+            self.write("}")?;
+            self.dec_indent_and_newline()?;
+        }
+
         Ok(())
     }
 
@@ -162,19 +250,22 @@ impl<'ast, T: io::Write> Visitor<'ast, io::Error> for Printer<T> {
         }
 
         self.write(" = ")?;
-        self.visit_let(&stmt.init_decl)?;
+        self.visit_expression(
+            stmt.init_decl
+                .init_exp
+                .as_ref()
+                .expect("For loop variable has no init exp"),
+        )?;
         self.write("; ")?;
         self.visit_expression(&stmt.continue_expression)?;
         self.write("; ")?;
         self.visit_expression(&stmt.modify_expression)?;
 
-        self.write("{")?;
+        self.write(" {")?;
         self.indent_and_newline()?;
         self.visit_statements(&stmt.body)?;
-        self.write("}")?;
         self.dec_indent_and_newline()?;
-
-        Ok(())
+        self.write("}")
     }
 
     fn visit_return(&mut self, stmt: &ReturnStatement) -> PrinterResult {
@@ -195,6 +286,18 @@ impl<'ast, T: io::Write> Visitor<'ast, io::Error> for Printer<T> {
         self.write("continue;")
     }
 
+    fn visit_assert(&mut self, stmt: &AssertStatement) -> PrinterResult {
+        self.write("assert(")?;
+        self.visit_expression(&stmt.condition)?;
+        self.write(");")
+    }
+
+    fn visit_defer(&mut self, expr: &Expression) -> PrinterResult {
+        self.write("defer ")?;
+        self.visit_expression(expr)?;
+        self.write(";\n")
+    }
+
     fn visit_binary_operation(&mut self, expr: &BinaryOperation) -> PrinterResult {
         self.visit_expression(&expr.left)?;
 
@@ -230,7 +333,12 @@ impl<'ast, T: io::Write> Visitor<'ast, io::Error> for Printer<T> {
             super::LiteralType::Integer(n) => self.write(&n.to_string()),
             super::LiteralType::Float(f) => self.write(&f.to_string()),
             super::LiteralType::Identifier(id) => self.write(id),
-            super::LiteralType::String(content) => self.write(&format!("\"{}\"", content)),
+            super::LiteralType::EnumVariant { enum_name, variant } => {
+                self.write(&format!("{enum_name}.{variant}"))
+            }
+            super::LiteralType::String(content) => {
+                self.write(&format!("\"{}\"", escape_string(content)))
+            }
             super::LiteralType::ArrayAccess(aa) => {
                 self.visit_expression(&aa.identifier)?;
                 self.write("[")?;
@@ -252,31 +360,75 @@ impl<'ast, T: io::Write> Visitor<'ast, io::Error> for Printer<T> {
         Ok(())
     }
 
+    fn visit_indirect_call(&mut self, expr: &IndirectCall) -> PrinterResult {
+        self.visit_expression(&expr.callee)?;
+        self.write("(")?;
+        for (i, arg) in expr.arguments.iter().enumerate() {
+            if i > 0 {
+                self.write(", ")?;
+            }
+            self.visit_expression(arg)?;
+        }
+        self.write(")")
+    }
+
     fn visit_type(&mut self, ty: &Type) -> PrinterResult {
-        match &ty.kind {
+        self.visit_type_kind(&ty.kind)
+    }
+
+    fn visit_type_kind(&mut self, kind: &TypeKind) -> PrinterResult {
+        match kind {
             TypeKind::U8 => self.write("u8"),
             TypeKind::U16 => self.write("u16"),
             TypeKind::U32 => self.write("u32"),
             TypeKind::U64 => self.write("u64"),
+            TypeKind::U128 => self.write("u128"),
             TypeKind::I8 => self.write("i8"),
             TypeKind::I16 => self.write("i16"),
             TypeKind::I32 => self.write("i32"),
             TypeKind::I64 => self.write("i64"),
+            TypeKind::I128 => self.write("i128"),
             TypeKind::Float => self.write("float"),
             TypeKind::String => self.write("string"),
             TypeKind::Bool => self.write("bool"),
             TypeKind::Null { .. } => self.write("Null"),
-            TypeKind::Identifier(id) => self.write(id),
+            TypeKind::Identifier(id) | TypeKind::Enum(id) => self.write(id),
             TypeKind::Void => self.write("<void>"),
             TypeKind::Array { size, array_type } => {
                 self.write("[")?;
+                self.visit_expression(&size.expr)?;
+                self.write("; ")?;
                 self.visit_type(array_type.as_ref())?;
-                self.write(&format!("; {}]", size))
+                self.write("]")
             }
             TypeKind::Ptr(pointee) => {
                 self.write("pointer of ")?;
                 self.visit_type(pointee.as_ref())
             } // void does not exists
+            TypeKind::Function {
+                parameters,
+                return_type,
+            } => {
+                self.write("function(")?;
+                for (i, parameter) in parameters.iter().enumerate() {
+                    if i > 0 {
+                        self.write(", ")?;
+                    }
+                    self.visit_type(parameter)?;
+                }
+                self.write("): ")?;
+                self.visit_type(return_type.as_ref())
+            }
+            TypeKind::Tuple(elements) => {
+                self.write("(")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        self.write(", ")?;
+                    }
+                    self.visit_type(element)?;
+                }
+                self.write(")")
+            }
         }
     }
 
@@ -285,4 +437,35 @@ impl<'ast, T: io::Write> Visitor<'ast, io::Error> for Printer<T> {
         self.write(" = ")?;
         self.visit_expression(&expr.right)
     }
+
+    fn visit_slice(&mut self, expr: &Slice) -> PrinterResult {
+        self.visit_expression(&expr.expr)?;
+        self.write("[")?;
+        self.visit_expression(&expr.start)?;
+        self.write("..")?;
+        self.visit_expression(&expr.end)?;
+        self.write("]")
+    }
+
+    fn visit_tuple(&mut self, expr: &Tuple) -> PrinterResult {
+        self.write("(")?;
+        for (i, value) in expr.values.iter().enumerate() {
+            if i > 0 {
+                self.write(", ")?;
+            }
+            self.visit_expression(value)?;
+        }
+        self.write(")")
+    }
+
+    fn visit_try_null(&mut self, expr: &TryNull) -> PrinterResult {
+        self.visit_expression(&expr.expr)?;
+        self.write("?")
+    }
+
+    fn visit_alignof(&mut self, expr: &AlignOf) -> PrinterResult {
+        self.write("alignof(")?;
+        self.visit_type(&expr.aligned_type)?;
+        self.write(")")
+    }
 }
@@ -1,27 +1,122 @@
+use std::fmt;
+
 use crate::type_system;
 
 use super::{
     expressions::Expression,
     impl_locatable,
     location::{Locatable, TokenLocation},
-    TypeKind,
+    Type, TypeKind,
 };
 
+/// Name of the parameter holding the receiver of a method
+pub const SELF_PARAMETER: &str = "self";
+
 #[derive(Debug, Clone)]
 pub enum GlobalStatement {
     Function(FunctionStatement),
     Struct(StructStatement),
+    Enum(EnumStatement),
     Let(LetStatement),
 }
 
 pub type FunctionParameter = (TypeKind, String);
 
+/// How arguments and return values are passed to a function, set with
+/// `extern "C"` or `extern "fast"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CallingConvention {
+    #[default]
+    C,
+    Fast,
+}
+
+impl CallingConvention {
+    pub fn from_abi(abi: &str) -> Option<Self> {
+        match abi {
+            "C" => Some(CallingConvention::C),
+            "fast" => Some(CallingConvention::Fast),
+            _ => None,
+        }
+    }
+
+    pub fn abi(&self) -> &'static str {
+        match self {
+            CallingConvention::C => "C",
+            CallingConvention::Fast => "fast",
+        }
+    }
+}
+
+/// Attributes understood by the compiler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeKind {
+    /// `@inline`: the function is always inlined
+    Inline,
+    /// `@noinline`: the function is never inlined
+    NoInline,
+    /// `@cfg(SYMBOL)`: the function is removed if `SYMBOL` isn't defined
+    Cfg,
+}
+
+/// An annotation written before a function definition, like `@inline` or
+/// `@cfg(DEBUG)`
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub name: String,
+    pub argument: Option<String>,
+    location: TokenLocation,
+}
+
+impl Attribute {
+    pub fn new(tk_begin: usize, tk_end: usize, name: String) -> Self {
+        Self {
+            name,
+            argument: None,
+            location: TokenLocation::new(tk_begin, tk_end),
+        }
+    }
+
+    pub fn with_argument(self, argument: String) -> Self {
+        Self {
+            argument: Some(argument),
+            ..self
+        }
+    }
+
+    /// Returns the attribute meaning or `None` if it's not known or doesn't
+    /// have the expected argument
+    pub fn kind(&self) -> Option<AttributeKind> {
+        match (self.name.as_str(), &self.argument) {
+            ("inline", None) => Some(AttributeKind::Inline),
+            ("noinline", None) => Some(AttributeKind::NoInline),
+            ("cfg", Some(_)) => Some(AttributeKind::Cfg),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.argument {
+            Some(argument) => write!(f, "{}({argument})", self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FunctionStatement {
     pub name: String,
     pub parameters: Vec<LetStatement>,
     pub return_type: TypeKind,
     pub is_extern: bool,
+    pub is_variadic: bool,
+    /// Exported functions keep their source name in the object file so they
+    /// can be called from other languages
+    pub is_exported: bool,
+    pub attributes: Vec<Attribute>,
+    pub calling_convention: CallingConvention,
     pub body: Option<Statements>,
     pub(crate) location: TokenLocation,
     pub(crate) ty: Option<type_system::Type>,
@@ -47,11 +142,107 @@ impl FunctionStatement {
                 .collect(),
             return_type,
             is_extern,
+            is_variadic: false,
+            is_exported: false,
+            attributes: Vec::new(),
+            calling_convention: CallingConvention::default(),
             body,
             location: TokenLocation::new(tk_begin, tk_end),
             ty: None,
         }
     }
+
+    /// Creates a method of the struct `struct_name`. Methods are functions
+    /// named `struct_name.name` taking a pointer to the struct as first
+    /// parameter, called `self`.
+    pub fn new_method(
+        tk_begin: usize,
+        tk_end: usize,
+        struct_name: String,
+        name: String,
+        mut parameters: Vec<FunctionParameter>,
+        return_type: TypeKind,
+        body: Statements,
+    ) -> Self {
+        let self_type = Type::new(tk_begin, tk_end, TypeKind::Identifier(struct_name.clone()));
+        parameters.insert(
+            0,
+            (
+                TypeKind::Ptr(Box::new(self_type)),
+                SELF_PARAMETER.to_string(),
+            ),
+        );
+
+        Self::new(
+            tk_begin,
+            tk_end,
+            Self::method_name(&struct_name, &name),
+            parameters,
+            return_type,
+            false,
+            Some(body),
+        )
+    }
+
+    /// Returns the name of the function implementing the method `method` of
+    /// the struct `struct_name`.
+    pub fn method_name(struct_name: &str, method: &str) -> String {
+        format!("{struct_name}.{method}")
+    }
+
+    /// Creates an extern function declaration accepting any number of
+    /// arguments after its fixed parameters, like C's `printf`.
+    pub fn new_variadic_extern(
+        tk_begin: usize,
+        tk_end: usize,
+        name: String,
+        parameters: Vec<FunctionParameter>,
+        return_type: TypeKind,
+    ) -> Self {
+        Self {
+            is_variadic: true,
+            ..Self::new(tk_begin, tk_end, name, parameters, return_type, true, None)
+        }
+    }
+
+    pub fn new_exported(
+        tk_begin: usize,
+        tk_end: usize,
+        name: String,
+        parameters: Vec<FunctionParameter>,
+        return_type: TypeKind,
+        body: Statements,
+    ) -> Self {
+        Self {
+            is_exported: true,
+            ..Self::new(
+                tk_begin,
+                tk_end,
+                name,
+                parameters,
+                return_type,
+                false,
+                Some(body),
+            )
+        }
+    }
+
+    pub fn with_attributes(self, attributes: Vec<Attribute>) -> Self {
+        Self { attributes, ..self }
+    }
+
+    pub fn with_calling_convention(self, calling_convention: CallingConvention) -> Self {
+        Self {
+            calling_convention,
+            ..self
+        }
+    }
+
+    pub fn has_attribute(&self, kind: AttributeKind) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| attribute.kind() == Some(kind))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -59,6 +250,10 @@ pub struct LetStatement {
     pub name: String,
     pub declaration_type: Option<TypeKind>,
     pub init_exp: Option<Box<Expression>>,
+    pub is_const: bool,
+    pub is_extern: bool,
+    /// Function local variable kept across calls
+    pub is_static: bool,
     location: TokenLocation,
     pub(crate) ty: Option<type_system::Type>,
 }
@@ -75,10 +270,111 @@ impl LetStatement {
             name,
             declaration_type,
             init_exp,
+            is_const: false,
+            is_extern: false,
+            is_static: false,
             location: TokenLocation::new(tk_begin, tk_end),
             ty: None,
         }
     }
+
+    /// Creates a compile-time constant declaration. Constants always have a
+    /// type hint and an initializer that must be constant-foldable.
+    pub fn new_const(
+        tk_begin: usize,
+        tk_end: usize,
+        name: String,
+        declaration_type: TypeKind,
+        init_exp: Box<Expression>,
+    ) -> Self {
+        Self {
+            is_const: true,
+            ..Self::new(
+                tk_begin,
+                tk_end,
+                name,
+                Some(declaration_type),
+                Some(init_exp),
+            )
+        }
+    }
+
+    /// Creates a function local variable initialized once and kept across
+    /// calls. Like constants, statics always have a type hint and a
+    /// constant-foldable initializer.
+    pub fn new_static(
+        tk_begin: usize,
+        tk_end: usize,
+        name: String,
+        declaration_type: TypeKind,
+        init_exp: Box<Expression>,
+    ) -> Self {
+        Self {
+            is_static: true,
+            ..Self::new(
+                tk_begin,
+                tk_end,
+                name,
+                Some(declaration_type),
+                Some(init_exp),
+            )
+        }
+    }
+
+    /// Creates a declaration of a global variable defined outside of the
+    /// module. Extern declarations have a type hint but no initializer.
+    pub fn new_extern(
+        tk_begin: usize,
+        tk_end: usize,
+        name: String,
+        declaration_type: TypeKind,
+    ) -> Self {
+        Self {
+            is_extern: true,
+            ..Self::new(tk_begin, tk_end, name, Some(declaration_type), None)
+        }
+    }
+}
+
+/// What a destructuring `let` takes apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DestructuringKind {
+    /// `let (a, b) = <init_exp>;`
+    Tuple,
+    /// `let [a, b] = <init_exp>;`
+    Array,
+}
+
+/// `let (a, b) = <init_exp>;` declares a variable for each element of a
+/// tuple, `let [a, b] = <init_exp>;` for each element of an array. The
+/// declarations have no initializer, their type is the type of the matching
+/// element.
+#[derive(Debug, Clone)]
+pub struct LetTupleStatement {
+    pub kind: DestructuringKind,
+    pub declarations: Vec<LetStatement>,
+    pub init_exp: Box<Expression>,
+    location: TokenLocation,
+}
+
+impl LetTupleStatement {
+    pub fn new(
+        tk_begin: usize,
+        tk_end: usize,
+        kind: DestructuringKind,
+        names: Vec<String>,
+        init_exp: Box<Expression>,
+    ) -> Self {
+        Self {
+            kind,
+            declarations: names
+                .into_iter()
+                .map(|name| LetStatement::new(tk_begin, tk_end, name, None, None))
+                .collect(),
+            init_exp,
+            location: TokenLocation::new(tk_begin, tk_end),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -122,6 +418,23 @@ impl ContinueStatement {
     }
 }
 
+/// `assert(<condition>);` aborts the program when its condition is false.
+/// It is desugared into an `if` statement before code generation.
+#[derive(Debug, Clone)]
+pub struct AssertStatement {
+    pub condition: Box<Expression>,
+    pub(crate) location: TokenLocation,
+}
+
+impl AssertStatement {
+    pub fn new(tk_begin: usize, tk_end: usize, condition: Box<Expression>) -> Self {
+        Self {
+            condition,
+            location: TokenLocation::new(tk_begin, tk_end),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct StructStatement {
     pub name: String,
@@ -146,6 +459,35 @@ impl StructStatement {
     }
 }
 
+/// A C-like enumeration. Variants are integers numbered from 0 in their
+/// declaration order.
+#[derive(Debug, Clone)]
+pub struct EnumStatement {
+    pub name: String,
+    pub variants: Vec<String>,
+    location: TokenLocation,
+    pub(crate) ty: Option<type_system::Type>,
+}
+
+impl EnumStatement {
+    pub fn new(tk_begin: usize, tk_end: usize, name: String, variants: Vec<String>) -> Self {
+        Self {
+            name,
+            variants,
+            location: TokenLocation::new(tk_begin, tk_end),
+            ty: None,
+        }
+    }
+
+    /// Integer value of the variant `variant`
+    pub fn discriminant(&self, variant: &str) -> Option<i64> {
+        self.variants
+            .iter()
+            .position(|name| name == variant)
+            .map(|position| position as i64)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Statements {
     pub statements: Vec<Statement>,
@@ -160,9 +502,15 @@ impl Statements {
         }
     }
 
-    pub fn append_statement(&mut self, stmt: Statement) {
+    /// Inserts `stmt` before every other statement. The parser builds
+    /// statements lists from their end.
+    pub fn prepend_statement(&mut self, stmt: Statement) {
         self.statements.insert(0, stmt);
     }
+
+    pub fn append_statement(&mut self, stmt: Statement) {
+        self.statements.push(stmt);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -184,12 +532,20 @@ impl Statement {
 pub enum StatementKind {
     If(IfStatement),
     Let(LetStatement),
+    LetTuple(LetTupleStatement),
     While(WhileStatement),
     For(ForStatement),
     Return(ReturnStatement),
     Break(BreakStatement),
     Continue(ContinueStatement),
-    Expression { expr: Box<Expression>, naked: bool },
+    Assert(AssertStatement),
+    /// `defer <expr>;` runs the expression when leaving the enclosing block.
+    /// It is desugared away before code generation.
+    Defer(Box<Expression>),
+    Expression {
+        expr: Box<Expression>,
+        naked: bool,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -221,6 +577,9 @@ impl IfStatement {
 pub struct WhileStatement {
     pub condition: Box<Expression>,
     pub body: Box<Statements>,
+    /// Executed when the loop ends because its condition is false, but not
+    /// when it is left with `break`.
+    pub else_clause: Option<Box<Statements>>,
     pub(crate) location: TokenLocation,
 }
 
@@ -230,10 +589,12 @@ impl WhileStatement {
         tk_end: usize,
         condition: Box<Expression>,
         body: Box<Statements>,
+        else_clause: Option<Box<Statements>>,
     ) -> Self {
         Self {
             condition,
             body,
+            else_clause,
             location: TokenLocation::new(tk_begin, tk_end),
         }
     }
@@ -269,12 +630,16 @@ impl ForStatement {
 }
 
 impl_locatable!(
+    AssertStatement,
+    Attribute,
     BreakStatement,
     ContinueStatement,
+    EnumStatement,
     ForStatement,
     FunctionStatement,
     IfStatement,
     LetStatement,
+    LetTupleStatement,
     ReturnStatement,
     Statement,
     Statements,
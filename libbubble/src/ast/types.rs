@@ -2,6 +2,7 @@ use super::{
     bindable::Definition,
     impl_locatable,
     location::{Locatable, TokenLocation},
+    Expression,
 };
 
 #[derive(Debug, Clone)]
@@ -33,18 +34,68 @@ pub enum TypeKind {
     U16,
     U32,
     U64,
+    U128,
     I8,
     I16,
     I32,
     I64,
+    I128,
     String,
     Bool,
     Float,
     Identifier(String),
-    Array { size: u32, array_type: Box<Type> },
+    /// Enums are written like structs, the binder turns identifiers naming
+    /// an enum into this kind.
+    Enum(String),
+    Array {
+        size: ArraySize,
+        array_type: Box<Type>,
+    },
     Ptr(Box<Type>),
+    /// `function(<parameters>): <return_type>`, mostly used behind a pointer
+    /// to declare function pointers.
+    Function {
+        parameters: Vec<Type>,
+        return_type: Box<Type>,
+    },
+    /// `(<type>, <type>, ...)`, at least two elements
+    Tuple(Vec<Type>),
     Void,
-    Null { concrete_type: Box<TypeKind> },
+    Null {
+        concrete_type: Box<TypeKind>,
+    },
 }
 
-impl_locatable!(Type);
+/// Size of an array type. The size is an expression that must be constant
+/// folded by the type checker before the type is used.
+#[derive(Debug, Clone)]
+pub struct ArraySize {
+    pub expr: Box<Expression>,
+    pub(crate) value: Option<u32>,
+    location: TokenLocation,
+}
+
+impl ArraySize {
+    pub fn new(tk_begin: usize, tk_end: usize, expr: Box<Expression>) -> Self {
+        Self {
+            expr,
+            value: None,
+            location: TokenLocation::new(tk_begin, tk_end),
+        }
+    }
+
+    /// Folded size of the array.
+    /// # Panics
+    /// This method panics if the size has not been folded yet.
+    pub fn value(&self) -> u32 {
+        self.value.expect("Array size is not folded!")
+    }
+}
+
+impl PartialEq for ArraySize {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl_locatable!(ArraySize, Type);
@@ -1,8 +1,9 @@
 use super::{
-    AddrOf, ArrayInitializer, Assignment, BinaryOperation, BreakStatement, Call, ContinueStatement,
-    Deref, Expression, ForStatement, FunctionStatement, GlobalStatement, IfStatement, LetStatement,
-    Literal, ReturnStatement, Statement, StatementKind, Statements, StructStatement, Type,
-    TypeKind, WhileStatement,
+    AddrOf, AlignOf, ArrayInitializer, AssertStatement, Assignment, BinaryOperation,
+    BreakStatement, Call, ContinueStatement, Deref, EnumStatement, Expression, ForStatement,
+    FunctionStatement, GlobalStatement, Group, IfStatement, IndirectCall, LetStatement,
+    LetTupleStatement, Literal, ReturnStatement, Slice, Statement, StatementKind, Statements,
+    StructStatement, TryNull, Tuple, Type, TypeKind, WhileStatement,
 };
 
 /// Default AST visitor
@@ -20,11 +21,14 @@ pub trait Visitor<'ast, E: std::error::Error> {
         match stmt {
             super::StatementKind::If(s) => self.visit_if(s),
             super::StatementKind::Let(s) => self.visit_let(s),
+            super::StatementKind::LetTuple(s) => self.visit_let_tuple(s),
             super::StatementKind::While(s) => self.visit_while(s),
             super::StatementKind::For(s) => self.visit_for(s),
             super::StatementKind::Return(s) => self.visit_return(s),
             super::StatementKind::Break(s) => self.visit_break(s),
             super::StatementKind::Continue(s) => self.visit_continue(s),
+            super::StatementKind::Assert(s) => self.visit_assert(s),
+            super::StatementKind::Defer(expr) => self.visit_defer(expr),
             super::StatementKind::Expression { expr, .. } => self.visit_expression(expr),
         }
     }
@@ -33,6 +37,7 @@ pub trait Visitor<'ast, E: std::error::Error> {
         match stmt {
             GlobalStatement::Function(f) => self.visit_function(f),
             GlobalStatement::Struct(s) => self.visit_struct(s),
+            GlobalStatement::Enum(e) => self.visit_enum(e),
             GlobalStatement::Let(l) => self.visit_let(l),
         }
     }
@@ -53,12 +58,28 @@ pub trait Visitor<'ast, E: std::error::Error> {
         Ok(())
     }
 
+    fn visit_enum(&mut self, _: &'ast EnumStatement) -> Result<(), E> {
+        Ok(())
+    }
+
     fn visit_let(&mut self, stmt: &'ast LetStatement) -> Result<(), E> {
         if let Some(dec_ty) = &stmt.declaration_type {
             self.visit_type_kind(dec_ty)?;
         }
 
-        self.visit_expression(stmt.init_exp.as_ref().expect("Let has no init type!"))?;
+        if let Some(init_exp) = &stmt.init_exp {
+            self.visit_expression(init_exp)?;
+        }
+        Ok(())
+    }
+
+    fn visit_let_tuple(&mut self, stmt: &'ast LetTupleStatement) -> Result<(), E> {
+        self.visit_expression(&stmt.init_exp)?;
+
+        for declaration in &stmt.declarations {
+            self.visit_let(declaration)?;
+        }
+
         Ok(())
     }
 
@@ -79,7 +100,13 @@ pub trait Visitor<'ast, E: std::error::Error> {
 
     fn visit_while(&mut self, stmt: &'ast WhileStatement) -> Result<(), E> {
         self.visit_expression(&stmt.condition)?;
-        self.visit_statements_vec(&stmt.body.statements)
+        self.visit_statements_vec(&stmt.body.statements)?;
+
+        if let Some(else_clause) = &stmt.else_clause {
+            self.visit_statements_vec(&else_clause.statements)?;
+        }
+
+        Ok(())
     }
 
     fn visit_for(&mut self, stmt: &'ast ForStatement) -> Result<(), E> {
@@ -108,16 +135,29 @@ pub trait Visitor<'ast, E: std::error::Error> {
         Ok(())
     }
 
+    fn visit_assert(&mut self, stmt: &'ast AssertStatement) -> Result<(), E> {
+        self.visit_expression(&stmt.condition)
+    }
+
+    fn visit_defer(&mut self, expr: &'ast Expression) -> Result<(), E> {
+        self.visit_expression(expr)
+    }
+
     fn visit_expression(&mut self, expr: &'ast Expression) -> Result<(), E> {
         match expr {
-            Expression::Group(g) => self.visit_expression(g),
+            Expression::Group(g) => self.visit_group(g),
             Expression::BinaryOperation(bo) => self.visit_binary_operation(bo),
             Expression::Literal(l) => self.visit_literal(l),
             Expression::Call(c) => self.visit_call(c),
+            Expression::IndirectCall(c) => self.visit_indirect_call(c),
             Expression::Assignment(a) => self.visit_assignment(a),
             Expression::ArrayInitializer(aa) => self.visit_array_initializer(aa),
             Expression::AddrOf(addrof) => self.visit_addrof(addrof),
             Expression::Deref(deref) => self.visit_deref(deref),
+            Expression::Slice(slice) => self.visit_slice(slice),
+            Expression::AlignOf(alignof) => self.visit_alignof(alignof),
+            Expression::Tuple(tuple) => self.visit_tuple(tuple),
+            Expression::TryNull(try_null) => self.visit_try_null(try_null),
         }
     }
 
@@ -142,6 +182,15 @@ pub trait Visitor<'ast, E: std::error::Error> {
         Ok(())
     }
 
+    fn visit_indirect_call(&mut self, expr: &'ast IndirectCall) -> Result<(), E> {
+        self.visit_expression(&expr.callee)?;
+        for expr in &expr.arguments {
+            self.visit_expression(expr)?;
+        }
+
+        Ok(())
+    }
+
     fn visit_type(&mut self, _: &'ast Type) -> Result<(), E> {
         Ok(())
     }
@@ -163,6 +212,10 @@ pub trait Visitor<'ast, E: std::error::Error> {
         Ok(())
     }
 
+    fn visit_group(&mut self, expr: &'ast Group) -> Result<(), E> {
+        self.visit_expression(&expr.expr)
+    }
+
     fn visit_addrof(&mut self, expr: &'ast AddrOf) -> Result<(), E> {
         self.visit_expression(&expr.expr)
     }
@@ -170,6 +223,29 @@ pub trait Visitor<'ast, E: std::error::Error> {
     fn visit_deref(&mut self, expr: &'ast Deref) -> Result<(), E> {
         self.visit_expression(&expr.expr)
     }
+
+    fn visit_slice(&mut self, expr: &'ast Slice) -> Result<(), E> {
+        self.visit_expression(&expr.expr)?;
+        self.visit_expression(&expr.start)?;
+        self.visit_expression(&expr.end)
+    }
+
+    fn visit_alignof(&mut self, expr: &'ast AlignOf) -> Result<(), E> {
+        self.visit_type(&expr.aligned_type)
+    }
+
+    fn visit_tuple(&mut self, expr: &'ast Tuple) -> Result<(), E> {
+        for value in &expr.values {
+            self.visit_expression(value)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_try_null(&mut self, expr: &'ast TryNull) -> Result<(), E> {
+        self.visit_expression(&expr.expr)?;
+        self.visit_statements_vec(&expr.deferred)
+    }
 }
 
 pub trait MutableVisitor<'ast, E: std::error::Error> {
@@ -177,6 +253,7 @@ pub trait MutableVisitor<'ast, E: std::error::Error> {
         match stmt {
             GlobalStatement::Function(f) => self.visit_function(f),
             GlobalStatement::Struct(s) => self.visit_struct(s),
+            GlobalStatement::Enum(e) => self.visit_enum(e),
             GlobalStatement::Let(l) => self.visit_let(l),
         }
     }
@@ -185,11 +262,14 @@ pub trait MutableVisitor<'ast, E: std::error::Error> {
         match stmt {
             super::StatementKind::If(s) => self.visit_if(s),
             super::StatementKind::Let(s) => self.visit_let(s),
+            super::StatementKind::LetTuple(s) => self.visit_let_tuple(s),
             super::StatementKind::While(s) => self.visit_while(s),
             super::StatementKind::For(s) => self.visit_for(s),
             super::StatementKind::Return(s) => self.visit_return(s),
             super::StatementKind::Break(s) => self.visit_break(s),
             super::StatementKind::Continue(s) => self.visit_continue(s),
+            super::StatementKind::Assert(s) => self.visit_assert(s),
+            super::StatementKind::Defer(expr) => self.visit_defer(expr),
             super::StatementKind::Expression { expr, .. } => self.visit_expression(expr),
         }
     }
@@ -217,12 +297,28 @@ pub trait MutableVisitor<'ast, E: std::error::Error> {
         Ok(())
     }
 
+    fn visit_enum(&mut self, _: &'ast mut EnumStatement) -> Result<(), E> {
+        Ok(())
+    }
+
     fn visit_let(&mut self, stmt: &'ast mut LetStatement) -> Result<(), E> {
         if let Some(dec_ty) = &mut stmt.declaration_type {
             self.visit_type_kind(dec_ty)?;
         }
 
-        self.visit_expression(stmt.init_exp.as_mut().expect("Let has no init type!"))?;
+        if let Some(init_exp) = &mut stmt.init_exp {
+            self.visit_expression(init_exp)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_let_tuple(&mut self, stmt: &'ast mut LetTupleStatement) -> Result<(), E> {
+        self.visit_expression(&mut stmt.init_exp)?;
+
+        for declaration in &mut stmt.declarations {
+            self.visit_let(declaration)?;
+        }
 
         Ok(())
     }
@@ -250,6 +346,10 @@ pub trait MutableVisitor<'ast, E: std::error::Error> {
 
         self.visit_statements_vec(&mut stmt.body.statements)?;
 
+        if let Some(else_clause) = &mut stmt.else_clause {
+            self.visit_statements_vec(&mut else_clause.statements)?;
+        }
+
         Ok(())
     }
 
@@ -279,16 +379,29 @@ pub trait MutableVisitor<'ast, E: std::error::Error> {
         Ok(())
     }
 
+    fn visit_assert(&mut self, stmt: &'ast mut AssertStatement) -> Result<(), E> {
+        self.visit_expression(&mut stmt.condition)
+    }
+
+    fn visit_defer(&mut self, expr: &'ast mut Expression) -> Result<(), E> {
+        self.visit_expression(expr)
+    }
+
     fn visit_expression(&mut self, expr: &'ast mut Expression) -> Result<(), E> {
         match expr {
-            Expression::Group(ref mut g) => self.visit_expression(g),
+            Expression::Group(ref mut g) => self.visit_group(g),
             Expression::BinaryOperation(ref mut bo) => self.visit_binary_operation(bo),
             Expression::Literal(ref mut l) => self.visit_literal(l),
             Expression::Call(ref mut c) => self.visit_call(c),
+            Expression::IndirectCall(c) => self.visit_indirect_call(c),
             Expression::Assignment(ref mut a) => self.visit_assignment(a),
             Expression::ArrayInitializer(aa) => self.visit_array_initializer(aa),
             Expression::AddrOf(addrof) => self.visit_addrof(addrof),
             Expression::Deref(deref) => self.visit_deref(deref),
+            Expression::Slice(slice) => self.visit_slice(slice),
+            Expression::AlignOf(alignof) => self.visit_alignof(alignof),
+            Expression::Tuple(tuple) => self.visit_tuple(tuple),
+            Expression::TryNull(try_null) => self.visit_try_null(try_null),
         }
     }
 
@@ -319,6 +432,15 @@ pub trait MutableVisitor<'ast, E: std::error::Error> {
         Ok(())
     }
 
+    fn visit_indirect_call(&mut self, expr: &'ast mut IndirectCall) -> Result<(), E> {
+        self.visit_expression(&mut expr.callee)?;
+        for expr in &mut expr.arguments {
+            self.visit_expression(expr)?;
+        }
+
+        Ok(())
+    }
+
     fn visit_type(&mut self, _: &'ast mut Type) -> Result<(), E> {
         Ok(())
     }
@@ -340,6 +462,10 @@ pub trait MutableVisitor<'ast, E: std::error::Error> {
         Ok(())
     }
 
+    fn visit_group(&mut self, expr: &'ast mut Group) -> Result<(), E> {
+        self.visit_expression(&mut expr.expr)
+    }
+
     fn visit_addrof(&mut self, expr: &'ast mut AddrOf) -> Result<(), E> {
         self.visit_expression(&mut expr.expr)
     }
@@ -347,4 +473,27 @@ pub trait MutableVisitor<'ast, E: std::error::Error> {
     fn visit_deref(&mut self, expr: &'ast mut Deref) -> Result<(), E> {
         self.visit_expression(&mut expr.expr)
     }
+
+    fn visit_slice(&mut self, expr: &'ast mut Slice) -> Result<(), E> {
+        self.visit_expression(&mut expr.expr)?;
+        self.visit_expression(&mut expr.start)?;
+        self.visit_expression(&mut expr.end)
+    }
+
+    fn visit_alignof(&mut self, expr: &'ast mut AlignOf) -> Result<(), E> {
+        self.visit_type(&mut expr.aligned_type)
+    }
+
+    fn visit_tuple(&mut self, expr: &'ast mut Tuple) -> Result<(), E> {
+        for value in &mut expr.values {
+            self.visit_expression(value)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_try_null(&mut self, expr: &'ast mut TryNull) -> Result<(), E> {
+        self.visit_expression(&mut expr.expr)?;
+        self.visit_statements_vec(&mut expr.deferred)
+    }
 }
@@ -0,0 +1,14 @@
+use std::path::Path;
+
+/// Directories holding the C runtime objects depending on the distribution
+const CRT_DIRECTORIES: [&str; 2] = ["/usr/lib64", "/usr/lib/x86_64-linux-gnu"];
+
+/// Directory holding the C runtime objects (`Scrt1.o`, `crti.o` and `crtn.o`)
+/// programs are linked with
+pub fn find_crt_directory() -> &'static Path {
+    CRT_DIRECTORIES
+        .into_iter()
+        .map(Path::new)
+        .find(|dir| dir.join("Scrt1.o").exists())
+        .unwrap_or(Path::new(CRT_DIRECTORIES[0]))
+}
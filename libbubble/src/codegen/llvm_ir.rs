@@ -1,10 +1,14 @@
 use inkwell::{
+    attributes::{Attribute, AttributeLoc},
+    basic_block::BasicBlock,
     builder::Builder,
     context::Context,
     module::{Linkage, Module},
+    targets::TargetData,
     types::{AnyTypeEnum, BasicMetadataTypeEnum, BasicType, BasicTypeEnum},
     values::{
-        AnyValue, AnyValueEnum, BasicMetadataValueEnum, BasicValueEnum, FunctionValue, PointerValue,
+        AggregateValue, AnyValue, AnyValueEnum, BasicMetadataValueEnum, BasicValueEnum,
+        FunctionValue, IntValue, PointerValue,
     },
     AddressSpace, FloatPredicate, IntPredicate,
 };
@@ -12,21 +16,47 @@ use std::{collections::HashMap, convert::Infallible};
 
 use crate::{
     ast::{
-        self, AddrOf, ArrayInitializer, Assignment, BinaryOperation, BreakStatement, Call,
-        Expression, ForStatement, FunctionStatement, GlobalStatement, IfStatement, LetStatement,
-        Literal, LiteralType, OpType, ReturnStatement, StructStatement, Visitor, WhileStatement,
+        self, AddrOf, AlignOf, ArrayInitializer, AssertStatement, Assignment, AttributeKind,
+        BinaryOperation, Bindable, BreakStatement, Builtin, Call, CallingConvention,
+        ContinueStatement, Definition, DestructuringKind, Expression, ForStatement,
+        FunctionStatement, GlobalStatement, IfStatement, IndirectCall, LetStatement,
+        LetTupleStatement, Literal, LiteralType, OpType, ReturnStatement, Slice, Statements,
+        StructStatement, TryNull, Tuple, Visitor, WhileStatement,
     },
     codegen::locals_collector::SymbolsMap,
-    type_system::{self, Typable, Type},
+    type_system::{self, utils::ScopedMap, Typable, Type},
 };
 
-use super::Collector;
+use super::{select::SelectAssignment, Collector};
+
+/// LLVM identifier of a calling convention
+fn llvm_calling_convention(calling_convention: CallingConvention) -> u32 {
+    match calling_convention {
+        CallingConvention::C => 0,
+        CallingConvention::Fast => 8,
+    }
+}
+
+/// Biggest struct returned in registers on x86-64. Bigger ones are written
+/// to a slot allocated by the caller.
+const MAX_REGISTER_RETURN_SIZE: u64 = 16;
+
+/// Hardening attributes added to every function definition
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HardeningOptions {
+    /// Protect the functions with a buffer overflow check (`sspstrong`)
+    pub stack_protector: bool,
+    /// Keep the frame pointer in every function
+    pub frame_pointer: bool,
+}
 
 pub fn build_module<'ctx>(
     context: &'ctx Context,
     module: &Module<'ctx>,
+    target_data: &TargetData,
     statements: &[GlobalStatement],
-    print_code: bool,
+    hardening: HardeningOptions,
+    print_ir: bool,
 ) {
     let mut frame_table = Collector::default();
     // Collect local variables and function parameters
@@ -34,23 +64,43 @@ pub fn build_module<'ctx>(
 
     let builder = context.create_builder();
 
-    let mut translator = Translator::new(context, builder, module, symbol_map);
+    let mut translator = Translator::new(
+        context,
+        builder,
+        module,
+        target_data,
+        symbol_map,
+        hardening,
+        print_ir,
+    );
     translator.translate_statements(statements).unwrap();
-
-    if print_code {
-        translator.print_code();
-    }
 }
 
 pub struct Translator<'ctx, 'ast, 'module> {
     context: &'ctx Context,
     builder: Builder<'ctx>,
     module: &'module Module<'ctx>,
+    /// Layout of the types on the target machine
+    target_data: &'module TargetData,
     frame_table: &'ast SymbolsMap<'ast>,
-    variables: HashMap<&'ast str, PointerValue<'ctx>>,
+    /// Variables visible from the current statement
+    variables: ScopedMap<PointerValue<'ctx>>,
+    /// Allocas of the current function locals
+    locals: HashMap<*const LetStatement, PointerValue<'ctx>>,
+    globals: HashMap<&'ast str, PointerValue<'ctx>>,
+    /// Hidden `sret` parameter of the current function if it returns a big
+    /// struct
+    return_slot: Option<PointerValue<'ctx>>,
     current_fn_value: Option<FunctionValue<'ctx>>,
     current_value: Option<AnyValueEnum<'ctx>>,
+    /// Blocks `break` statements jump to, one for each nested loop
+    loop_exits: Vec<BasicBlock<'ctx>>,
+    /// Blocks `continue` statements jump to, one for each nested loop
+    loop_continues: Vec<BasicBlock<'ctx>>,
     should_load: bool,
+    hardening: HardeningOptions,
+    /// Print the module IR once translated
+    print_ir: bool,
 }
 
 impl<'ctx, 'ast, 'module> Translator<'ctx, 'ast, 'module> {
@@ -58,17 +108,28 @@ impl<'ctx, 'ast, 'module> Translator<'ctx, 'ast, 'module> {
         context: &'ctx Context,
         builder: Builder<'ctx>,
         module: &'module Module<'ctx>,
+        target_data: &'module TargetData,
         frame_table: &'ast SymbolsMap<'ast>,
+        hardening: HardeningOptions,
+        print_ir: bool,
     ) -> Self {
         Self {
             context,
             builder,
             module,
+            target_data,
             frame_table,
-            variables: HashMap::new(),
+            variables: ScopedMap::default(),
+            locals: HashMap::new(),
+            globals: HashMap::new(),
+            return_slot: None,
             current_fn_value: None,
             current_value: None,
+            loop_exits: Vec::new(),
+            loop_continues: Vec::new(),
             should_load: true,
+            hardening,
+            print_ir,
         }
     }
 
@@ -76,18 +137,144 @@ impl<'ctx, 'ast, 'module> Translator<'ctx, 'ast, 'module> {
         &mut self,
         stmts: &'ast [GlobalStatement],
     ) -> Result<(), Infallible> {
+        // Declare every struct and function first so they may be used before
+        // their definition. Struct bodies are set once every struct is
+        // declared since fields may refer to structs declared later.
+        for stmt in stmts {
+            if let GlobalStatement::Struct(strct) = stmt {
+                self.context.opaque_struct_type(&strct.name);
+            }
+        }
+
+        for stmt in stmts {
+            if let GlobalStatement::Struct(strct) = stmt {
+                self.set_struct_body(strct);
+            }
+        }
+
+        for stmt in stmts {
+            if let GlobalStatement::Function(function) = stmt {
+                self.declare_function(function);
+            }
+        }
+
         for stmt in stmts {
             self.visit_global_statement(stmt)?;
         }
 
-        if let Err(msg) = self.module.verify() {
+        if self.print_ir {
             self.print_code();
+        }
+
+        if let Err(msg) = self.module.verify() {
             eprintln!("Failed to verify module!\n{}", msg.to_string());
         }
 
         Ok(())
     }
 
+    /// Adds the function prototype to the module if it's not declared yet.
+    fn declare_function(&self, stmt: &FunctionStatement) -> FunctionValue<'ctx> {
+        if let Some(fn_val) = self.module.get_function(&stmt.name) {
+            return fn_val;
+        }
+
+        let (return_type, parameters) = if let Type::Function {
+            return_type,
+            parameters,
+        } = stmt.ty.as_ref().expect("Function has no type")
+        {
+            (return_type, parameters)
+        } else {
+            panic!("Function type isn't a function type!")
+        };
+
+        let returns_through_slot = self.returns_through_slot(return_type);
+        let return_slot_type: Option<BasicMetadataTypeEnum<'ctx>> =
+            returns_through_slot.then(|| {
+                self.context
+                    .i8_type()
+                    .ptr_type(AddressSpace::default())
+                    .into()
+            });
+        let llvm_parameters_type: Vec<BasicMetadataTypeEnum<'ctx>> = return_slot_type
+            .into_iter()
+            .chain(parameters.iter().map(|(ty, _)| {
+                self.as_basic_type(self.to_llvm_type(ty))
+                    .as_basic_type_enum()
+                    .into()
+            }))
+            .collect();
+
+        let fn_ty = if *return_type.as_ref() != type_system::Type::Void && !returns_through_slot {
+            self.as_basic_type(self.to_llvm_type(return_type))
+                .fn_type(&llvm_parameters_type, stmt.is_variadic)
+        } else {
+            self.context
+                .void_type()
+                .fn_type(&llvm_parameters_type, stmt.is_variadic)
+        };
+
+        // Functions aren't mangled yet. Once they are, exported functions must
+        // still use their source name.
+        let fn_val = self.module.add_function(
+            &stmt.name,
+            fn_ty,
+            Some(if stmt.body.is_some() {
+                // We don't want external function to be exported
+                Linkage::External
+            } else {
+                Linkage::ExternalWeak
+            }),
+        );
+
+        fn_val.set_call_conventions(llvm_calling_convention(stmt.calling_convention));
+        if returns_through_slot {
+            fn_val.add_attribute(AttributeLoc::Param(0), self.sret_attribute(return_type));
+        }
+
+        fn_val
+    }
+
+    fn set_struct_body(&self, stmt: &StructStatement) {
+        let fields = if let Type::Struct { fields, .. } = stmt.get_type() {
+            fields
+        } else {
+            panic!("Struct type isn't a struct type!")
+        };
+
+        let fields_type: Vec<BasicTypeEnum<'ctx>> = fields
+            .iter()
+            .map(|(ty, _)| self.as_basic_type(self.to_llvm_type(ty)))
+            .collect();
+
+        // Structs without fields get an empty body and are zero sized
+        self.module
+            .get_struct_type(&stmt.name)
+            .expect("Struct not declared")
+            .set_body(&fields_type, false);
+    }
+
+    /// Size of a value of type `ty` in the target data layout, padding
+    /// included. Struct bodies must be set.
+    fn type_size(&self, ty: &Type) -> u64 {
+        self.target_data.get_abi_size(&self.to_llvm_type(ty))
+    }
+
+    /// Big structs are returned through a slot allocated by the caller and
+    /// passed as a hidden first parameter (the `sret` ABI).
+    fn returns_through_slot(&self, return_type: &Type) -> bool {
+        matches!(return_type, Type::Struct { .. })
+            && self.type_size(return_type) > MAX_REGISTER_RETURN_SIZE
+    }
+
+    fn sret_attribute(&self, return_type: &Type) -> Attribute {
+        self.context.create_type_attribute(
+            Attribute::get_named_enum_kind_id("sret"),
+            self.to_llvm_type(return_type),
+        )
+    }
+
     pub fn print_code(&self) {
         let content = self.module.print_to_string().to_string();
         println!(
@@ -129,8 +316,11 @@ impl<'ctx, 'ast, 'module> Translator<'ctx, 'ast, 'module> {
         match ty {
             type_system::Type::U8 | type_system::Type::I8 => self.context.i8_type().into(),
             type_system::Type::U16 | type_system::Type::I16 => self.context.i16_type().into(),
-            type_system::Type::U32 | type_system::Type::I32 => self.context.i32_type().into(),
+            type_system::Type::U32 | type_system::Type::I32 | type_system::Type::Enum { .. } => {
+                self.context.i32_type().into()
+            }
             type_system::Type::U64 | type_system::Type::I64 => self.context.i64_type().into(),
+            type_system::Type::U128 | type_system::Type::I128 => self.context.i128_type().into(),
             type_system::Type::Int => unreachable!(),
             type_system::Type::Float => self.context.f64_type().into(),
             type_system::Type::String => self
@@ -139,18 +329,24 @@ impl<'ctx, 'ast, 'module> Translator<'ctx, 'ast, 'module> {
                 .ptr_type(AddressSpace::default())
                 .into(),
             type_system::Type::Bool => self.context.bool_type().into(),
-            type_system::Type::Struct { .. } => todo!(),
+            type_system::Type::Struct { name, .. } => self
+                .module
+                .get_struct_type(name)
+                .expect("Struct not declared")
+                .into(),
             type_system::Type::Function {
                 parameters,
                 return_type,
             } => {
-                let ret = self.as_basic_type(self.to_llvm_type(return_type));
                 let param_ty: Vec<BasicMetadataTypeEnum> = parameters
                     .iter()
                     .map(|(kind, _)| self.as_basic_type(self.to_llvm_type(kind)).into())
                     .collect();
 
-                ret.fn_type(&param_ty, false).into()
+                match self.to_llvm_type(return_type) {
+                    AnyTypeEnum::VoidType(t) => t.fn_type(&param_ty, false).into(),
+                    ret => self.as_basic_type(ret).fn_type(&param_ty, false).into(),
+                }
             }
             type_system::Type::Void => self.context.void_type().into(),
             type_system::Type::Array { size, array_type } => {
@@ -166,11 +362,20 @@ impl<'ctx, 'ast, 'module> Translator<'ctx, 'ast, 'module> {
                     _ => unreachable!("Type couldn't be an array!"),
                 }
             }
+            // `void` has no pointer type, use `i8*` like C does
             type_system::Type::Ptr(pointee) if pointee.as_ref() == &Type::Void => self
                 .context
-                .i64_type()
+                .i8_type()
                 .ptr_type(AddressSpace::default())
                 .into(),
+            type_system::Type::Ptr(pointee)
+                if matches!(pointee.as_ref(), Type::Function { .. }) =>
+            {
+                self.to_llvm_type(pointee)
+                    .into_function_type()
+                    .ptr_type(AddressSpace::default())
+                    .into()
+            }
             type_system::Type::Ptr(pointee) => {
                 match self.as_basic_type(self.to_llvm_type(pointee)) {
                     BasicTypeEnum::ArrayType(t) => t.ptr_type(AddressSpace::from(0u16)).into(),
@@ -181,12 +386,346 @@ impl<'ctx, 'ast, 'module> Translator<'ctx, 'ast, 'module> {
                     BasicTypeEnum::VectorType(t) => t.ptr_type(AddressSpace::from(0u16)).into(),
                 }
             }
+            type_system::Type::Slice(base_type) => {
+                // A slice is a fat pointer: { base_type*, i64 }
+                let ptr_type = self.as_basic_type(self.to_llvm_type(&Type::Ptr(base_type.clone())));
+                self.context
+                    .struct_type(&[ptr_type, self.context.i64_type().into()], false)
+                    .into()
+            }
+            type_system::Type::Tuple(element_types) => {
+                // Tuples are anonymous structs, returning one returns every
+                // element at once
+                let element_types: Vec<BasicTypeEnum> = element_types
+                    .iter()
+                    .map(|ty| self.as_basic_type(self.to_llvm_type(ty)))
+                    .collect();
+                self.context.struct_type(&element_types, false).into()
+            }
             type_system::Type::Null { concrete_type } => {
                 self.to_llvm_type(concrete_type.as_ref().expect("Should have a concrete type"))
             }
         }
     }
 
+    /// Whether the block the builder is positioned in already has a
+    /// terminator, e.g. after a `return`
+    fn is_current_block_terminated(&self) -> bool {
+        self.builder
+            .get_insert_block()
+            .expect("Builder is not positioned")
+            .get_terminator()
+            .is_some()
+    }
+
+    fn build_branch_if_unterminated(&self, destination: BasicBlock<'ctx>) {
+        if !self.is_current_block_terminated() {
+            self.builder
+                .build_unconditional_branch(destination)
+                .expect("Fail to build unconditional branch");
+        }
+    }
+
+    fn translate_select_assignment(
+        &mut self,
+        condition: IntValue<'ctx>,
+        select: SelectAssignment<'ast>,
+    ) -> Result<(), Infallible> {
+        self.visit_expression(select.then_value)?;
+        let then_value = self.current_value.expect("Then value has no value");
+        self.visit_expression(select.else_value)?;
+        let else_value = self.current_value.expect("Else value has no value");
+
+        let selected = self
+            .builder
+            .build_select(
+                condition,
+                self.as_basic_value(then_value),
+                self.as_basic_value(else_value),
+                "if_select",
+            )
+            .expect("Fail to build select");
+
+        let variable_ptr = *self
+            .variables
+            .find_symbol(select.variable)
+            .expect("Variable does not exist!");
+        self.builder
+            .build_store(variable_ptr, selected)
+            .expect("Fail to build store");
+
+        Ok(())
+    }
+
+    /// Return a pointer to the first element of an array or a slice variable
+    fn get_elements_ptr(&self, name: &str, ty: &Type) -> PointerValue<'ctx> {
+        let variable_ptr = *self
+            .variables
+            .find_symbol(name)
+            .expect("Variable does not exist");
+
+        match ty {
+            Type::Slice(_) => {
+                let slice_value = self
+                    .builder
+                    .build_load(
+                        self.as_basic_type(self.to_llvm_type(ty)),
+                        variable_ptr,
+                        "slice_load",
+                    )
+                    .expect("Fail to build slice load")
+                    .into_struct_value();
+
+                self.builder
+                    .build_extract_value(slice_value, 0, "slice_ptr")
+                    .expect("Fail to build slice ptr extract")
+                    .into_pointer_value()
+            }
+            _ => variable_ptr,
+        }
+    }
+
+    fn translate_builtin_call(
+        &mut self,
+        builtin: Builtin,
+        expr: &'ast Call,
+    ) -> Result<(), Infallible> {
+        match builtin {
+            Builtin::Min | Builtin::Max => self.translate_min_max_call(builtin, expr),
+            Builtin::IsNull => self.translate_is_null_call(expr),
+            Builtin::Len => self.translate_len_call(expr),
+        }
+    }
+
+    /// `len` is a constant, the string literal isn't emitted.
+    fn translate_len_call(&mut self, expr: &'ast Call) -> Result<(), Infallible> {
+        let length = match &expr.arguments[0] {
+            Expression::Literal(Literal {
+                literal_type: LiteralType::String(content),
+                ..
+            }) => content.len(),
+            _ => unreachable!("len argument is not a string literal"),
+        };
+
+        self.current_value = Some(
+            self.context
+                .i64_type()
+                .const_int(length as u64, false)
+                .as_any_value_enum(),
+        );
+
+        Ok(())
+    }
+
+    /// `is_null` is lowered to a comparison of the pointer with null.
+    fn translate_is_null_call(&mut self, expr: &'ast Call) -> Result<(), Infallible> {
+        self.visit_expression(&expr.arguments[0])?;
+        let pointer = self
+            .current_value
+            .expect("Builtin argument has no value")
+            .into_pointer_value();
+
+        self.current_value = Some(
+            self.builder
+                .build_is_null(pointer, "is_null")
+                .expect("Fail to build is null")
+                .as_any_value_enum(),
+        );
+
+        Ok(())
+    }
+
+    /// `min` and `max` are lowered to a comparison and a select. The compare
+    /// instruction depends on the arguments type.
+    fn translate_min_max_call(
+        &mut self,
+        builtin: Builtin,
+        expr: &'ast Call,
+    ) -> Result<(), Infallible> {
+        self.visit_expression(&expr.arguments[0])?;
+        let left = self.current_value.expect("Builtin argument has no value");
+        self.visit_expression(&expr.arguments[1])?;
+        let right = self.current_value.expect("Builtin argument has no value");
+
+        let condition = match (left, right) {
+            (AnyValueEnum::IntValue(l), AnyValueEnum::IntValue(r)) => {
+                let predicate = match (builtin, expr.get_type().is_signed()) {
+                    (Builtin::Min, true) => IntPredicate::SLT,
+                    (Builtin::Min, false) => IntPredicate::ULT,
+                    (Builtin::Max, true) => IntPredicate::SGT,
+                    (Builtin::Max, false) => IntPredicate::UGT,
+                    (Builtin::IsNull | Builtin::Len, _) => {
+                        unreachable!("{} is not a min max builtin", builtin.name())
+                    }
+                };
+
+                self.builder
+                    .build_int_compare(predicate, l, r, builtin.name())
+                    .expect("Fail to build int compare")
+            }
+            (AnyValueEnum::FloatValue(l), AnyValueEnum::FloatValue(r)) => {
+                let predicate = match builtin {
+                    Builtin::Min => FloatPredicate::OLT,
+                    Builtin::Max => FloatPredicate::OGT,
+                    Builtin::IsNull | Builtin::Len => {
+                        unreachable!("{} is not a min max builtin", builtin.name())
+                    }
+                };
+
+                self.builder
+                    .build_float_compare(predicate, l, r, builtin.name())
+                    .expect("Fail to build float compare")
+            }
+            _ => unreachable!("Builtin arguments are not numbers"),
+        };
+
+        self.current_value = Some(
+            self.builder
+                .build_select(
+                    condition,
+                    self.as_basic_value(left),
+                    self.as_basic_value(right),
+                    builtin.name(),
+                )
+                .expect("Fail to build select")
+                .as_any_value_enum(),
+        );
+
+        Ok(())
+    }
+
+    /// Translate a slice bound and extend it to an i64
+    fn translate_slice_bound(
+        &mut self,
+        bound: &'ast Expression,
+    ) -> Result<IntValue<'ctx>, Infallible> {
+        self.visit_expression(bound)?;
+        let bound_value = self
+            .current_value
+            .expect("Slice bound has no value")
+            .into_int_value();
+
+        Ok(self
+            .builder
+            .build_int_cast_sign_flag(
+                bound_value,
+                self.context.i64_type(),
+                bound.get_type().is_signed(),
+                "slice_bound",
+            )
+            .expect("Fail to build slice bound cast"))
+    }
+
+    /// Return the signed integer predicate of a comparison operator
+    fn comparison_predicate(op: OpType) -> Option<IntPredicate> {
+        match op {
+            OpType::Equal => Some(IntPredicate::EQ),
+            OpType::Different => Some(IntPredicate::NE),
+            OpType::Less => Some(IntPredicate::SLT),
+            OpType::LessEqual => Some(IntPredicate::SLE),
+            OpType::More => Some(IntPredicate::SGT),
+            OpType::MoreEqual => Some(IntPredicate::SGE),
+            _ => None,
+        }
+    }
+
+    /// Compare two strings by calling the C library `strcmp` and comparing its
+    /// result to zero. `strcmp` is declared in the module if it's not already.
+    fn build_string_comparison(
+        &self,
+        predicate: IntPredicate,
+        left: PointerValue<'ctx>,
+        right: PointerValue<'ctx>,
+    ) -> IntValue<'ctx> {
+        let strcmp = self.module.get_function("strcmp").unwrap_or_else(|| {
+            let string_type = self.context.i8_type().ptr_type(AddressSpace::default());
+            let fn_type = self
+                .context
+                .i32_type()
+                .fn_type(&[string_type.into(), string_type.into()], false);
+
+            self.module
+                .add_function("strcmp", fn_type, Some(Linkage::External))
+        });
+
+        let cmp_result = self
+            .builder
+            .build_call(strcmp, &[left.into(), right.into()], "strcmp")
+            .expect("Fail to build strcmp call")
+            .try_as_basic_value()
+            .left()
+            .expect("strcmp has no return value")
+            .into_int_value();
+
+        self.builder
+            .build_int_compare(
+                predicate,
+                cmp_result,
+                self.context.i32_type().const_zero(),
+                "string_compare",
+            )
+            .expect("Fail to build int compare")
+    }
+
+    /// Extract the `count` first elements of an array or a struct value
+    fn extract_elements<V: AggregateValue<'ctx> + Copy>(
+        &self,
+        aggregate: V,
+        count: usize,
+        name: &str,
+    ) -> Vec<BasicValueEnum<'ctx>> {
+        (0..count as u32)
+            .map(|i| {
+                self.builder
+                    .build_extract_value(aggregate, i, name)
+                    .expect("Fail to build element extract")
+            })
+            .collect()
+    }
+
+    /// Implicit conversions between `ptr T` and `ptr void` only change the
+    /// pointee type, the pointer is bitcast to the expected pointer type.
+    fn cast_pointer(
+        &self,
+        value: BasicValueEnum<'ctx>,
+        expected_type: BasicTypeEnum<'ctx>,
+    ) -> BasicValueEnum<'ctx> {
+        match (value, expected_type) {
+            (BasicValueEnum::PointerValue(pointer), BasicTypeEnum::PointerType(pointer_type))
+                if pointer.get_type() != pointer_type =>
+            {
+                self.builder
+                    .build_pointer_cast(pointer, pointer_type, "pointer_cast")
+                    .expect("Fail to build pointer cast")
+                    .into()
+            }
+            _ => value,
+        }
+    }
+
+    /// Compare the addresses held by two pointers. They are converted to
+    /// integers first as their pointee types may differ, e.g. with `ptr void`.
+    fn build_pointer_comparison(
+        &self,
+        predicate: IntPredicate,
+        left: PointerValue<'ctx>,
+        right: PointerValue<'ctx>,
+    ) -> IntValue<'ctx> {
+        let address_type = self.context.i64_type();
+        let left_address = self
+            .builder
+            .build_ptr_to_int(left, address_type, "left_address")
+            .expect("Fail to build ptr to int");
+        let right_address = self
+            .builder
+            .build_ptr_to_int(right, address_type, "right_address")
+            .expect("Fail to build ptr to int");
+
+        self.builder
+            .build_int_compare(predicate, left_address, right_address, "pointer_compare")
+            .expect("Fail to build int compare")
+    }
+
     #[inline]
     fn get_fn_value(&self) -> &FunctionValue<'ctx> {
         self.current_fn_value
@@ -194,6 +733,47 @@ impl<'ctx, 'ast, 'module> Translator<'ctx, 'ast, 'module> {
             .expect("current_fn_value is None!")
     }
 
+    /// Translates `expr` without loading its value to get the address an
+    /// assignment stores to.
+    fn translate_lvalue_address(
+        &mut self,
+        expr: &'ast Expression,
+    ) -> Result<PointerValue<'ctx>, Infallible> {
+        self.should_load = false;
+        self.visit_expression(expr)?;
+        self.should_load = true;
+
+        match self.current_value.expect("Lvalue has no value") {
+            AnyValueEnum::PointerValue(address) => Ok(address),
+            _ => panic!("Lvalue address is not a pointer"),
+        }
+    }
+
+    /// Translate a call argument passed to a parameter of `parameter_type`,
+    /// `None` in the variadic part of a function. An array passed to a
+    /// pointer parameter decays to its address which is also the address of
+    /// its first element.
+    fn translate_argument(
+        &mut self,
+        arg: &'ast Expression,
+        parameter_type: Option<BasicTypeEnum<'ctx>>,
+    ) -> Result<BasicValueEnum<'ctx>, Infallible> {
+        let should_load = self.should_load;
+        if matches!(arg.get_type(), Type::Array { .. })
+            && matches!(parameter_type, Some(BasicTypeEnum::PointerType(_)))
+        {
+            self.should_load = false;
+        }
+        self.visit_expression(arg)?;
+        self.should_load = should_load;
+
+        let value = self.as_basic_value(self.current_value.unwrap());
+        Ok(match parameter_type {
+            Some(parameter_type) => self.cast_pointer(value, parameter_type),
+            None => value,
+        })
+    }
+
     fn create_entry_block_alloca<T: BasicType<'ctx>>(
         &self,
         name: &str,
@@ -217,45 +797,33 @@ impl<'ctx, 'ast, 'module> Translator<'ctx, 'ast, 'module> {
 }
 
 impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, 'module> {
+    fn visit_statements(&mut self, stmts: &'ast Statements) -> Result<(), Infallible> {
+        // Every block opens a new scope where locals may shadow outer ones
+        self.variables.new_scope();
+        for stmt in stmts.statements.iter() {
+            self.visit_statement_kind(&stmt.kind)?;
+
+            // Statements following a return are unreachable and can't be
+            // emitted after the block terminator. It happens with a return in
+            // a desugared for loop body followed by its modify expression.
+            if self.is_current_block_terminated() {
+                break;
+            }
+        }
+        self.variables.delete_scope();
+
+        Ok(())
+    }
+
     fn visit_function(&mut self, stmt: &'ast FunctionStatement) -> Result<(), Infallible> {
-        let (return_type, parameters) = if let Type::Function {
-            return_type,
-            parameters,
-        } = stmt.ty.as_ref().expect("Function has no type")
+        let return_type = if let Type::Function { return_type, .. } =
+            stmt.ty.as_ref().expect("Function has no type")
         {
-            (return_type, parameters)
+            return_type
         } else {
             panic!("Function type isn't a function type!")
         };
-
-        let llvm_parameters_type: Vec<BasicMetadataTypeEnum<'ctx>> = parameters
-            .iter()
-            .map(|(ty, _)| {
-                self.as_basic_type(self.to_llvm_type(ty))
-                    .as_basic_type_enum()
-                    .into()
-            })
-            .collect();
-
-        let fn_ty = if *return_type.as_ref() != type_system::Type::Void {
-            self.as_basic_type(self.to_llvm_type(return_type))
-                .fn_type(&llvm_parameters_type, false)
-        } else {
-            self.context
-                .void_type()
-                .fn_type(&llvm_parameters_type, false)
-        };
-
-        let fn_val = self.module.add_function(
-            &stmt.name,
-            fn_ty,
-            Some(if stmt.body.is_some() {
-                // We don't want external function to be exported
-                Linkage::External
-            } else {
-                Linkage::ExternalWeak
-            }),
-        );
+        let fn_val = self.declare_function(stmt);
 
         // Stop function generation here it's an extern declaration
         if stmt.body.is_none() {
@@ -263,21 +831,66 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
             return Ok(());
         }
 
+        for (kind, llvm_name) in [
+            (AttributeKind::Inline, "alwaysinline"),
+            (AttributeKind::NoInline, "noinline"),
+        ] {
+            if stmt.has_attribute(kind) {
+                let attribute = self
+                    .context
+                    .create_enum_attribute(Attribute::get_named_enum_kind_id(llvm_name), 0);
+                fn_val.add_attribute(AttributeLoc::Function, attribute);
+            }
+        }
+
+        if self.hardening.stack_protector {
+            let attribute = self
+                .context
+                .create_enum_attribute(Attribute::get_named_enum_kind_id("sspstrong"), 0);
+            fn_val.add_attribute(AttributeLoc::Function, attribute);
+        }
+        if self.hardening.frame_pointer {
+            let attribute = self.context.create_string_attribute("frame-pointer", "all");
+            fn_val.add_attribute(AttributeLoc::Function, attribute);
+        }
+
         self.current_fn_value = Some(fn_val);
         let entry = self.context.append_basic_block(fn_val, &stmt.name);
         self.builder.position_at_end(entry);
 
-        for (i, arg) in fn_val.get_param_iter().enumerate() {
+        // Globals are visible from every function unless shadowed by a local
+        self.variables = ScopedMap::default();
+        for (name, global) in self.globals.iter() {
+            self.variables.insert_symbol(name, *global);
+        }
+        self.variables.new_scope();
+
+        self.return_slot = if self.returns_through_slot(return_type) {
+            let slot = fn_val
+                .get_first_param()
+                .expect("Function has no return slot")
+                .into_pointer_value();
+            slot.set_name("return_slot");
+            Some(slot)
+        } else {
+            None
+        };
+
+        let parameters = fn_val
+            .get_param_iter()
+            .skip(self.return_slot.is_some() as usize);
+        for (i, arg) in parameters.enumerate() {
             let arg_name = &stmt.parameters[i].name;
             arg.set_name(&stmt.parameters[i].name);
             let alloca = self.create_entry_block_alloca(arg_name, arg.get_type());
             self.builder
                 .build_store(alloca, arg)
                 .expect("Fail to build store");
-            self.variables.insert(arg_name, alloca);
+            self.variables.insert_symbol(arg_name, alloca);
         }
 
-        // local variables allocas
+        // local variables allocas, they are only visible once declared
+        self.locals.clear();
         for stack_var in self
             .frame_table
             .get(stmt.name.as_str())
@@ -288,25 +901,99 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
                 stack_var.name,
                 self.as_basic_type(self.to_llvm_type(stack_var.kind)),
             );
-            self.variables.insert(stack_var.name, alloca);
+            self.locals.insert(stack_var.declaration, alloca);
         }
 
         self.visit_statements(stmt.body.as_ref().unwrap())?;
+
+        // The last block may have no terminator: a void function without a
+        // trailing return or the merge block of an if/else where both arms
+        // return.
+        if !self.is_current_block_terminated() {
+            if *return_type.as_ref() == type_system::Type::Void {
+                self.builder
+                    .build_return(None)
+                    .expect("Fail to build return");
+            } else {
+                self.builder
+                    .build_unreachable()
+                    .expect("Fail to build unreachable");
+            }
+        }
+
         self.current_fn_value = None;
+        self.return_slot = None;
 
         Ok(())
     }
 
-    fn visit_struct(&mut self, _: &'ast StructStatement) -> Result<(), Infallible> {
-        todo!("Implement struct!")
+    fn visit_struct(&mut self, _stmt: &'ast StructStatement) -> Result<(), Infallible> {
+        // Struct bodies are set before translating any function
+        Ok(())
     }
 
     fn visit_let(&mut self, stmt: &'ast LetStatement) -> Result<(), Infallible> {
-        self.visit_expression(
-            stmt.init_exp
-                .as_ref()
-                .expect("Let statement has no init exp"),
-        )?;
+        // Constants uses are replaced by their value during type checking
+        if stmt.is_const {
+            return Ok(());
+        }
+
+        // Extern variables are defined in an other object file, only declare them
+        if stmt.is_extern {
+            let global = self.module.add_global(
+                self.as_basic_type(self.to_llvm_type(stmt.get_type())),
+                None,
+                &stmt.name,
+            );
+            global.set_linkage(Linkage::External);
+            self.globals.insert(&stmt.name, global.as_pointer_value());
+
+            return Ok(());
+        }
+
+        // Statics are initialized once in a global named after their function
+        // so statics of different functions don't clash
+        if stmt.is_static {
+            let function_name = self
+                .current_fn_value
+                .expect("Static variable outside of a function")
+                .get_name()
+                .to_str()
+                .expect("Function name is not valid UTF-8")
+                .to_string();
+            let global_type = self.as_basic_type(self.to_llvm_type(stmt.get_type()));
+            let global = self.module.add_global(
+                global_type,
+                None,
+                &format!("{function_name}.{}", stmt.name),
+            );
+            global.set_linkage(Linkage::Internal);
+
+            // Initializers are constant literals: a string is the address of
+            // its global and `null` may need a cast to the pointer type
+            self.visit_expression(stmt.init_exp.as_ref().expect("Static has no init exp"))?;
+            let initializer = self.cast_pointer(
+                self.as_basic_value(self.current_value.expect("Static initializer has no value")),
+                global_type,
+            );
+            global.set_initializer(&initializer);
+            self.variables
+                .insert_symbol(&stmt.name, global.as_pointer_value());
+
+            return Ok(());
+        }
+
+        let store_value = *self
+            .locals
+            .get(&(stmt as *const LetStatement))
+            .expect("Variable does not exist!");
+
+        // Uninitialized variables only need their alloca
+        let Some(init_exp) = stmt.init_exp.as_ref() else {
+            self.variables.insert_symbol(&stmt.name, store_value);
+            return Ok(());
+        };
+        self.visit_expression(init_exp)?;
 
         if let Type::Array { array_type, .. } = stmt.get_type() {
             let Expression::ArrayInitializer(ArrayInitializer { values, .. }) = stmt
@@ -318,11 +1005,6 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
                 unreachable!("Array variable initializer is not an array initializer");
             };
 
-            let store_value = *self
-                .variables
-                .get(stmt.name.as_str())
-                .expect("Variable does not exist!");
-
             let pointee_type = self.to_llvm_type(array_type);
 
             for (i, exp) in values.iter().enumerate() {
@@ -339,30 +1021,82 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
                         .expect("Fail to build array init GEP")
                 };
 
+                // Functions have their own pointer type, e.g. in a dispatch table
+                let value = self.cast_pointer(
+                    self.as_basic_value(self.current_value.expect("Array expression has no value")),
+                    self.as_basic_type(pointee_type),
+                );
                 self.builder
-                    .build_store(
-                        ptr_offset,
-                        self.as_basic_value(
-                            self.current_value.expect("Array expression has no value"),
-                        ),
-                    )
+                    .build_store(ptr_offset, value)
                     .expect("Fail to build array init store");
             }
         } else {
-            let store_value = self
-                .variables
-                .get(stmt.name.as_str())
+            let value = self.cast_pointer(
+                self.as_basic_value(self.current_value.unwrap()),
+                self.as_basic_type(self.to_llvm_type(stmt.get_type())),
+            );
+            self.builder
+                .build_store(store_value, value)
+                .expect("Fail to build store");
+
+            self.current_value = Some(store_value.as_any_value_enum());
+        }
+
+        // Bind the name in the current scope, shadowing outer variables
+        self.variables.insert_symbol(&stmt.name, store_value);
+
+        Ok(())
+    }
+
+    fn visit_let_tuple(&mut self, stmt: &'ast LetTupleStatement) -> Result<(), Infallible> {
+        let count = stmt.declarations.len();
+        let elements = match (stmt.kind, stmt.init_exp.as_ref()) {
+            // An array initializer has no value, use its elements
+            (DestructuringKind::Array, Expression::ArrayInitializer(initializer)) => {
+                let mut elements = Vec::with_capacity(count);
+                for value in &initializer.values {
+                    self.visit_expression(value)?;
+                    let element = self.current_value.expect("Array element has no value");
+                    elements.push(self.as_basic_value(element));
+                }
+
+                elements
+            }
+            (DestructuringKind::Array, init_exp) => {
+                self.visit_expression(init_exp)?;
+                let array_value = self
+                    .current_value
+                    .expect("Array expression has no value")
+                    .into_array_value();
+
+                self.extract_elements(array_value, count, "array_element")
+            }
+            (DestructuringKind::Tuple, init_exp) => {
+                self.visit_expression(init_exp)?;
+                let tuple_value = self
+                    .current_value
+                    .expect("Tuple expression has no value")
+                    .into_struct_value();
+
+                self.extract_elements(tuple_value, count, "tuple_element")
+            }
+        };
+
+        for (declaration, element) in stmt.declarations.iter().zip(elements) {
+            let store_value = *self
+                .locals
+                .get(&(declaration as *const LetStatement))
                 .expect("Variable does not exist!");
 
+            let element = self.cast_pointer(
+                element,
+                self.as_basic_type(self.to_llvm_type(declaration.get_type())),
+            );
             self.builder
-                .build_store(
-                    *store_value,
-                    self.as_basic_value(self.current_value.unwrap()),
-                )
+                .build_store(store_value, element)
                 .expect("Fail to build store");
 
-            self.current_value = Some(store_value.as_any_value_enum());
-            self.variables.insert(&stmt.name, *store_value);
+            self.variables.insert_symbol(&declaration.name, store_value);
         }
 
         Ok(())
@@ -370,7 +1104,7 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
 
     fn visit_if(&mut self, stmt: &'ast IfStatement) -> Result<(), Infallible> {
         let parent = self.current_fn_value.unwrap();
-        let zero_const = self.context.i64_type().const_zero();
+        let zero_const = self.context.bool_type().const_zero();
 
         self.visit_expression(&stmt.condition)?;
         let condition = self
@@ -383,6 +1117,10 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
             )
             .expect("Fail to build int compare");
 
+        if let Some(select) = SelectAssignment::recognize(stmt) {
+            return self.translate_select_assignment(condition, select);
+        }
+
         let then_bb = self.context.append_basic_block(parent, "then");
         let else_bb = self.context.append_basic_block(parent, "else");
         let merge_bb = self.context.append_basic_block(parent, "merge");
@@ -393,18 +1131,14 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
 
         self.builder.position_at_end(then_bb);
         self.visit_statements(&stmt.then_clause)?;
-        self.builder
-            .build_unconditional_branch(merge_bb)
-            .expect("Fail to build unconditional branch");
+        self.build_branch_if_unterminated(merge_bb);
 
         self.builder.position_at_end(else_bb);
         if let Some(ref stmts) = stmt.else_clause {
             self.visit_statements(stmts)?;
         }
 
-        self.builder
-            .build_unconditional_branch(merge_bb)
-            .expect("Fail to build unconditional branch");
+        self.build_branch_if_unterminated(merge_bb);
         self.builder.position_at_end(merge_bb);
         Ok(())
     }
@@ -415,6 +1149,11 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
 
         let condition_block = self.context.append_basic_block(parent, "while_test");
         let while_block = self.context.append_basic_block(parent, "while_body");
+        // The else clause is only reached when the condition is false
+        let else_block = stmt
+            .else_clause
+            .as_ref()
+            .map(|_| self.context.append_basic_block(parent, "while_else"));
         let after_while_block = self.context.append_basic_block(parent, "after_while");
 
         self.builder
@@ -433,14 +1172,26 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
             )
             .expect("Fail to build int compare");
         self.builder
-            .build_conditional_branch(condition, while_block, after_while_block)
+            .build_conditional_branch(
+                condition,
+                while_block,
+                else_block.unwrap_or(after_while_block),
+            )
             .expect("Fail to build unconditional branch");
 
         self.builder.position_at_end(while_block);
+        self.loop_exits.push(after_while_block);
+        self.loop_continues.push(condition_block);
         self.visit_statements(&stmt.body)?;
-        self.builder
-            .build_unconditional_branch(condition_block) // Loop
-            .expect("Fail to build unconditional branch");
+        self.loop_continues.pop();
+        self.loop_exits.pop();
+        self.build_branch_if_unterminated(condition_block); // Loop
+
+        if let (Some(else_block), Some(else_clause)) = (else_block, &stmt.else_clause) {
+            self.builder.position_at_end(else_block);
+            self.visit_statements(else_clause)?;
+            self.build_branch_if_unterminated(after_while_block);
+        }
 
         self.builder.position_at_end(after_while_block);
 
@@ -451,15 +1202,37 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
         unreachable!("for desugar")
     }
 
+    fn visit_assert(&mut self, _stmt: &'ast AssertStatement) -> Result<(), Infallible> {
+        unreachable!("assert desugar")
+    }
+
+    fn visit_defer(&mut self, _expr: &'ast Expression) -> Result<(), Infallible> {
+        unreachable!("defer desugar")
+    }
+
     fn visit_return(&mut self, stmt: &'ast ReturnStatement) -> Result<(), Infallible> {
         if let Some(ref exp) = stmt.exp {
             self.visit_expression(exp)?;
+            let mut value = self.as_basic_value(*self.current_value.as_ref().unwrap());
+            if let Some(return_type) = self.get_fn_value().get_type().get_return_type() {
+                value = self.cast_pointer(value, return_type);
+            }
 
-            self.builder
-                .build_return(Some(
-                    &self.as_basic_value(*self.current_value.as_ref().unwrap()),
-                ))
-                .expect("Fail to build return");
+            match self.return_slot {
+                Some(slot) => {
+                    self.builder
+                        .build_store(slot, value)
+                        .expect("Fail to build store to return slot");
+                    self.builder
+                        .build_return(None)
+                        .expect("Fail to build return");
+                }
+                None => {
+                    self.builder
+                        .build_return(Some(&value))
+                        .expect("Fail to build return");
+                }
+            }
         } else {
             self.builder
                 .build_return(None)
@@ -470,7 +1243,35 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
     }
 
     fn visit_break(&mut self, _stmt: &'ast BreakStatement) -> Result<(), Infallible> {
-        todo!("implement break")
+        let loop_exit = *self.loop_exits.last().expect("break outside of a loop");
+        self.builder
+            .build_unconditional_branch(loop_exit)
+            .expect("Fail to build unconditional branch");
+
+        // Statements following the break are unreachable but still need a
+        // block to be translated in
+        let parent = self.current_fn_value.unwrap();
+        let unreachable_block = self.context.append_basic_block(parent, "after_break");
+        self.builder.position_at_end(unreachable_block);
+
+        Ok(())
+    }
+
+    fn visit_continue(&mut self, _stmt: &'ast ContinueStatement) -> Result<(), Infallible> {
+        let loop_condition = *self
+            .loop_continues
+            .last()
+            .expect("continue outside of a loop");
+        self.builder
+            .build_unconditional_branch(loop_condition)
+            .expect("Fail to build unconditional branch");
+
+        // Like after a break, the following statements are unreachable
+        let parent = self.current_fn_value.unwrap();
+        let unreachable_block = self.context.append_basic_block(parent, "after_continue");
+        self.builder.position_at_end(unreachable_block);
+
+        Ok(())
     }
 
     fn visit_binary_operation(&mut self, expr: &'ast BinaryOperation) -> Result<(), Infallible> {
@@ -507,6 +1308,30 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
         self.visit_expression(expr.right.as_ref().unwrap())?;
         let right = self.current_value.unwrap();
 
+        if *expr.left.get_type() == Type::String {
+            if let Some(predicate) = Self::comparison_predicate(expr.op) {
+                self.current_value = Some(
+                    self.build_string_comparison(
+                        predicate,
+                        left.into_pointer_value(),
+                        right.into_pointer_value(),
+                    )
+                    .into(),
+                );
+
+                return Ok(());
+            }
+        }
+
+        // The type checker only allows `==` and `!=` between pointers
+        if let (AnyValueEnum::PointerValue(v1), AnyValueEnum::PointerValue(v2)) = (left, right) {
+            let predicate =
+                Self::comparison_predicate(expr.op).expect("Pointers can only be compared");
+            self.current_value = Some(self.build_pointer_comparison(predicate, v1, v2).into());
+
+            return Ok(());
+        }
+
         let value: AnyValueEnum = match expr.op {
             OpType::And => match (left, right) {
                 (AnyValueEnum::IntValue(v1), AnyValueEnum::IntValue(v2)) => self
@@ -522,6 +1347,11 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
                     .build_int_compare(IntPredicate::NE, v1, v2, "!=")
                     .expect("Fail to build int compare")
                     .into(),
+                (AnyValueEnum::FloatValue(v1), AnyValueEnum::FloatValue(v2)) => self
+                    .builder
+                    .build_float_compare(FloatPredicate::ONE, v1, v2, "different_float")
+                    .expect("Fail to build float compare")
+                    .into(),
                 _ => unreachable!(),
             },
             OpType::Divide => match (left, right) {
@@ -537,6 +1367,7 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
                     .into(),
                 _ => unreachable!(),
             },
+            // Booleans are `i1` integers, they are compared like any integer
             OpType::Equal => match (left, right) {
                 (AnyValueEnum::IntValue(v1), AnyValueEnum::IntValue(v2)) => self
                     .builder
@@ -684,21 +1515,41 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
                     .as_ref()
                     .expect("integer like literal must have type!");
 
+                // Little endian words of the two's complement value, LLVM
+                // truncates them to the width of the type
+                let words = [*x as u64, (*x >> 64) as u64];
                 self.current_value = Some(
                     self.to_llvm_type(int_ty)
                         .into_int_type()
-                        .const_int(*x as u64, int_ty.is_signed())
+                        .const_int_arbitrary_precision(&words)
                         .into(),
                 );
             }
             LiteralType::Float(x) => {
                 self.current_value = Some(self.context.f64_type().const_float(*x).into())
             }
+            LiteralType::EnumVariant { variant, .. } => {
+                let discriminant = stmt
+                    .get_enum_def()
+                    .discriminant(variant)
+                    .expect("Enum variant not declared");
+
+                self.current_value = Some(
+                    self.context
+                        .i32_type()
+                        .const_int(discriminant as u64, false)
+                        .into(),
+                );
+            }
+            // A function used as a value is its address
+            LiteralType::Identifier(id)
+                if matches!(stmt.definition, Some(Definition::Function(_))) =>
+            {
+                let function = self.module.get_function(id).expect("Function not found");
+                self.current_value = Some(function.as_global_value().as_pointer_value().into());
+            }
             LiteralType::Identifier(id) => {
-                let ptr = self
-                    .variables
-                    .get(id.as_str())
-                    .expect("variable not found!");
+                let ptr = self.variables.find_symbol(id).expect("variable not found!");
 
                 self.current_value = Some(if self.should_load {
                     self.builder
@@ -729,13 +1580,12 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
                 // If it's a literal we can't visit the expression because we need
                 // a pointer like type. Visiting the expression would give us the pointee value
                 let ptr_value = match array_access.identifier.as_ref() {
-                    Expression::Literal(Literal {
-                        literal_type: LiteralType::Identifier(name),
-                        ..
-                    }) => *self
-                        .variables
-                        .get(name.as_str())
-                        .expect("Variable does not exist"),
+                    Expression::Literal(
+                        literal @ Literal {
+                            literal_type: LiteralType::Identifier(name),
+                            ..
+                        },
+                    ) => self.get_elements_ptr(name, literal.get_type()),
                     _ => {
                         self.visit_expression(&array_access.identifier)?;
                         self.current_value
@@ -745,13 +1595,28 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
                     }
                 };
 
-                // Translate and store index expression
+                // Translate and store index expression. The index is always
+                // loaded even if we only want the element address. It is
+                // cast to the pointer sized integer type of the target.
+                let should_load = self.should_load;
+                self.should_load = true;
                 self.visit_expression(&array_access.index)?;
+                self.should_load = should_load;
                 let index_value = self
                     .current_value
                     .as_ref()
                     .expect("Array access index has no value")
                     .into_int_value();
+                let index_value = self
+                    .builder
+                    .build_int_cast_sign_flag(
+                        index_value,
+                        self.target_data
+                            .ptr_sized_int_type_in_context(self.context, None),
+                        array_access.index.get_type().is_signed(),
+                        "array_index",
+                    )
+                    .expect("Fail to build array index cast");
 
                 // Compute offset with getelementptr
                 let load_ptr_value = unsafe {
@@ -765,13 +1630,15 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
                         .expect("Fail to build gep for array access")
                 };
 
-                // Load it as usual
-                self.current_value = Some(
+                // `addrof arr[i]` wants the element pointer, otherwise load it as usual
+                self.current_value = Some(if self.should_load {
                     self.builder
                         .build_load(pointee_ty, load_ptr_value, "array_acess_load")
                         .expect("Fail to build array access load")
-                        .into(),
-                );
+                        .into()
+                } else {
+                    load_ptr_value.into()
+                });
             }
             LiteralType::Null(n) => {
                 let llvm_ty = self.to_llvm_type(n.get_type()).into_pointer_type();
@@ -783,6 +1650,11 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
     }
 
     fn visit_call(&mut self, expr: &'ast Call) -> Result<(), Infallible> {
+        // Builtins have no LLVM function to call, they are translated in place
+        if let Some(Definition::Builtin(builtin)) = expr.definition {
+            return self.translate_builtin_call(builtin, expr);
+        }
+
         let mut parameters_values: Vec<BasicMetadataValueEnum<'_>> =
             Vec::with_capacity(expr.arguments.len());
         let fn_value = self
@@ -790,69 +1662,276 @@ impl<'ast, 'ctx, 'module> Visitor<'ast, Infallible> for Translator<'ctx, 'ast, '
             .get_function(&expr.callee)
             .expect("Function not found");
 
+        let return_slot = if self.returns_through_slot(expr.get_type()) {
+            let slot = self.create_entry_block_alloca(
+                "return_slot",
+                self.as_basic_type(self.to_llvm_type(expr.get_type())),
+            );
+            parameters_values.push(slot.into());
+            Some(slot)
+        } else {
+            None
+        };
+
         for arg in &expr.arguments {
-            self.visit_expression(arg)?;
-            parameters_values.push(self.as_basic_value(self.current_value.unwrap()).into());
+            // Arguments passed to the variadic part of a function have no
+            // parameter
+            let parameter_type = fn_value
+                .get_nth_param(parameters_values.len() as u32)
+                .map(|parameter| parameter.get_type());
+            let value = self.translate_argument(arg, parameter_type)?;
+            parameters_values.push(value.into());
         }
 
-        self.current_value = Some(
-            self.builder
-                .build_call(fn_value, &parameters_values, "call")
-                .expect("Fail to build call")
-                .as_any_value_enum(),
-        );
+        let call = self
+            .builder
+            .build_call(fn_value, &parameters_values, "call")
+            .expect("Fail to build call");
+        // The caller and the callee must agree on the calling convention
+        call.set_call_convention(fn_value.get_call_conventions());
+
+        self.current_value = Some(match return_slot {
+            Some(slot) => {
+                call.add_attribute(AttributeLoc::Param(0), self.sret_attribute(expr.get_type()));
+                self.builder
+                    .build_load(
+                        self.as_basic_type(self.to_llvm_type(expr.get_type())),
+                        slot,
+                        "call_result",
+                    )
+                    .expect("Fail to load call result")
+                    .as_any_value_enum()
+            }
+            None => call.as_any_value_enum(),
+        });
+
+        Ok(())
+    }
+
+    fn visit_indirect_call(&mut self, expr: &'ast IndirectCall) -> Result<(), Infallible> {
+        let Type::Ptr(function_type) = expr.callee.get_type() else {
+            unreachable!("Indirect call callee is not a function pointer");
+        };
+        let function_type = self.to_llvm_type(function_type).into_function_type();
+
+        // The function pointer is always loaded, even in `addrof table[i]()`
+        let should_load = self.should_load;
+        self.should_load = true;
+        self.visit_expression(&expr.callee)?;
+        let function_pointer = self
+            .current_value
+            .expect("Indirect call callee has no value")
+            .into_pointer_value();
+
+        let mut arguments: Vec<BasicMetadataValueEnum<'_>> =
+            Vec::with_capacity(expr.arguments.len());
+        for (arg, parameter_type) in expr.arguments.iter().zip(function_type.get_param_types()) {
+            let value = self.translate_argument(arg, Some(parameter_type))?;
+            arguments.push(value.into());
+        }
+        self.should_load = should_load;
+
+        let call = self
+            .builder
+            .build_indirect_call(function_type, function_pointer, &arguments, "indirect_call")
+            .expect("Fail to build indirect call");
+        self.current_value = Some(call.as_any_value_enum());
 
         Ok(())
     }
 
     fn visit_assignment(&mut self, expr: &'ast Assignment) -> Result<(), Infallible> {
         self.visit_expression(&expr.right)?;
-        let rhs = self.current_value.unwrap();
+        let rhs = self.cast_pointer(
+            self.as_basic_value(self.current_value.unwrap()),
+            self.as_basic_type(self.to_llvm_type(expr.left.get_type())),
+        );
 
-        // TODO: Add an `as_lvalue` method for `Literal` to make it cleaner
         let lhs = match expr.left.as_ref() {
-            Expression::Literal(l) => self
-                .variables
-                .get(match &l.literal_type {
-                    LiteralType::Identifier(id) => id.as_str(),
-                    _ => unreachable!(),
-                })
-                .expect("Undeclared variable!"),
-            Expression::Deref(deref) => {
-                self.visit_expression(&deref.expr)?;
-                match self.current_value.as_ref().expect("Deref has no value") {
-                    AnyValueEnum::PointerValue(v) => v,
-                    _ => panic!("Deref has a non pointer type"),
-                }
-            }
-            _ => unreachable!(),
+            Expression::Literal(literal) => match literal.as_lvalue() {
+                Some(name) => *self
+                    .variables
+                    .find_symbol(name)
+                    .expect("Undeclared variable!"),
+                // Like `addrof arr[i]`, an array access lvalue is the element address
+                None => self.translate_lvalue_address(&expr.left)?,
+            },
+            // Like `addrof deref p`, a deref lvalue is the pointer itself
+            Expression::Deref(_) => self.translate_lvalue_address(&expr.left)?,
+            _ => unreachable!("Left hand side is not a lvalue"),
         };
 
         self.builder
-            .build_store(*lhs, self.as_basic_value(rhs))
+            .build_store(lhs, rhs)
             .expect("Fail to build store");
 
+        // The assigned value is the value of the assignment: `a = b = 0`
+        self.current_value = Some(rhs.as_any_value_enum());
+
         Ok(())
     }
 
     fn visit_addrof(&mut self, expr: &'ast AddrOf) -> Result<(), Infallible> {
         // Here is the hack: addrof just means that we want the pointer so
         // we don't want to load the value from inside the llvm value
+        let should_load = self.should_load;
         self.should_load = false;
         self.visit_expression(&expr.expr)?;
-        self.should_load = true;
+        self.should_load = should_load;
+
+        Ok(())
+    }
+
+    fn visit_alignof(&mut self, expr: &'ast AlignOf) -> Result<(), Infallible> {
+        let aligned_type: Type = expr.aligned_type.kind.clone().into();
+        let alignment = self
+            .target_data
+            .get_abi_alignment(&self.to_llvm_type(&aligned_type));
+
+        self.current_value = Some(
+            self.context
+                .i64_type()
+                .const_int(alignment as u64, false)
+                .into(),
+        );
+
+        Ok(())
+    }
+
+    fn visit_slice(&mut self, expr: &'ast Slice) -> Result<(), Infallible> {
+        let base_type =
+            self.as_basic_type(self.to_llvm_type(expr.get_type().get_base_array_type()));
+
+        let base_ptr = match expr.expr.as_ref() {
+            Expression::Literal(
+                literal @ Literal {
+                    literal_type: LiteralType::Identifier(name),
+                    ..
+                },
+            ) => self.get_elements_ptr(name, literal.get_type()),
+            _ => {
+                self.visit_expression(&expr.expr)?;
+                match self.current_value.expect("Sliced expression has no value") {
+                    AnyValueEnum::StructValue(slice_value) => self
+                        .builder
+                        .build_extract_value(slice_value, 0, "slice_ptr")
+                        .expect("Fail to build slice ptr extract")
+                        .into_pointer_value(),
+                    value => value.into_pointer_value(),
+                }
+            }
+        };
+
+        let start = self.translate_slice_bound(&expr.start)?;
+        let end = self.translate_slice_bound(&expr.end)?;
+
+        let slice_ptr = unsafe {
+            self.builder
+                .build_gep(base_type, base_ptr, &[start], "slice_begin")
+                .expect("Fail to build gep for slice")
+        };
+        let slice_len = self
+            .builder
+            .build_int_sub(end, start, "slice_len")
+            .expect("Fail to build slice length");
+
+        let slice_type = self.to_llvm_type(expr.get_type()).into_struct_type();
+        let slice_value = self
+            .builder
+            .build_insert_value(slice_type.get_undef(), slice_ptr, 0, "slice_insert_ptr")
+            .expect("Fail to build slice ptr insert");
+        let slice_value = self
+            .builder
+            .build_insert_value(slice_value, slice_len, 1, "slice_insert_len")
+            .expect("Fail to build slice len insert");
+
+        self.current_value = Some(slice_value.into_struct_value().into());
+
+        Ok(())
+    }
+
+    fn visit_tuple(&mut self, expr: &'ast Tuple) -> Result<(), Infallible> {
+        let tuple_type = self.to_llvm_type(expr.get_type()).into_struct_type();
+        let mut tuple_value = tuple_type.get_undef();
+
+        for (i, value) in expr.values.iter().enumerate() {
+            self.visit_expression(value)?;
+            let element = self.cast_pointer(
+                self.as_basic_value(self.current_value.expect("Tuple element has no value")),
+                tuple_type
+                    .get_field_type_at_index(i as u32)
+                    .expect("Tuple element has no type"),
+            );
+
+            tuple_value = self
+                .builder
+                .build_insert_value(tuple_value, element, i as u32, "tuple_insert")
+                .expect("Fail to build tuple insert")
+                .into_struct_value();
+        }
+
+        self.current_value = Some(tuple_value.into());
+
+        Ok(())
+    }
+
+    /// A null pointer runs the deferred statements and returns null, the
+    /// translation goes on in a block where the pointer is not null.
+    fn visit_try_null(&mut self, expr: &'ast TryNull) -> Result<(), Infallible> {
+        let parent = self.current_fn_value.unwrap();
+
+        self.visit_expression(&expr.expr)?;
+        let pointer = self
+            .current_value
+            .expect("Pointer has no value")
+            .into_pointer_value();
+        let is_null = self
+            .builder
+            .build_is_null(pointer, "is_null")
+            .expect("Fail to build is null");
+
+        let null_block = self.context.append_basic_block(parent, "try_null_return");
+        let not_null_block = self.context.append_basic_block(parent, "try_null_continue");
+        self.builder
+            .build_conditional_branch(is_null, null_block, not_null_block)
+            .expect("Fail to build conditional branch");
+
+        self.builder.position_at_end(null_block);
+        self.visit_statements_vec(&expr.deferred)?;
+        let return_type = parent
+            .get_type()
+            .get_return_type()
+            .expect("Function doesn't return a pointer")
+            .into_pointer_type();
+        self.builder
+            .build_return(Some(&return_type.const_null()))
+            .expect("Fail to build return");
+
+        self.builder.position_at_end(not_null_block);
+        self.current_value = Some(pointer.into());
 
         Ok(())
     }
 
     fn visit_deref(&mut self, expr: &'ast ast::Deref) -> Result<(), Infallible> {
+        // The dereferenced pointer is always loaded even under an `addrof`
+        let should_load = self.should_load;
+        self.should_load = true;
         self.visit_expression(&expr.expr)?;
+        self.should_load = should_load;
+
+        // `addrof deref p` is the address held by `p`
+        if !self.should_load {
+            return Ok(());
+        }
+
         let ptr_value = self.current_value.as_ref().expect("Should have a value");
 
+        // Pointers are opaque, the loaded type is given by the pointee type
         self.current_value = Some(
             self.builder
                 .build_load(
-                    self.as_basic_type(ptr_value.get_type()),
+                    self.as_basic_type(self.to_llvm_type(expr.get_type())),
                     ptr_value.into_pointer_value(),
                     "deref_ptr",
                 )
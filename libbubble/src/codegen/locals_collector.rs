@@ -1,28 +1,42 @@
 use std::{collections::HashMap, convert::Infallible};
 
 use crate::{
-    ast::{FunctionStatement, GlobalStatement, LetStatement, Visitor},
-    type_system::{Typable, Type},
+    ast::{FunctionStatement, GlobalStatement, LetStatement, Locatable, Statement, Visitor},
+    type_system::{utils::ScopedMap, Typable, Type},
 };
 
+/// A local variable of a function. Locals may share the same name when they
+/// are declared in different scopes so they are identified by their
+/// declaration.
 #[derive(Debug)]
 pub struct StackVariable<'a> {
     pub name: &'a str,
     pub kind: &'a Type,
+    pub declaration: *const LetStatement,
 }
 
 impl<'a> StackVariable<'a> {
-    pub fn new(name: &'a str, kind: &'a Type) -> Self {
-        Self { name, kind }
+    pub fn new(declaration: &'a LetStatement) -> Self {
+        Self {
+            name: &declaration.name,
+            kind: declaration.get_type(),
+            declaration,
+        }
     }
 }
 
 pub type SymbolsMap<'ast> = HashMap<&'ast str, Vec<StackVariable<'ast>>>;
 
+/// Collects the locals of every function definition, whatever their type, so
+/// the translator allocates them in the function entry block. Struct
+/// declarations have no locals and are skipped.
 #[derive(Default)]
 pub struct Collector<'ast> {
     function_symbols: SymbolsMap<'ast>,
     current_function: Option<&'ast str>,
+    /// Locals of the blocks enclosing the visited statement, they are
+    /// collected when their block ends
+    scopes: ScopedMap<&'ast LetStatement>,
 }
 
 impl<'ast> Collector<'ast> {
@@ -39,6 +53,31 @@ impl<'ast> Collector<'ast> {
 
         Ok(&self.function_symbols)
     }
+
+    fn collect(&mut self, declaration: &'ast LetStatement) {
+        let current_function = self
+            .current_function
+            .expect("Local variable outside of a function");
+        self.function_symbols
+            .get_mut(current_function)
+            .unwrap()
+            .push(StackVariable::new(declaration));
+    }
+
+    /// Collect the locals of the innermost block in declaration order
+    fn collect_current_scope(&mut self) {
+        let mut declarations: Vec<&'ast LetStatement> = self
+            .scopes
+            .current_scope_symbols()
+            .map(|(_, declaration)| *declaration)
+            .collect();
+        declarations
+            .sort_by_key(|declaration| (declaration.get_location().begin, &declaration.name));
+
+        for declaration in declarations {
+            self.collect(declaration);
+        }
+    }
 }
 
 impl<'ast> Visitor<'ast, Infallible> for Collector<'ast> {
@@ -54,6 +93,11 @@ impl<'ast> Visitor<'ast, Infallible> for Collector<'ast> {
                 self.function_symbols
                     .insert(&stmt.name, collected_parameters);
                 self.visit_statements(stmt.body.as_ref().unwrap())?;
+                debug_assert!(
+                    self.scopes.all_symbols().next().is_none(),
+                    "Locals of {} were not collected",
+                    stmt.name
+                );
                 self.current_function = None;
             }
             _ => unreachable!(),
@@ -63,14 +107,39 @@ impl<'ast> Visitor<'ast, Infallible> for Collector<'ast> {
     }
 
     fn visit_let(&mut self, stmt: &'ast LetStatement) -> Result<(), Infallible> {
+        // Statics are globals, they have no alloca
+        if stmt.is_static {
+            return Ok(());
+        }
+
         // Let statement can be global, leaving them into no functions. In our case we're just
         // ignoring them
-        if let Some(current_function) = self.current_function {
-            self.function_symbols
-                .get_mut(current_function)
-                .unwrap()
-                .push(StackVariable::new(&stmt.name, stmt.get_type()));
+        if self.current_function.is_none() {
+            return Ok(());
+        }
+
+        // A declaration shadowing another one of the same block replaces it
+        // in the scope, the shadowed one still needs its alloca
+        let shadowed = self
+            .scopes
+            .current_scope_symbols()
+            .find(|(name, _)| **name == stmt.name)
+            .map(|(_, declaration)| *declaration);
+        if let Some(shadowed) = shadowed {
+            self.collect(shadowed);
+        }
+
+        self.scopes.insert_symbol(&stmt.name, stmt);
+        Ok(())
+    }
+
+    fn visit_statements_vec(&mut self, stmts: &'ast [Statement]) -> Result<(), Infallible> {
+        self.scopes.new_scope();
+        for stmt in stmts {
+            self.visit_statement_kind(&stmt.kind)?;
         }
+        self.collect_current_scope();
+        self.scopes.delete_scope();
 
         Ok(())
     }
@@ -1,5 +1,10 @@
+mod crt;
 mod llvm_ir;
 mod locals_collector;
+mod passes;
+mod select;
 
+pub use crt::*;
 pub use llvm_ir::*;
 pub use locals_collector::*;
+pub use passes::*;
@@ -0,0 +1,15 @@
+use inkwell::{module::Module, passes::PassManager};
+
+/// Runs LLVM optimizations on a translated module. Every variable is
+/// translated to an alloca with loads and stores, these passes promote them to
+/// registers and remove the resulting redundant instructions.
+///
+/// Returns whether the module was modified.
+pub fn run_optimization_passes(module: &Module) -> bool {
+    let pass_manager = PassManager::create(());
+    pass_manager.add_promote_memory_to_register_pass();
+    pass_manager.add_instruction_combining_pass();
+    pass_manager.add_cfg_simplification_pass();
+
+    pass_manager.run_on(module)
+}
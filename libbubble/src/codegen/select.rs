@@ -0,0 +1,67 @@
+use crate::ast::{
+    Assignment, Expression, IfStatement, LiteralType, OpType, Statement, StatementKind, Statements,
+};
+
+/// An `if cond { x = a; } else { x = b; }` statement. It is lowered to a
+/// `select` instead of branches.
+pub struct SelectAssignment<'ast> {
+    pub variable: &'ast str,
+    pub then_value: &'ast Expression,
+    pub else_value: &'ast Expression,
+}
+
+impl<'ast> SelectAssignment<'ast> {
+    /// Both values are evaluated whatever the condition is, so they must not
+    /// have side effects nor trap.
+    pub fn recognize(stmt: &'ast IfStatement) -> Option<Self> {
+        let (variable, then_value) = single_assignment(&stmt.then_clause)?;
+        let (else_variable, else_value) = single_assignment(stmt.else_clause.as_ref()?)?;
+
+        (variable == else_variable && is_speculatable(then_value) && is_speculatable(else_value))
+            .then_some(Self {
+                variable,
+                then_value,
+                else_value,
+            })
+    }
+}
+
+fn single_assignment(stmts: &Statements) -> Option<(&str, &Expression)> {
+    let [Statement {
+        kind: StatementKind::Expression { expr, .. },
+        ..
+    }] = stmts.statements.as_slice()
+    else {
+        return None;
+    };
+
+    match expr.as_ref() {
+        Expression::Assignment(Assignment { left, right, .. }) => match left.as_ref() {
+            Expression::Literal(literal) => Some((literal.as_lvalue()?, right.as_ref())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_speculatable(expr: &Expression) -> bool {
+    match expr {
+        Expression::Literal(literal) => {
+            !matches!(literal.literal_type, LiteralType::ArrayAccess(_))
+        }
+        Expression::Group(group) => is_speculatable(&group.expr),
+        Expression::AlignOf(_) => true,
+        // Divisions by zero trap
+        Expression::BinaryOperation(operation) => {
+            let right_speculatable = match &operation.right {
+                Some(right) => is_speculatable(right),
+                None => true,
+            };
+
+            !matches!(operation.op, OpType::Divide | OpType::Modulo)
+                && is_speculatable(&operation.left)
+                && right_speculatable
+        }
+        _ => false,
+    }
+}
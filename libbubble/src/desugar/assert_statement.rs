@@ -0,0 +1,309 @@
+use crate::{
+    ast::{
+        self, AssertStatement, BinaryOperation, Call, Expression, FunctionStatement,
+        GlobalStatement, IfStatement, Literal, LiteralType, Locatable, Null, OpType, SourceFile,
+        Statement, StatementKind, Statements, TypeKind,
+    },
+    type_system::{Typable, Type},
+};
+
+const REPORT_FUNCTION: &str = "printf";
+const REPORT_FORMAT: &str = "%s:%d:%d: assertion failed\n";
+// `abort` doesn't flush stdout, the report would be lost if it's a pipe
+const FLUSH_FUNCTION: &str = "fflush";
+const ABORT_FUNCTION: &str = "abort";
+
+fn build_call(
+    assert_stmt: &AssertStatement,
+    callee: &str,
+    arguments: Vec<Expression>,
+    ty: Type,
+) -> Statement {
+    let location = assert_stmt.get_location();
+    let mut call = Call::new(location.begin, location.end, callee.to_string(), arguments);
+    call.set_type(ty);
+
+    Statement::new(
+        location.begin,
+        location.end,
+        StatementKind::Expression {
+            expr: Box::new(Expression::Call(call)),
+            naked: false,
+        },
+    )
+}
+
+fn build_literal(assert_stmt: &AssertStatement, literal_type: LiteralType, ty: Type) -> Expression {
+    let location = assert_stmt.get_location();
+    let mut literal = Literal::new(location.begin, location.end, literal_type);
+    literal.set_type(ty);
+
+    Expression::Literal(literal)
+}
+
+/// Builds `if not <condition> { printf("file:line:column: ..."); fflush(null); abort(); }`.
+fn build_if(assert_stmt: AssertStatement, source: &SourceFile) -> IfStatement {
+    let location = assert_stmt.get_location().clone();
+    let (line, column) = source.line_column(location.begin);
+
+    let mut null = Null::new(location.begin, location.end);
+    null.set_type(Type::Ptr(Box::new(Type::Void)));
+
+    let report_arguments = vec![
+        build_literal(
+            &assert_stmt,
+            LiteralType::String(REPORT_FORMAT.to_string()),
+            Type::String,
+        ),
+        build_literal(
+            &assert_stmt,
+            LiteralType::String(source.path().to_string()),
+            Type::String,
+        ),
+        build_literal(&assert_stmt, LiteralType::Integer(line as i128), Type::I32),
+        build_literal(
+            &assert_stmt,
+            LiteralType::Integer(column as i128),
+            Type::I32,
+        ),
+    ];
+    let flush_arguments = vec![build_literal(
+        &assert_stmt,
+        LiteralType::Null(null),
+        Type::Ptr(Box::new(Type::Void)),
+    )];
+
+    let then_clause = Statements::new(
+        location.begin,
+        location.end,
+        vec![
+            build_call(&assert_stmt, REPORT_FUNCTION, report_arguments, Type::I32),
+            build_call(&assert_stmt, FLUSH_FUNCTION, flush_arguments, Type::I32),
+            build_call(&assert_stmt, ABORT_FUNCTION, Vec::new(), Type::Void),
+        ],
+    );
+
+    let mut condition = BinaryOperation::new(
+        location.begin,
+        location.end,
+        assert_stmt.condition,
+        OpType::Not,
+        None,
+    );
+    condition.set_type(Type::Bool);
+
+    IfStatement::new(
+        location.begin,
+        location.end,
+        Box::new(Expression::BinaryOperation(condition)),
+        Box::new(then_clause),
+        None,
+    )
+}
+
+fn desugar_statements(
+    stmts: Statements,
+    source: &SourceFile,
+    found_assert: &mut bool,
+) -> Statements {
+    let Statements {
+        statements,
+        location,
+    } = stmts;
+
+    let statements = statements
+        .into_iter()
+        .map(|Statement { kind, location }| {
+            let kind = match kind {
+                StatementKind::Assert(assert_stmt) => {
+                    *found_assert = true;
+                    StatementKind::If(build_if(assert_stmt, source))
+                }
+                StatementKind::If(mut if_stmt) => {
+                    if_stmt.then_clause = Box::new(desugar_statements(
+                        *if_stmt.then_clause,
+                        source,
+                        found_assert,
+                    ));
+                    if_stmt.else_clause = if_stmt
+                        .else_clause
+                        .map(|stmts| Box::new(desugar_statements(*stmts, source, found_assert)));
+                    StatementKind::If(if_stmt)
+                }
+                StatementKind::While(mut while_stmt) => {
+                    while_stmt.body =
+                        Box::new(desugar_statements(*while_stmt.body, source, found_assert));
+                    while_stmt.else_clause = while_stmt
+                        .else_clause
+                        .map(|stmts| Box::new(desugar_statements(*stmts, source, found_assert)));
+                    StatementKind::While(while_stmt)
+                }
+                StatementKind::For(mut for_stmt) => {
+                    for_stmt.body =
+                        Box::new(desugar_statements(*for_stmt.body, source, found_assert));
+                    StatementKind::For(for_stmt)
+                }
+                _ => kind,
+            };
+
+            Statement { kind, location }
+        })
+        .collect();
+
+    Statements {
+        statements,
+        location,
+    }
+}
+
+fn build_extern_declaration(
+    name: &str,
+    parameters: Vec<(TypeKind, Type)>,
+    return_type: (TypeKind, Type),
+    is_variadic: bool,
+) -> FunctionStatement {
+    let (parameters_kind, parameters_ty): (Vec<_>, Vec<_>) = parameters
+        .into_iter()
+        .enumerate()
+        .map(|(i, (kind, ty))| ((kind, format!("arg{i}")), (ty, format!("arg{i}"))))
+        .unzip();
+
+    let mut function = if is_variadic {
+        FunctionStatement::new_variadic_extern(
+            0,
+            0,
+            name.to_string(),
+            parameters_kind,
+            return_type.0,
+        )
+    } else {
+        FunctionStatement::new(
+            0,
+            0,
+            name.to_string(),
+            parameters_kind,
+            return_type.0,
+            true,
+            None,
+        )
+    };
+    function.set_type(Type::Function {
+        parameters: parameters_ty,
+        return_type: Box::new(return_type.1),
+    });
+
+    function
+}
+
+/// C functions called by desugared asserts, declared as they are called
+fn runtime_declarations() -> [FunctionStatement; 3] {
+    [
+        build_extern_declaration(
+            REPORT_FUNCTION,
+            vec![(TypeKind::String, Type::String)],
+            (TypeKind::I32, Type::I32),
+            true,
+        ),
+        build_extern_declaration(
+            FLUSH_FUNCTION,
+            vec![(
+                TypeKind::Ptr(Box::new(ast::Type::new(0, 0, TypeKind::Void))),
+                Type::Ptr(Box::new(Type::Void)),
+            )],
+            (TypeKind::I32, Type::I32),
+            false,
+        ),
+        build_extern_declaration(
+            ABORT_FUNCTION,
+            Vec::new(),
+            (TypeKind::Void, Type::Void),
+            false,
+        ),
+    ]
+}
+
+fn find_function<'a>(
+    global_stmts: &'a [GlobalStatement],
+    name: &str,
+) -> Option<&'a FunctionStatement> {
+    global_stmts.iter().find_map(|stmt| match stmt {
+        GlobalStatement::Function(f) if f.name == name => Some(f),
+        _ => None,
+    })
+}
+
+/// `function printf(string, ...): i32`
+fn signature(function: &FunctionStatement) -> String {
+    let Type::Function {
+        parameters,
+        return_type,
+    } = function.get_type()
+    else {
+        unreachable!("Function {} has no function type", function.name);
+    };
+
+    let mut parameters: Vec<String> = parameters.iter().map(|(ty, _)| ty.to_string()).collect();
+    if function.is_variadic {
+        parameters.push("...".to_string());
+    }
+
+    format!(
+        "function {}({}): {return_type}",
+        function.name,
+        parameters.join(", ")
+    )
+}
+
+/// Desugared asserts call C functions the program may declare too. Returns
+/// the name and expected signature of the first one declared with another
+/// signature in the type checked `global_stmts`, asserts can't call it.
+pub(crate) fn incompatible_assert_function(
+    global_stmts: &[GlobalStatement],
+) -> Option<(String, String)> {
+    runtime_declarations().into_iter().find_map(|expected| {
+        let declared = find_function(global_stmts, &expected.name)?;
+        if declared.is_variadic == expected.is_variadic
+            && declared.get_type() == expected.get_type()
+        {
+            None
+        } else {
+            Some((expected.name.clone(), signature(&expected)))
+        }
+    })
+}
+
+/// Replaces every `assert` statement by a condition check reporting the failure
+/// location in `source` and aborting the program. Required C functions are
+/// declared if needed, see `incompatible_assert_function`.
+pub fn desugar_assert(
+    global_stmts: Vec<GlobalStatement>,
+    source: &SourceFile,
+) -> Vec<GlobalStatement> {
+    let mut found_assert = false;
+    let mut desugared_stmts: Vec<GlobalStatement> = global_stmts
+        .into_iter()
+        .map(|stmt| match stmt {
+            GlobalStatement::Function(mut fn_stmt) if !fn_stmt.is_extern => {
+                fn_stmt.body = fn_stmt
+                    .body
+                    .map(|body| desugar_statements(body, source, &mut found_assert));
+                GlobalStatement::Function(fn_stmt)
+            }
+            _ => stmt,
+        })
+        .collect();
+
+    if !found_assert {
+        return desugared_stmts;
+    }
+
+    // The type checker made sure the functions declared by the program have
+    // the expected signature
+    for declaration in runtime_declarations().into_iter().rev() {
+        if find_function(&desugared_stmts, &declaration.name).is_none() {
+            desugared_stmts.insert(0, GlobalStatement::Function(declaration));
+        }
+    }
+
+    desugared_stmts
+}
@@ -0,0 +1,30 @@
+use std::collections::HashSet;
+
+use crate::ast::{AttributeKind, FunctionStatement, GlobalStatement};
+
+/// Returns whether the symbols of every `@cfg` attribute of the function are
+/// defined.
+fn is_enabled(fn_stmt: &FunctionStatement, defined: &HashSet<String>) -> bool {
+    fn_stmt
+        .attributes
+        .iter()
+        .filter(|attribute| attribute.kind() == Some(AttributeKind::Cfg))
+        .filter_map(|attribute| attribute.argument.as_ref())
+        .all(|symbol| defined.contains(symbol))
+}
+
+/// Removes the functions guarded by a `@cfg(SYMBOL)` attribute whose symbol
+/// isn't in `defined`. It runs before the binder so the removed functions
+/// are never checked nor compiled.
+pub fn strip_cfg(
+    global_statements: Vec<GlobalStatement>,
+    defined: &HashSet<String>,
+) -> Vec<GlobalStatement> {
+    global_statements
+        .into_iter()
+        .filter(|stmt| match stmt {
+            GlobalStatement::Function(fn_stmt) => is_enabled(fn_stmt, defined),
+            _ => true,
+        })
+        .collect()
+}
@@ -0,0 +1,258 @@
+use std::convert::Infallible;
+
+use crate::{
+    ast::{
+        Expression, GlobalStatement, LetStatement, Literal, LiteralType, Locatable, MutableVisitor,
+        ReturnStatement, Statement, StatementKind, Statements, TokenLocation, TryNull,
+    },
+    type_system::Typable,
+};
+
+/// Identifiers can't start with `_` so this variable never shadows one
+/// used by a deferred expression.
+const RETURN_VALUE: &str = "_return_value";
+
+/// Expressions deferred in a block, in declaration order
+struct Scope {
+    deferred: Vec<Expression>,
+    is_loop_body: bool,
+}
+
+fn expression_statement(expr: Expression, location: &TokenLocation) -> Statement {
+    Statement::new(
+        location.begin,
+        location.end,
+        StatementKind::Expression {
+            expr: Box::new(expr),
+            naked: false,
+        },
+    )
+}
+
+/// Statements running the expressions deferred in `scopes` when leaving them,
+/// from the last deferred expression of the innermost scope to the first one
+/// of the outermost scope.
+fn deferred_statements(scopes: &[Scope], location: &TokenLocation) -> Vec<Statement> {
+    scopes
+        .iter()
+        .rev()
+        .flat_map(|scope| scope.deferred.iter().rev())
+        .map(|expr| expression_statement(expr.clone(), location))
+        .collect()
+}
+
+/// Sets the statements run by the `?` expressions before returning null
+struct TryNullDeferred<'a> {
+    scopes: &'a [Scope],
+}
+
+impl<'ast> MutableVisitor<'ast, Infallible> for TryNullDeferred<'_> {
+    fn visit_try_null(&mut self, expr: &'ast mut TryNull) -> Result<(), Infallible> {
+        self.visit_expression(&mut expr.expr)?;
+        expr.deferred = deferred_statements(self.scopes, expr.get_location());
+
+        Ok(())
+    }
+}
+
+/// A `?` leaves the function like a `return` so it runs every deferred
+/// expression of `scopes` when its pointer is null.
+fn set_try_null_deferred(expr: &mut Expression, scopes: &[Scope]) {
+    TryNullDeferred { scopes }
+        .visit_expression(expr)
+        .expect("Should never fail");
+}
+
+/// Scopes left by a `break` or a `continue`, up to the innermost loop body.
+fn loop_scopes(scopes: &[Scope]) -> &[Scope] {
+    let loop_body = scopes
+        .iter()
+        .rposition(|scope| scope.is_loop_body)
+        .unwrap_or(0);
+
+    &scopes[loop_body..]
+}
+
+/// Runs every deferred expression of the function before returning. The
+/// returned value is computed first and kept in a variable as the deferred
+/// expressions may modify what it depends on, e.g. `defer free(p);`.
+fn build_return(mut return_stmt: ReturnStatement, scopes: &[Scope]) -> Vec<Statement> {
+    let location = return_stmt.get_location().clone();
+    if let Some(exp) = return_stmt.exp.as_mut() {
+        set_try_null_deferred(exp, scopes);
+    }
+
+    let mut deferred = deferred_statements(scopes, &location);
+    if deferred.is_empty() {
+        return vec![Statement::new(
+            location.begin,
+            location.end,
+            StatementKind::Return(return_stmt),
+        )];
+    }
+
+    let Some(exp) = return_stmt.exp else {
+        deferred.push(Statement::new(
+            location.begin,
+            location.end,
+            StatementKind::Return(return_stmt),
+        ));
+        return deferred;
+    };
+
+    let ty = exp.get_type().clone();
+    let mut return_value = LetStatement::new(
+        location.begin,
+        location.end,
+        RETURN_VALUE.to_string(),
+        None,
+        Some(exp),
+    );
+    return_value.set_type(ty.clone());
+
+    let mut returned = Literal::new(
+        location.begin,
+        location.end,
+        LiteralType::Identifier(RETURN_VALUE.to_string()),
+    );
+    returned.set_type(ty);
+
+    let mut statements = vec![Statement::new(
+        location.begin,
+        location.end,
+        StatementKind::Let(return_value),
+    )];
+    statements.append(&mut deferred);
+    statements.push(Statement::new(
+        location.begin,
+        location.end,
+        StatementKind::Return(ReturnStatement::new(
+            location.begin,
+            location.end,
+            Some(Box::new(Expression::Literal(returned))),
+        )),
+    ));
+
+    statements
+}
+
+fn desugar_statements(
+    stmts: Statements,
+    scopes: &mut Vec<Scope>,
+    is_loop_body: bool,
+) -> Statements {
+    let Statements {
+        statements,
+        location,
+    } = stmts;
+
+    scopes.push(Scope {
+        deferred: Vec::new(),
+        is_loop_body,
+    });
+
+    let mut desugared_stmts = Vec::with_capacity(statements.len());
+    for Statement { mut kind, location } in statements {
+        match &mut kind {
+            StatementKind::Expression { expr, .. } => set_try_null_deferred(expr, scopes),
+            StatementKind::Let(LetStatement {
+                init_exp: Some(init_exp),
+                ..
+            }) => set_try_null_deferred(init_exp, scopes),
+            StatementKind::LetTuple(let_tuple) => {
+                set_try_null_deferred(&mut let_tuple.init_exp, scopes)
+            }
+            StatementKind::If(if_stmt) => set_try_null_deferred(&mut if_stmt.condition, scopes),
+            StatementKind::While(while_stmt) => {
+                set_try_null_deferred(&mut while_stmt.condition, scopes)
+            }
+            // The modify expression runs once the body deferred expressions
+            // have run
+            StatementKind::For(for_stmt) => {
+                if let Some(init_exp) = for_stmt.init_decl.init_exp.as_mut() {
+                    set_try_null_deferred(init_exp, scopes);
+                }
+                set_try_null_deferred(&mut for_stmt.continue_expression, scopes);
+                set_try_null_deferred(&mut for_stmt.modify_expression, scopes);
+            }
+            _ => (),
+        }
+
+        match kind {
+            StatementKind::Defer(expr) => scopes
+                .last_mut()
+                .expect("Statements have no scope")
+                .deferred
+                .push(*expr),
+            StatementKind::Return(return_stmt) => {
+                desugared_stmts.append(&mut build_return(return_stmt, scopes))
+            }
+            StatementKind::Break(_) | StatementKind::Continue(_) => {
+                desugared_stmts.append(&mut deferred_statements(loop_scopes(scopes), &location));
+                desugared_stmts.push(Statement { kind, location });
+            }
+            StatementKind::If(mut if_stmt) => {
+                if_stmt.then_clause =
+                    Box::new(desugar_statements(*if_stmt.then_clause, scopes, false));
+                if_stmt.else_clause = if_stmt
+                    .else_clause
+                    .map(|stmts| Box::new(desugar_statements(*stmts, scopes, false)));
+                desugared_stmts.push(Statement {
+                    kind: StatementKind::If(if_stmt),
+                    location,
+                });
+            }
+            StatementKind::While(mut while_stmt) => {
+                while_stmt.body = Box::new(desugar_statements(*while_stmt.body, scopes, true));
+                while_stmt.else_clause = while_stmt
+                    .else_clause
+                    .map(|stmts| Box::new(desugar_statements(*stmts, scopes, false)));
+                desugared_stmts.push(Statement {
+                    kind: StatementKind::While(while_stmt),
+                    location,
+                });
+            }
+            StatementKind::For(mut for_stmt) => {
+                for_stmt.body = Box::new(desugar_statements(*for_stmt.body, scopes, true));
+                desugared_stmts.push(Statement {
+                    kind: StatementKind::For(for_stmt),
+                    location,
+                });
+            }
+            _ => desugared_stmts.push(Statement { kind, location }),
+        }
+    }
+
+    // The end of the block is only reached if it doesn't end with a jump
+    let scope = scopes.pop().expect("Statements have no scope");
+    if !matches!(
+        desugared_stmts.last().map(|stmt| &stmt.kind),
+        Some(StatementKind::Return(_) | StatementKind::Break(_) | StatementKind::Continue(_))
+    ) {
+        desugared_stmts.append(&mut deferred_statements(&[scope], &location));
+    }
+
+    Statements {
+        statements: desugared_stmts,
+        location,
+    }
+}
+
+/// Replaces every `defer` statement by copies of its expression on each path
+/// leaving the block it is declared in: before a `return`, a `break` or a
+/// `continue` and at the end of the block. Deferred expressions run in the
+/// reverse order of their declaration.
+pub fn desugar_defer(global_stmts: Vec<GlobalStatement>) -> Vec<GlobalStatement> {
+    global_stmts
+        .into_iter()
+        .map(|stmt| match stmt {
+            GlobalStatement::Function(mut fn_stmt) if !fn_stmt.is_extern => {
+                fn_stmt.body = fn_stmt
+                    .body
+                    .map(|body| desugar_statements(body, &mut Vec::new(), false));
+                GlobalStatement::Function(fn_stmt)
+            }
+            _ => stmt,
+        })
+        .collect()
+}
@@ -1,8 +1,45 @@
 use crate::ast::{
-    ForStatement, FunctionStatement, GlobalStatement, Statement, StatementKind, Statements,
-    WhileStatement,
+    Expression, ForStatement, FunctionStatement, GlobalStatement, Locatable, Statement,
+    StatementKind, Statements, WhileStatement,
 };
 
+fn modify_statement(modify_expression: &Expression) -> Statement {
+    Statement {
+        location: modify_expression.get_location().clone(),
+        kind: StatementKind::Expression {
+            expr: Box::new(modify_expression.clone()),
+            naked: false,
+        },
+    }
+}
+
+/// A `continue` jumps to the loop condition, skipping the modify expression
+/// appended to the body. Run it before each `continue` of the loop, those of
+/// nested loop bodies belong to these loops.
+fn modify_before_continue(stmts: &mut Statements, modify_expression: &Expression) {
+    for mut stmt in std::mem::take(&mut stmts.statements) {
+        match &mut stmt.kind {
+            StatementKind::Continue(_) => {
+                stmts.statements.push(modify_statement(modify_expression))
+            }
+            StatementKind::If(if_stmt) => {
+                modify_before_continue(&mut if_stmt.then_clause, modify_expression);
+                if let Some(else_clause) = if_stmt.else_clause.as_mut() {
+                    modify_before_continue(else_clause, modify_expression);
+                }
+            }
+            StatementKind::While(while_stmt) => {
+                if let Some(else_clause) = while_stmt.else_clause.as_mut() {
+                    modify_before_continue(else_clause, modify_expression);
+                }
+            }
+            _ => (),
+        }
+
+        stmts.statements.push(stmt);
+    }
+}
+
 fn build_while(for_stmt: ForStatement) -> Vec<Statement> {
     let ForStatement {
         init_decl,
@@ -12,24 +49,24 @@ fn build_while(for_stmt: ForStatement) -> Vec<Statement> {
         location,
     } = for_stmt;
 
-    for_body.statements.push(Statement {
-        kind: StatementKind::Expression {
-            expr: modify_expression,
-            naked: false,
-        },
-        location: location.clone(),
-    });
+    // The synthesized statements keep the location of the code they come
+    // from, errors about them point to the matching part of the loop
+    modify_before_continue(&mut for_body, &modify_expression);
+    for_body
+        .statements
+        .push(modify_statement(&modify_expression));
 
     let while_stmt = WhileStatement {
         condition: continue_expression,
         body: for_body,
+        else_clause: None,
         location: location.clone(),
     };
 
     vec![
         Statement {
+            location: init_decl.get_location().clone(),
             kind: StatementKind::Let(init_decl),
-            location: location.clone(),
         },
         Statement {
             kind: StatementKind::While(while_stmt),
@@ -70,6 +107,10 @@ pub fn desugar_for(global_stmts: Vec<GlobalStatement>) -> Vec<GlobalStatement> {
                     parameters,
                     return_type,
                     is_extern,
+                    is_variadic,
+                    is_exported,
+                    attributes,
+                    calling_convention,
                     body,
                     location,
                     ty,
@@ -81,6 +122,10 @@ pub fn desugar_for(global_stmts: Vec<GlobalStatement>) -> Vec<GlobalStatement> {
                     parameters,
                     return_type,
                     is_extern,
+                    is_variadic,
+                    is_exported,
+                    attributes,
+                    calling_convention,
                     body: Some(desugared_body),
                     location,
                     ty,
@@ -1,9 +1,37 @@
-use crate::ast::GlobalStatement;
+use crate::{
+    ast::{GlobalStatement, SourceFile},
+    type_system::binder::Binder,
+};
 
-use self::for_statement::desugar_for;
+use self::{
+    assert_statement::desugar_assert, defer_statement::desugar_defer, for_statement::desugar_for,
+};
 
+pub(crate) use self::assert_statement::incompatible_assert_function;
+pub use self::cfg_attribute::strip_cfg;
+
+mod assert_statement;
+mod cfg_attribute;
+mod defer_statement;
 mod for_statement;
 
-pub fn desugar_ast(global_statements: Vec<GlobalStatement>) -> Vec<GlobalStatement> {
-    desugar_for(global_statements)
+/// Desugars the bound and type checked `global_statements`.
+///
+/// Desugaring needs the types so it runs after the binder, but it moves and
+/// rebuilds statements, leaving the definitions set by the binder pointing to
+/// freed declarations. The desugared statements are bound again so these
+/// definitions are valid in the returned AST. Runtime messages point to
+/// locations in `source`, the file the statements are parsed from.
+pub fn desugar_ast(
+    global_statements: Vec<GlobalStatement>,
+    source: &SourceFile,
+) -> Vec<GlobalStatement> {
+    // Deferred expressions must run before the modify expression appended to
+    // desugared for loop bodies
+    let mut desugared_stmts = desugar_for(desugar_defer(desugar_assert(global_statements, source)));
+    Binder::default()
+        .bind_statements(&mut desugared_stmts)
+        .expect("Failed to bind desugared statements");
+
+    desugared_stmts
 }
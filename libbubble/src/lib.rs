@@ -3,3 +3,5 @@ pub mod codegen;
 pub mod desugar;
 pub mod parser;
 pub mod type_system;
+
+pub use parser::try_parse;
@@ -1,49 +1,116 @@
-use std::fmt;
+use std::{fmt, ops::Range};
 
 use logos::{Logos, SpannedIter};
+use thiserror::Error;
 
 pub type Spanned<Tok, Loc, Error> = Result<(Loc, Tok, Loc), Error>;
 
-#[derive(Debug)]
+/// Errors carry the byte range of the offending source code
+#[derive(Error, Debug, PartialEq)]
 pub enum LexicalError {
-    InvalidToken,
-    InvalidIntegerLiteral { msg: String },
-    InvalidFloatLiteral { msg: String },
+    #[error("Invalid token at {}:{}", span.start, span.end)]
+    InvalidToken { span: Range<usize> },
+    #[error("Invalid integer literal at {}:{}: {msg}", span.start, span.end)]
+    InvalidIntegerLiteral { msg: String, span: Range<usize> },
+    #[error("Invalid float literal at {}:{}: {msg}", span.start, span.end)]
+    InvalidFloatLiteral { msg: String, span: Range<usize> },
+}
+
+impl LexicalError {
+    pub fn span(&self) -> &Range<usize> {
+        match self {
+            LexicalError::InvalidToken { span }
+            | LexicalError::InvalidIntegerLiteral { span, .. }
+            | LexicalError::InvalidFloatLiteral { span, .. } => span,
+        }
+    }
 }
 
 pub struct Lexer<'input> {
+    input: &'input str,
     token_stream: SpannedIter<'input, Token>,
+    keep_comments: bool,
 }
 
 impl<'input> Lexer<'input> {
     pub fn new(input: &'input str) -> Self {
         Lexer {
+            input,
             token_stream: Token::lexer(input).spanned(),
+            keep_comments: false,
         }
     }
+
+    /// Creates a lexer yielding comments as `LineComment` and `BlockComment`
+    /// tokens instead of skipping them. The parser doesn't accept comment
+    /// tokens, this mode is meant for tools like formatters.
+    pub fn new_with_comments(input: &'input str) -> Self {
+        Lexer {
+            input,
+            token_stream: Token::lexer(input).spanned(),
+            keep_comments: true,
+        }
+    }
+
+    /// Collects every token of `input` along with its byte range, stopping at
+    /// the first lexical error. Meant for tools like syntax highlighters.
+    pub fn tokenize(input: &'input str) -> Result<Vec<(Token, Range<usize>)>, LexicalError> {
+        Lexer::new(input)
+            .map(|spanned| spanned.map(|(begin, token, end)| (token, begin..end)))
+            .collect()
+    }
 }
 
 /// Walks the source code until an other " is reached.
 /// Then bump the lexer to second " location to resume lexing
 /// it acts likes Flex sublexer
+///
+/// Escape sequences are replaced by the character they stand for, an unknown
+/// escape sequence is a lexical error.
 fn handle_quote(lex: &mut logos::Lexer<Token>) -> Result<String, ()> {
-    let mut inner_content: Vec<char> = Vec::new();
-    let remainder_string = lex.remainder();
-
-    for chr in remainder_string.chars() {
-        if chr == '"' {
-            // Bump to the literal's size + 1 to skip the closing quote
-            lex.bump(inner_content.len() + 1);
-            return Ok(inner_content.iter().collect());
-        }
+    let mut content = String::new();
+    let mut chars = lex.remainder().char_indices();
 
-        inner_content.push(chr);
+    while let Some((index, chr)) = chars.next() {
+        match chr {
+            '"' => {
+                // `bump` takes a byte count, bump to the closing quote offset
+                // + 1 to skip it
+                lex.bump(index + 1);
+                return Ok(content);
+            }
+            '\\' => {
+                let (_, escaped) = chars.next().ok_or(())?;
+                content.push(match escaped {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    '0' => '\0',
+                    '\\' => '\\',
+                    '"' => '"',
+                    _ => return Err(()),
+                });
+            }
+            _ => content.push(chr),
+        }
     }
 
-    eprint!("Error: Unclosed string literal.");
     Err(())
 }
 
+/// Bumps the lexer after the `*/` closing a block comment. Block comments
+/// don't nest.
+fn handle_block_comment(lex: &mut logos::Lexer<Token>) -> Result<String, ()> {
+    match lex.remainder().find("*/") {
+        Some(end) => {
+            let content = lex.remainder()[..end].to_string();
+            lex.bump(end + 2);
+            Ok(content)
+        }
+        None => Err(()),
+    }
+}
+
 #[derive(Logos, Debug, PartialEq, Clone)]
 pub enum Token {
     // Syntax elements
@@ -65,6 +132,16 @@ pub enum Token {
     Semicolon,
     #[token(":")]
     Colon,
+    #[token(".")]
+    Dot,
+    #[token("..")]
+    DotDot,
+    #[token("...")]
+    Ellipsis,
+    #[token("@")]
+    At,
+    #[token("?")]
+    Question,
     #[token("=")]
     Equal,
 
@@ -103,6 +180,8 @@ pub enum Token {
     Function,
     #[token("struct")]
     Struct,
+    #[token("enum")]
+    Enum,
     #[token("if")]
     If,
     #[token("else")]
@@ -119,12 +198,24 @@ pub enum Token {
     Break,
     #[token("continue")]
     Continue,
+    #[token("assert")]
+    Assert,
+    #[token("defer")]
+    Defer,
     #[token("true")]
     True,
     #[token("false")]
     False,
     #[token("extern")]
     Extern,
+    #[token("export")]
+    Export,
+    #[token("const")]
+    Const,
+    #[token("static")]
+    Static,
+    #[token("self")]
+    SelfKw,
 
     // =================
     //       Types
@@ -139,6 +230,8 @@ pub enum Token {
     U32Ty,
     #[token("u64")]
     U64Ty,
+    #[token("u128")]
+    U128Ty,
 
     // signed integer type
     #[token("i8")]
@@ -149,10 +242,14 @@ pub enum Token {
     I32Ty,
     #[token("i64")]
     I64Ty,
+    #[token("i128")]
+    I128Ty,
 
-    // Bool, string and void
+    // Bool, float, string and void
     #[token("bool")]
     BoolTy,
+    #[token("float")]
+    FloatTy,
     #[token("string")]
     StringTy,
     #[token("void")]
@@ -168,18 +265,35 @@ pub enum Token {
     #[token("null")]
     Null,
 
+    #[token("alignof")]
+    Alignof,
+
     // Literals
     #[regex(r"[a-zA-Z][a-zA-Z0-9_]*", |lex| lex.slice().parse())]
+    // Raw identifiers allow keywords to be used as names, `r#for` is `for`
+    #[regex(r"r#[a-zA-Z][a-zA-Z0-9_]*", |lex| lex.slice()[2..].parse())]
     Identifier(String),
-    #[regex(r"([0-9]+)?\.[0-9]+", |lex| lex.slice().parse())]
+    // The exponent is optional when there is a fractional part: `1.5`, `.5e2`
+    // and `1e10` are all floats
+    #[regex(r"([0-9]+)?\.[0-9]+([eE][+-]?[0-9]+)?", |lex| lex.slice().parse())]
+    #[regex(r"[0-9]+[eE][+-]?[0-9]+", |lex| lex.slice().parse())]
     Real(f64),
     #[regex(r"[1-9]+[0-9]*|0", |lex| lex.slice().parse())]
-    Integer(i64),
+    Integer(i128),
     #[token("\"", handle_quote)]
     String(String),
 
+    // Comments text is stored without their delimiters
+    #[regex(r"//[^\n]*", |lex| lex.slice()[2..].to_string())]
+    LineComment(String),
+    #[token("/*", handle_block_comment)]
+    BlockComment(String),
+
     #[error]
     #[regex(r"[ \r\t\v\r\n]", logos::skip)]
+    // A number directly followed by an identifier like `123abc` is most
+    // likely a typo, it is rejected instead of being read as two tokens
+    #[regex(r"[0-9]+[a-zA-Z_][a-zA-Z0-9_]*")]
     Error,
 }
 
@@ -193,11 +307,28 @@ impl<'input> Iterator for Lexer<'input> {
     type Item = Spanned<Token, usize, LexicalError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.token_stream.next().map(|(token, span)| match token {
-            Token::Error => {
-                println!("{} {:?} {}", span.start, token, span.end);
-                Err(LexicalError::InvalidToken)
+        let keep_comments = self.keep_comments;
+        let (token, span) = self.token_stream.find(|(token, _)| {
+            keep_comments || !matches!(token, Token::LineComment(_) | Token::BlockComment(_))
+        })?;
+        let number_suffix =
+            self.input[span.clone()].trim_start_matches(|c: char| c.is_ascii_digit());
+
+        Some(match token {
+            // Only numbers followed by identifier characters have a suffix,
+            // integers too large to be parsed are plain invalid tokens
+            Token::Error if number_suffix.len() < span.len() && !number_suffix.is_empty() => {
+                Err(LexicalError::InvalidIntegerLiteral {
+                    msg: format!("invalid suffix `{number_suffix}`"),
+                    span,
+                })
             }
+            Token::Error => Err(LexicalError::InvalidToken { span }),
+            // Parsing a too large literal doesn't fail but gives infinity
+            Token::Real(value) if value.is_infinite() => Err(LexicalError::InvalidFloatLiteral {
+                msg: "value does not fit in a float".to_string(),
+                span,
+            }),
             _ => Ok((span.start, token, span.end)),
         })
     }
@@ -1,4 +1,9 @@
-use self::lexer::{LexicalError, Token};
+use crate::ast::GlobalStatement;
+
+use self::{
+    grammar::GlobalStatementsParser,
+    lexer::{Lexer, LexicalError, Token},
+};
 
 // We don't want to check this module because it's autogenerated by the build
 // script. It's not actual part of the source code.
@@ -8,3 +13,10 @@ pub mod lexer;
 
 pub type StatementsParserResult<T> = Result<T, ParserError>;
 pub type ParserError = lalrpop_util::ParseError<usize, Token, LexicalError>;
+
+/// Parses the global statements of `source_code`. Any malformed input is
+/// reported as an `Err`, this function never panics which makes it suitable
+/// for fuzzing.
+pub fn try_parse(source_code: &str) -> StatementsParserResult<Vec<GlobalStatement>> {
+    GlobalStatementsParser::new().parse(Lexer::new(source_code))
+}
@@ -1,24 +1,82 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, convert::Infallible};
 
 use crate::ast::{
-    Bindable, BreakStatement, Call, ContinueStatement, Definition, Expression, ForStatement,
-    FunctionStatement, GlobalStatement, IfStatement, LetStatement, Literal, LiteralType, Locatable,
-    MutableVisitor, ReturnStatement, StructStatement, Type, TypeKind, WhileStatement,
+    AddrOf, AlignOf, Bindable, BreakStatement, Builtin, Call, ContinueStatement, Definition,
+    EnumStatement, Expression, ForStatement, FunctionStatement, GlobalStatement, IfStatement,
+    LetStatement, Literal, LiteralType, Locatable, MutableVisitor, ReturnStatement,
+    StructStatement, TokenLocation, Type, TypeKind, Visitor, WhileStatement,
 };
 
-use super::{errors::BinderError, utils::ScopedMap};
+use super::{errors::BinderError, utils::ScopedMap, warnings::Warning};
+
+/// Names of the local variables an expression reads
+#[derive(Default)]
+struct LocalVariablesCollector {
+    names: Vec<String>,
+}
+
+impl<'ast> Visitor<'ast, Infallible> for LocalVariablesCollector {
+    fn visit_literal(&mut self, expr: &'ast Literal) -> Result<(), Infallible> {
+        match &expr.literal_type {
+            LiteralType::Identifier(name) if expr.get_definition().is_local_variable() => {
+                self.names.push(name.clone());
+                Ok(())
+            }
+            LiteralType::ArrayAccess(array_access) => {
+                self.visit_expression(&array_access.identifier)?;
+                self.visit_expression(&array_access.index)
+            }
+            _ => Ok(()),
+        }
+    }
+}
 
 #[derive(Default)]
 pub struct Binder {
     functions_statements: HashMap<String, *const FunctionStatement>,
     struct_statement: HashMap<String, *const StructStatement>,
+    enum_statements: HashMap<String, *const EnumStatement>,
     local_variables: ScopedMap<*const LetStatement>,
+    /// Variables read by the deferred expressions of each scope
+    deferred_variables: ScopedMap<()>,
     nested_loop: usize,
     in_function: bool,
+    warn_shadow: bool,
+    warnings: Vec<Warning>,
 }
 
 impl Binder {
+    /// Creates a binder reporting variables shadowing a previous declaration.
+    pub fn with_shadow_warnings() -> Self {
+        Self {
+            warn_shadow: true,
+            ..Default::default()
+        }
+    }
+
+    /// Warnings found while binding the statements
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
     pub fn bind_statements(&mut self, stmts: &mut [GlobalStatement]) -> Result<(), BinderError> {
+        // Register global functions and structs first so they can be used
+        // before their definition
+        for stmt in stmts.iter() {
+            match stmt {
+                GlobalStatement::Function(f) => {
+                    self.functions_statements.insert(f.name.to_string(), f);
+                }
+                GlobalStatement::Struct(s) => {
+                    self.struct_statement.insert(s.name.to_string(), s);
+                }
+                GlobalStatement::Enum(e) => {
+                    self.enum_statements.insert(e.name.to_string(), e);
+                }
+                GlobalStatement::Let(_) => (),
+            }
+        }
+
         for stmt in stmts {
             self.visit_global_statement(stmt)?;
         }
@@ -26,14 +84,81 @@ impl Binder {
         Ok(())
     }
 
+    fn new_scope(&mut self) {
+        self.local_variables.new_scope();
+        self.deferred_variables.new_scope();
+    }
+
+    fn delete_scope(&mut self) {
+        self.local_variables.delete_scope();
+        self.deferred_variables.delete_scope();
+    }
+
     fn begin_loop(&mut self) {
         self.nested_loop += 1;
-        self.local_variables.new_scope();
+        self.new_scope();
     }
 
     fn end_loop(&mut self) {
         self.nested_loop -= 1;
-        self.local_variables.delete_scope();
+        self.delete_scope();
+    }
+
+    /// Turns the method call `receiver.method(...)` into a call to the method
+    /// function `Struct.method(addrof receiver, ...)`. The struct is found
+    /// from the receiver type annotation as types aren't known yet.
+    fn resolve_method_call(&self, expr: &mut Call) -> Result<(), BinderError> {
+        let receiver_name = match &expr.arguments[0] {
+            Expression::Literal(Literal {
+                literal_type: LiteralType::Identifier(name),
+                ..
+            }) => name,
+            _ => unreachable!("Method receivers are identifiers"),
+        };
+
+        let declaration = match self.local_variables.find_symbol(receiver_name) {
+            Some(declaration) => unsafe { &**declaration },
+            None => {
+                return Err(BinderError::UndeclaredVariable {
+                    location: expr.arguments[0].get_location().clone(),
+                    name: receiver_name.clone(),
+                })
+            }
+        };
+
+        let (struct_name, is_pointer) = match &declaration.declaration_type {
+            Some(TypeKind::Identifier(name)) => (name, false),
+            Some(TypeKind::Ptr(pointee)) => match &pointee.kind {
+                TypeKind::Identifier(name) => (name, true),
+                _ => {
+                    return Err(BinderError::InvalidMethodReceiver {
+                        location: expr.arguments[0].get_location().clone(),
+                    })
+                }
+            },
+            _ => {
+                return Err(BinderError::InvalidMethodReceiver {
+                    location: expr.arguments[0].get_location().clone(),
+                })
+            }
+        };
+
+        expr.callee = FunctionStatement::method_name(struct_name, &expr.callee);
+        if !is_pointer {
+            let receiver = expr.arguments.remove(0);
+            let location = receiver.get_location().clone();
+            expr.arguments.insert(
+                0,
+                Expression::AddrOf(AddrOf::new(
+                    location.begin,
+                    location.end,
+                    Box::new(receiver),
+                )),
+            );
+        }
+
+        expr.is_method_call = false;
+        Ok(())
     }
 
     fn is_subscriptable(expr: &Expression) -> bool {
@@ -56,18 +181,30 @@ impl<'ast> MutableVisitor<'ast, BinderError> for Binder {
             .insert(stmt.name.to_string(), stmt);
 
         if !stmt.is_extern {
-            self.local_variables.new_scope();
+            self.new_scope();
             // We treat functions parameters as simple declarations as it'll simplify the rest of our
             // implementation.
             // TODO: Investigate if it's possible to do it directly in the ast
             for let_stmt in &stmt.parameters {
                 self.local_variables.insert_symbol(&let_stmt.name, let_stmt);
             }
+        }
+
+        for parameter in &mut stmt.parameters {
+            if let Some(kind) = &mut parameter.declaration_type {
+                self.visit_type_kind(kind)?;
+            }
+        }
+        self.visit_type_kind(&mut stmt.return_type)?;
 
+        if !stmt.is_extern {
+            // The body has its own scope so a local may shadow a parameter
+            self.new_scope();
             self.in_function = true;
             self.visit_statements(stmt.body.as_mut().unwrap())?;
             self.in_function = false;
-            self.local_variables.delete_scope();
+            self.delete_scope();
+            self.delete_scope();
         }
 
         Ok(())
@@ -84,12 +221,46 @@ impl<'ast> MutableVisitor<'ast, BinderError> for Binder {
     }
 
     fn visit_let(&mut self, stmt: &'ast mut LetStatement) -> Result<(), BinderError> {
+        // Deferred expressions are copied to the exit points of their scope
+        // and bound again by name, they would read the shadowing variable
+        if self.deferred_variables.find_symbol(&stmt.name).is_some() {
+            return Err(BinderError::ShadowedDeferredVariable {
+                location: stmt.get_location().clone(),
+                name: stmt.name.clone(),
+            });
+        }
+
+        if self.warn_shadow {
+            if let Some(shadowed) = self.local_variables.find_symbol(&stmt.name) {
+                self.warnings.push(Warning::ShadowedVariable {
+                    name: stmt.name.clone(),
+                    location: stmt.get_location().clone(),
+                    shadowed: unsafe { &(**shadowed) }.get_location().clone(),
+                });
+            }
+        }
+
         self.local_variables.insert_symbol(&stmt.name, stmt);
-        self.visit_expression(
-            stmt.init_exp
-                .as_mut()
-                .expect("variable declaration has no init type!"),
-        )?;
+        if let Some(kind) = &mut stmt.declaration_type {
+            self.visit_type_kind(kind)?;
+        }
+
+        // Extern declarations have no initializer
+        if let Some(init_exp) = &mut stmt.init_exp {
+            self.visit_expression(init_exp)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_defer(&mut self, expr: &'ast mut Expression) -> Result<(), BinderError> {
+        self.visit_expression(expr)?;
+
+        let mut collector = LocalVariablesCollector::default();
+        collector.visit_expression(expr).expect("Never fails");
+        for name in collector.names {
+            self.deferred_variables.insert_symbol(&name, ());
+        }
 
         Ok(())
     }
@@ -97,14 +268,14 @@ impl<'ast> MutableVisitor<'ast, BinderError> for Binder {
     fn visit_if(&mut self, stmt: &'ast mut IfStatement) -> Result<(), BinderError> {
         self.visit_expression(&mut stmt.condition)?;
 
-        self.local_variables.new_scope();
+        self.new_scope();
         self.visit_statements_vec(&mut stmt.then_clause.statements)?;
-        self.local_variables.delete_scope();
+        self.delete_scope();
 
         if let Some(else_clause) = &mut stmt.else_clause {
-            self.local_variables.new_scope();
+            self.new_scope();
             self.visit_statements_vec(&mut else_clause.statements)?;
-            self.local_variables.delete_scope();
+            self.delete_scope();
         }
 
         Ok(())
@@ -117,6 +288,13 @@ impl<'ast> MutableVisitor<'ast, BinderError> for Binder {
         self.visit_statements_vec(&mut stmt.body.statements)?;
         self.end_loop();
 
+        // The else clause is not part of the loop
+        if let Some(else_clause) = &mut stmt.else_clause {
+            self.new_scope();
+            self.visit_statements_vec(&mut else_clause.statements)?;
+            self.delete_scope();
+        }
+
         Ok(())
     }
 
@@ -135,8 +313,10 @@ impl<'ast> MutableVisitor<'ast, BinderError> for Binder {
 
     fn visit_return(&mut self, stmt: &'ast mut ReturnStatement) -> Result<(), BinderError> {
         if !self.in_function {
+            // Point at the `return` keyword, the statement itself is fine
+            let begin = stmt.get_location().begin;
             Err(BinderError::BadReturn {
-                location: stmt.get_location().clone(),
+                location: TokenLocation::new(begin, begin + "return".len()),
             })
         } else {
             if let Some(ref mut exp) = stmt.exp {
@@ -169,14 +349,18 @@ impl<'ast> MutableVisitor<'ast, BinderError> for Binder {
 
     fn visit_literal(&mut self, expr: &'ast mut Literal) -> Result<(), BinderError> {
         match &expr.literal_type {
+            // A function name used as a value is a pointer to the function
             LiteralType::Identifier(name) => match self.local_variables.find_symbol(name) {
                 Some(var) => expr.set_definition(Definition::LocalVariable(*var)),
-                None => {
-                    return Err(BinderError::UndeclaredVariable {
-                        location: expr.get_location().clone(),
-                        name: name.clone(),
-                    })
-                }
+                None => match self.functions_statements.get(name) {
+                    Some(f) => expr.set_definition(Definition::Function(*f)),
+                    None => {
+                        return Err(BinderError::UndeclaredVariable {
+                            location: expr.get_location().clone(),
+                            name: name.clone(),
+                        })
+                    }
+                },
             },
             LiteralType::ArrayAccess(array_access)
                 if Self::is_subscriptable(&array_access.identifier) =>
@@ -203,6 +387,27 @@ impl<'ast> MutableVisitor<'ast, BinderError> for Binder {
                     },
                 }
             }
+            LiteralType::EnumVariant { enum_name, variant } => {
+                let declaration = match self.enum_statements.get(enum_name) {
+                    Some(declaration) => *declaration,
+                    None => {
+                        return Err(BinderError::UndeclaredEnum {
+                            location: expr.get_location().clone(),
+                            name: enum_name.clone(),
+                        })
+                    }
+                };
+
+                if unsafe { &*declaration }.discriminant(variant).is_none() {
+                    return Err(BinderError::UndeclaredEnumVariant {
+                        location: expr.get_location().clone(),
+                        name: enum_name.clone(),
+                        variant: variant.clone(),
+                    });
+                }
+
+                expr.set_definition(Definition::Enum(declaration));
+            }
             LiteralType::ArrayAccess(_) => {
                 return Err(BinderError::NotSubscriptable {
                     location: expr.get_location().clone(),
@@ -221,17 +426,27 @@ impl<'ast> MutableVisitor<'ast, BinderError> for Binder {
     }
 
     fn visit_call(&mut self, expr: &'ast mut Call) -> Result<(), BinderError> {
-        let declaration = self.functions_statements.get(&expr.callee);
-        if declaration.is_none() {
+        if expr.is_method_call {
+            self.resolve_method_call(expr)?;
+        }
+
+        // Variables and structs are bound as well so the type checker can
+        // report they are not callable
+        if let Some(dec) = self.functions_statements.get(&expr.callee) {
+            expr.set_definition(Definition::Function(*dec));
+        } else if let Some(builtin) = Builtin::from_name(&expr.callee) {
+            expr.set_definition(Definition::Builtin(builtin));
+        } else if let Some(var) = self.local_variables.find_symbol(&expr.callee) {
+            expr.set_definition(Definition::LocalVariable(*var));
+        } else if let Some(dec) = self.struct_statement.get(&expr.callee) {
+            expr.set_definition(Definition::Struct(*dec));
+        } else {
             return Err(BinderError::UndeclaredFunction {
                 location: expr.get_location().clone(),
                 name: expr.callee.to_string(),
             });
         }
 
-        let dec = *declaration.unwrap();
-        expr.set_definition(Definition::Function(dec));
-
         for arg in &mut expr.arguments {
             self.visit_expression(arg)?;
         }
@@ -239,6 +454,44 @@ impl<'ast> MutableVisitor<'ast, BinderError> for Binder {
         Ok(())
     }
 
+    fn visit_alignof(&mut self, expr: &'ast mut AlignOf) -> Result<(), BinderError> {
+        self.visit_type_kind(&mut expr.aligned_type.kind)?;
+        self.visit_type(&mut expr.aligned_type)
+    }
+
+    fn visit_type_kind(&mut self, kind: &'ast mut TypeKind) -> Result<(), BinderError> {
+        // Array sizes may refer to constants
+        match kind {
+            TypeKind::Array { size, array_type } => {
+                self.visit_expression(&mut size.expr)?;
+                self.visit_type_kind(&mut array_type.kind)
+            }
+            TypeKind::Ptr(pointee) => self.visit_type_kind(&mut pointee.kind),
+            TypeKind::Function {
+                parameters,
+                return_type,
+            } => {
+                for parameter in parameters.iter_mut() {
+                    self.visit_type_kind(&mut parameter.kind)?;
+                }
+
+                self.visit_type_kind(&mut return_type.kind)
+            }
+            TypeKind::Tuple(elements) => {
+                for element in elements.iter_mut() {
+                    self.visit_type_kind(&mut element.kind)?;
+                }
+
+                Ok(())
+            }
+            TypeKind::Identifier(name) if self.enum_statements.contains_key(name) => {
+                *kind = TypeKind::Enum(name.clone());
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn visit_type(&mut self, expr: &'ast mut Type) -> Result<(), BinderError> {
         match &expr.kind {
             TypeKind::Identifier(name) => {
@@ -0,0 +1,341 @@
+use crate::ast::{
+    AlignOf, Assignment, BinaryOperation, Bindable, Definition, Expression, FunctionStatement,
+    GlobalStatement, LetStatement, Literal, LiteralType, Locatable, MutableVisitor, OpType,
+    TypeKind,
+};
+
+use super::{errors::TypeCheckerError, Typable};
+
+/// A value known at compile time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConstantValue {
+    Integer(i128),
+    Bool(bool),
+}
+
+impl ConstantValue {
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            ConstantValue::Integer(value) => Some(*value),
+            ConstantValue::Bool(_) => None,
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            ConstantValue::Bool(value) => Some(*value),
+            ConstantValue::Integer(_) => None,
+        }
+    }
+
+    fn to_literal_type(self) -> LiteralType {
+        match self {
+            ConstantValue::Integer(value) => LiteralType::Integer(value),
+            ConstantValue::Bool(true) => LiteralType::True,
+            ConstantValue::Bool(false) => LiteralType::False,
+        }
+    }
+}
+
+/// Try to evaluate an expression at compile time. Returns `None` if the
+/// expression is not constant (calls, non `const` variables...) or if its
+/// evaluation fails (overflow, division by zero).
+pub fn fold_expression(expr: &Expression) -> Option<ConstantValue> {
+    match expr {
+        Expression::Group(group) => fold_expression(&group.expr),
+        Expression::Literal(literal) => fold_literal(literal),
+        Expression::BinaryOperation(operation) => fold_binary_operation(operation),
+        _ => None,
+    }
+}
+
+fn fold_literal(literal: &Literal) -> Option<ConstantValue> {
+    match &literal.literal_type {
+        LiteralType::True => Some(ConstantValue::Bool(true)),
+        LiteralType::False => Some(ConstantValue::Bool(false)),
+        LiteralType::Integer(value) => Some(ConstantValue::Integer(*value)),
+        // Identifiers are only folded once bound to a `const` declaration
+        LiteralType::Identifier(_) => match literal.definition {
+            Some(Definition::LocalVariable(_)) => {
+                let declaration = literal.get_local_variable_def();
+                if declaration.is_const {
+                    fold_expression(declaration.init_exp.as_ref()?)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn fold_binary_operation(operation: &BinaryOperation) -> Option<ConstantValue> {
+    let left = fold_expression(&operation.left)?;
+
+    let right = match &operation.right {
+        Some(right) => fold_expression(right)?,
+        None => {
+            return match operation.op {
+                OpType::Minus => Some(ConstantValue::Integer(left.as_integer()?.checked_neg()?)),
+                OpType::Not => Some(ConstantValue::Bool(!left.as_bool()?)),
+                _ => None,
+            };
+        }
+    };
+
+    match (left, right) {
+        (ConstantValue::Integer(l), ConstantValue::Integer(r)) => match operation.op {
+            OpType::Plus => l.checked_add(r).map(ConstantValue::Integer),
+            OpType::Minus => l.checked_sub(r).map(ConstantValue::Integer),
+            OpType::Multiply => l.checked_mul(r).map(ConstantValue::Integer),
+            OpType::Divide => l.checked_div(r).map(ConstantValue::Integer),
+            OpType::Modulo => l.checked_rem(r).map(ConstantValue::Integer),
+            OpType::Equal => Some(ConstantValue::Bool(l == r)),
+            OpType::Different => Some(ConstantValue::Bool(l != r)),
+            OpType::Less => Some(ConstantValue::Bool(l < r)),
+            OpType::LessEqual => Some(ConstantValue::Bool(l <= r)),
+            OpType::More => Some(ConstantValue::Bool(l > r)),
+            OpType::MoreEqual => Some(ConstantValue::Bool(l >= r)),
+            _ => None,
+        },
+        (ConstantValue::Bool(l), ConstantValue::Bool(r)) => match operation.op {
+            OpType::And => Some(ConstantValue::Bool(l && r)),
+            OpType::Or => Some(ConstantValue::Bool(l || r)),
+            OpType::Equal => Some(ConstantValue::Bool(l == r)),
+            OpType::Different => Some(ConstantValue::Bool(l != r)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Float, string and `null` literals aren't folded but they are constants a
+/// static can be initialized with.
+fn is_constant_literal(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::Literal(Literal {
+            literal_type: LiteralType::Float(_) | LiteralType::String(_) | LiteralType::Null(_),
+            ..
+        })
+    )
+}
+
+/// Checks `const` declarations and replaces every use of a constant by its
+/// value. This pass runs once types are fully inferred, the substituted
+/// literals keep the type of the constant they replace.
+#[derive(Default)]
+pub(crate) struct ConstantFolder;
+
+impl ConstantFolder {
+    pub fn fold_statements(
+        &mut self,
+        stmts: &mut [GlobalStatement],
+    ) -> Result<(), TypeCheckerError> {
+        for stmt in stmts {
+            self.visit_global_statement(stmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'ast> MutableVisitor<'ast, TypeCheckerError> for ConstantFolder {
+    fn visit_expression(&mut self, expr: &'ast mut Expression) -> Result<(), TypeCheckerError> {
+        // `-<literal>` is replaced by a single negative literal. Its type was
+        // already range checked as a signed constant.
+        if let Expression::BinaryOperation(BinaryOperation {
+            op: OpType::Minus,
+            left,
+            right: None,
+            ..
+        }) = &mut *expr
+        {
+            if let Expression::Literal(
+                literal @ Literal {
+                    literal_type: LiteralType::Integer(_),
+                    ..
+                },
+            ) = left.as_mut()
+            {
+                if let LiteralType::Integer(value) = literal.literal_type {
+                    literal.literal_type = LiteralType::Integer(-value);
+                }
+
+                *expr = Expression::Literal(literal.clone());
+                return Ok(());
+            }
+        }
+
+        match expr {
+            Expression::Group(group) => self.visit_expression(&mut group.expr),
+            Expression::BinaryOperation(operation) => self.visit_binary_operation(operation),
+            Expression::Literal(literal) => self.visit_literal(literal),
+            Expression::Call(call) => self.visit_call(call),
+            Expression::IndirectCall(call) => self.visit_indirect_call(call),
+            Expression::Assignment(assignment) => self.visit_assignment(assignment),
+            Expression::ArrayInitializer(initializer) => self.visit_array_initializer(initializer),
+            Expression::AddrOf(addrof) => self.visit_addrof(addrof),
+            Expression::Deref(deref) => self.visit_deref(deref),
+            Expression::Slice(slice) => self.visit_slice(slice),
+            Expression::AlignOf(alignof) => self.visit_alignof(alignof),
+            Expression::Tuple(tuple) => self.visit_tuple(tuple),
+            Expression::TryNull(try_null) => self.visit_try_null(try_null),
+        }
+    }
+
+    fn visit_let(&mut self, stmt: &'ast mut LetStatement) -> Result<(), TypeCheckerError> {
+        // Statics are initialized before the program runs
+        if stmt.is_const || stmt.is_static {
+            let init_exp = stmt.init_exp.as_ref().expect("Constant has no init exp");
+
+            match fold_expression(init_exp) {
+                Some(ConstantValue::Integer(value)) if !stmt.get_type().can_hold_integer(value) => {
+                    return Err(TypeCheckerError::IntegerLiteralOutOfRange {
+                        value,
+                        ty: stmt.get_type().clone(),
+                    });
+                }
+                Some(_) => (),
+                None if stmt.is_static && is_constant_literal(init_exp) => (),
+                None if stmt.is_static => {
+                    return Err(TypeCheckerError::NonConstantStaticInitializer {
+                        name: stmt.name.clone(),
+                    })
+                }
+                None => {
+                    return Err(TypeCheckerError::NonConstantInitializer {
+                        name: stmt.name.clone(),
+                    })
+                }
+            }
+        }
+
+        match &mut stmt.init_exp {
+            Some(init_exp) => self.visit_expression(init_exp),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_assignment(&mut self, expr: &'ast mut Assignment) -> Result<(), TypeCheckerError> {
+        // The assigned variable is a location and must not be replaced by a value
+        match expr.left.as_ref() {
+            Expression::Literal(Literal {
+                literal_type: LiteralType::Identifier(_),
+                ..
+            }) => (),
+            _ => self.visit_expression(&mut expr.left)?,
+        }
+
+        self.visit_expression(&mut expr.right)
+    }
+
+    fn visit_literal(&mut self, expr: &'ast mut Literal) -> Result<(), TypeCheckerError> {
+        match &mut expr.literal_type {
+            LiteralType::Identifier(_) => {
+                if let Some(value) = fold_literal(expr) {
+                    expr.literal_type = value.to_literal_type();
+                }
+
+                Ok(())
+            }
+            LiteralType::ArrayAccess(aa) => {
+                self.visit_expression(&mut aa.identifier)?;
+                self.visit_expression(&mut aa.index)?;
+
+                // Non constant indices can only be checked at runtime
+                match fold_expression(&aa.index) {
+                    Some(ConstantValue::Integer(value)) if value < 0 => {
+                        Err(TypeCheckerError::NegativeArrayIndex {
+                            value,
+                            location: expr.get_location().clone(),
+                        })
+                    }
+                    _ => Ok(()),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Folds every array size found in type annotations. This pass runs before
+/// type checking as array types can't be built until their size is known.
+#[derive(Default)]
+pub(crate) struct ArraySizeFolder;
+
+impl ArraySizeFolder {
+    pub fn fold_statements(
+        &mut self,
+        stmts: &mut [GlobalStatement],
+    ) -> Result<(), TypeCheckerError> {
+        for stmt in stmts {
+            self.visit_global_statement(stmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'ast> MutableVisitor<'ast, TypeCheckerError> for ArraySizeFolder {
+    fn visit_function(
+        &mut self,
+        stmt: &'ast mut FunctionStatement,
+    ) -> Result<(), TypeCheckerError> {
+        for parameter in &mut stmt.parameters {
+            if let Some(kind) = &mut parameter.declaration_type {
+                self.visit_type_kind(kind)?;
+            }
+        }
+        self.visit_type_kind(&mut stmt.return_type)?;
+
+        match &mut stmt.body {
+            Some(body) => self.visit_statements(body),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_alignof(&mut self, expr: &'ast mut AlignOf) -> Result<(), TypeCheckerError> {
+        self.visit_type_kind(&mut expr.aligned_type.kind)
+    }
+
+    fn visit_type_kind(&mut self, kind: &'ast mut TypeKind) -> Result<(), TypeCheckerError> {
+        match kind {
+            TypeKind::Array { size, array_type } => {
+                match fold_expression(&size.expr)
+                    .and_then(|value| value.as_integer())
+                    .and_then(|value| u32::try_from(value).ok())
+                {
+                    Some(value) => size.value = Some(value),
+                    None => {
+                        return Err(TypeCheckerError::NonConstantArraySize(
+                            size.get_location().clone(),
+                        ))
+                    }
+                }
+
+                self.visit_type_kind(&mut array_type.kind)
+            }
+            TypeKind::Ptr(pointee) => self.visit_type_kind(&mut pointee.kind),
+            TypeKind::Function {
+                parameters,
+                return_type,
+            } => {
+                for parameter in parameters.iter_mut() {
+                    self.visit_type_kind(&mut parameter.kind)?;
+                }
+
+                self.visit_type_kind(&mut return_type.kind)
+            }
+            TypeKind::Tuple(elements) => {
+                for element in elements.iter_mut() {
+                    self.visit_type_kind(&mut element.kind)?;
+                }
+
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
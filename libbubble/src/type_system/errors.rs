@@ -1,49 +1,151 @@
 use thiserror::Error;
 
-use crate::ast::{Definition, OpType, TokenLocation};
+use crate::ast::{OpType, TokenLocation};
 
 use super::Type;
 
+/// Conditions are never implicitly tested, suggest the explicit test of the
+/// types users often expect to be accepted
+fn bool_condition_hint(ty: &Type) -> &'static str {
+    match ty {
+        Type::Ptr(_) => ", use `not is_null(...)` to check that a pointer isn't null",
+        ty if ty.is_integer() => ", use `... != 0` to check that an integer isn't zero",
+        _ => "",
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum TypeCheckerError {
-    #[error("{left:?} cannot be initialized with {right:?}")]
+    #[error("{left} cannot be initialized with {right}")]
     BadInit { left: Type, right: Type },
-    #[error("condition should be of type bool but is {0:?}")]
-    NonBoolCondition(Type),
-    #[error("{left:?} cannot be assigned to {right:?}")]
+    #[error("condition should be of type bool but is {ty}{}", bool_condition_hint(.ty))]
+    NonBoolCondition { ty: Type, location: TokenLocation },
+    #[error("{left} cannot be assigned to {right}")]
     BadAssigment { left: Type, right: Type },
-    #[error("{0:?} is not callable")]
-    NotCallable(Definition),
+    #[error("`{name}` is not a function")]
+    NotCallable {
+        name: String,
+        location: TokenLocation,
+    },
     #[error("Expected {expected} parameters but got {got}")]
     BadParameterCount { expected: u32, got: u32 },
-    #[error("Expected type {expected_type:?} as parameter '{name}' but got {got:?}")]
+    #[error("Expected type {expected_type} as parameter '{name}' but got {got}")]
     BadParameter {
         name: String,
         expected_type: Type,
         got: Type,
     },
-    #[error("Cannot apply {operator:?} between {left_ty:?} and {right_ty:?}")]
+    #[error("Cannot apply {operator:?} between {left_ty} and {right_ty}")]
     IncompatibleOperationType {
         operator: OpType,
         left_ty: Type,
         right_ty: Type,
     },
-    #[error("Function return type is {expected:?} but a {got:?} type is returned")]
-    ReturnTypeMismatch { got: Type, expected: Type },
+    #[error("Function return type is {expected} but a {got} type is returned")]
+    ReturnTypeMismatch {
+        got: Type,
+        expected: Type,
+        location: TokenLocation,
+    },
     #[error("Can't infer a proper type to the variable. Please, add a type annotation")]
     InferenceError(TokenLocation),
-    #[error("Different type in array initializer. Fisrt type is: {first:?} but found {found:?} at position {position}")]
+    #[error("Different type in array initializer. Fisrt type is: {first} but found {found} at position {position}")]
     DifferentTypeInArrayInitializer {
         first: Type,
         found: Type,
         position: u32,
     },
-    #[error("Type {ty:?} is not subscriptable")]
+    #[error("Type {ty} is not subscriptable")]
     NonSubscriptable { ty: Type },
-    #[error("Index type is not integer like. Got: {got:?}")]
+    #[error("Index type is not integer like. Got: {got}")]
     IndexNotInteger { got: Type },
-    #[error("Deref a non pointer type: {0:?}.")]
+    #[error("Deref a non pointer type: {0}.")]
     DerefNonPointer(Type),
+    #[error("Integer literal {value} does not fit in type {ty}")]
+    IntegerLiteralOutOfRange { value: i128, ty: Type },
+    #[error("Constant '{name}' must be initialized with a constant expression")]
+    NonConstantInitializer { name: String },
+    #[error("Static variable '{name}' must be initialized with a constant expression")]
+    NonConstantStaticInitializer { name: String },
+    #[error("Cannot assign to constant '{name}'")]
+    ConstantAssignment { name: String },
+    #[error("Array size must be a constant non negative integer")]
+    NonConstantArraySize(TokenLocation),
+    #[error("Local array '{name}' takes {size} bytes which exceeds the {max} bytes stack limit. Consider allocating it on the heap")]
+    StackAllocationTooLarge { name: String, size: u64, max: u64 },
+    #[error("Local array '{name}' has a zero size")]
+    ZeroSizeArray { name: String },
+    #[error("'{name}' expects two numbers of the same type but got {left} and {right}")]
+    BadBuiltinArguments {
+        name: String,
+        left: Type,
+        right: Type,
+    },
+    #[error("'{name}' expects a pointer but got {ty}")]
+    ExpectedPointerArgument { name: String, ty: Type },
+    #[error("'{name}' expects a string literal")]
+    ExpectedStringLiteral {
+        name: String,
+        location: TokenLocation,
+    },
+    #[error("Function '{name}' does not return a value on every path")]
+    MissingReturn { name: String },
+    #[error("Strings can't be concatenated with '+' as there is no allocator to store the result")]
+    StringConcatenation,
+    #[error("Cannot destructure {ty} into {expected} variables")]
+    BadDestructuring {
+        expected: usize,
+        ty: Type,
+        location: TokenLocation,
+    },
+    #[error("Variable '{name}' may be used before being initialized")]
+    UseOfUninitialized {
+        name: String,
+        location: TokenLocation,
+    },
+    #[error("Array index {value} is negative")]
+    NegativeArrayIndex {
+        value: i128,
+        location: TokenLocation,
+    },
+    #[error("Type {ty} has no alignment")]
+    NoAlignment { ty: Type, location: TokenLocation },
+    #[error("Unknown attribute '@{name}'")]
+    UnknownAttribute {
+        name: String,
+        location: TokenLocation,
+    },
+    #[error("Function '{name}' can't be both '@inline' and '@noinline'")]
+    ConflictingAttributes {
+        name: String,
+        location: TokenLocation,
+    },
+    #[error("'?' expects a pointer but got {ty}")]
+    TryNullNonPointer { ty: Type, location: TokenLocation },
+    #[error("'?' can only be used in a function returning a pointer")]
+    TryNullOutsidePointerFunction { location: TokenLocation },
+    #[error("Can't infer the integer type of the expression")]
+    UninferredInteger { location: TokenLocation },
+    #[error("'assert' can't report failures, '{name}' must be declared as `{expected}`")]
+    IncompatibleAssertFunction {
+        name: String,
+        expected: String,
+        location: TokenLocation,
+    },
+    #[error("{ty} is not a function pointer")]
+    NotAFunctionPointer { ty: Type, location: TokenLocation },
+    #[error("Function '{name}' uses the \"{abi}\" calling convention and can't be called through a pointer")]
+    NonCFunctionPointer {
+        name: String,
+        abi: &'static str,
+        location: TokenLocation,
+    },
+    #[error("Variadic function '{name}' must use the \"C\" calling convention, not \"{abi}\"")]
+    NonCVariadicFunction {
+        name: String,
+        abi: &'static str,
+        location: TokenLocation,
+    },
 }
 
 impl PartialEq for TypeCheckerError {
@@ -53,18 +155,21 @@ impl PartialEq for TypeCheckerError {
             (
                 TypeCheckerError::DerefNonPointer(_),
                 TypeCheckerError::DerefNonPointer(_),
+            ) | (
+                TypeCheckerError::NonConstantArraySize(_),
+                TypeCheckerError::NonConstantArraySize(_),
             ) | (
                 TypeCheckerError::BadInit { .. },
                 TypeCheckerError::BadInit { .. }
             ) | (
-                TypeCheckerError::NonBoolCondition(_),
-                TypeCheckerError::NonBoolCondition(_)
+                TypeCheckerError::NonBoolCondition { .. },
+                TypeCheckerError::NonBoolCondition { .. }
             ) | (
                 TypeCheckerError::BadAssigment { .. },
                 TypeCheckerError::BadAssigment { .. }
             ) | (
-                TypeCheckerError::NotCallable(_),
-                TypeCheckerError::NotCallable(_)
+                TypeCheckerError::NotCallable { .. },
+                TypeCheckerError::NotCallable { .. }
             ) | (
                 TypeCheckerError::BadParameterCount { .. },
                 TypeCheckerError::BadParameterCount { .. },
@@ -86,6 +191,81 @@ impl PartialEq for TypeCheckerError {
             ) | (
                 TypeCheckerError::NonSubscriptable { .. },
                 TypeCheckerError::NonSubscriptable { .. },
+            ) | (
+                TypeCheckerError::IndexNotInteger { .. },
+                TypeCheckerError::IndexNotInteger { .. },
+            ) | (
+                TypeCheckerError::IntegerLiteralOutOfRange { .. },
+                TypeCheckerError::IntegerLiteralOutOfRange { .. },
+            ) | (
+                TypeCheckerError::NonConstantInitializer { .. },
+                TypeCheckerError::NonConstantInitializer { .. },
+            ) | (
+                TypeCheckerError::NonConstantStaticInitializer { .. },
+                TypeCheckerError::NonConstantStaticInitializer { .. },
+            ) | (
+                TypeCheckerError::ConstantAssignment { .. },
+                TypeCheckerError::ConstantAssignment { .. },
+            ) | (
+                TypeCheckerError::StackAllocationTooLarge { .. },
+                TypeCheckerError::StackAllocationTooLarge { .. },
+            ) | (
+                TypeCheckerError::ZeroSizeArray { .. },
+                TypeCheckerError::ZeroSizeArray { .. },
+            ) | (
+                TypeCheckerError::BadBuiltinArguments { .. },
+                TypeCheckerError::BadBuiltinArguments { .. },
+            ) | (
+                TypeCheckerError::ExpectedPointerArgument { .. },
+                TypeCheckerError::ExpectedPointerArgument { .. },
+            ) | (
+                TypeCheckerError::ExpectedStringLiteral { .. },
+                TypeCheckerError::ExpectedStringLiteral { .. },
+            ) | (
+                TypeCheckerError::MissingReturn { .. },
+                TypeCheckerError::MissingReturn { .. },
+            ) | (
+                TypeCheckerError::StringConcatenation,
+                TypeCheckerError::StringConcatenation,
+            ) | (
+                TypeCheckerError::BadDestructuring { .. },
+                TypeCheckerError::BadDestructuring { .. },
+            ) | (
+                TypeCheckerError::UseOfUninitialized { .. },
+                TypeCheckerError::UseOfUninitialized { .. },
+            ) | (
+                TypeCheckerError::NegativeArrayIndex { .. },
+                TypeCheckerError::NegativeArrayIndex { .. },
+            ) | (
+                TypeCheckerError::NoAlignment { .. },
+                TypeCheckerError::NoAlignment { .. },
+            ) | (
+                TypeCheckerError::UnknownAttribute { .. },
+                TypeCheckerError::UnknownAttribute { .. },
+            ) | (
+                TypeCheckerError::ConflictingAttributes { .. },
+                TypeCheckerError::ConflictingAttributes { .. },
+            ) | (
+                TypeCheckerError::TryNullNonPointer { .. },
+                TypeCheckerError::TryNullNonPointer { .. },
+            ) | (
+                TypeCheckerError::TryNullOutsidePointerFunction { .. },
+                TypeCheckerError::TryNullOutsidePointerFunction { .. },
+            ) | (
+                TypeCheckerError::UninferredInteger { .. },
+                TypeCheckerError::UninferredInteger { .. },
+            ) | (
+                TypeCheckerError::NotAFunctionPointer { .. },
+                TypeCheckerError::NotAFunctionPointer { .. },
+            ) | (
+                TypeCheckerError::NonCFunctionPointer { .. },
+                TypeCheckerError::NonCFunctionPointer { .. },
+            ) | (
+                TypeCheckerError::NonCVariadicFunction { .. },
+                TypeCheckerError::NonCVariadicFunction { .. },
+            ) | (
+                TypeCheckerError::IncompatibleAssertFunction { .. },
+                TypeCheckerError::IncompatibleAssertFunction { .. },
             )
         )
     }
@@ -103,6 +283,17 @@ pub enum BinderError {
         location: TokenLocation,
         name: String,
     },
+    #[error("undeclared enum {name:?}")]
+    UndeclaredEnum {
+        location: TokenLocation,
+        name: String,
+    },
+    #[error("enum {name:?} has no variant {variant:?}")]
+    UndeclaredEnumVariant {
+        location: TokenLocation,
+        name: String,
+        variant: String,
+    },
     #[error("undeclared function {name:?}")]
     UndeclaredFunction {
         location: TokenLocation,
@@ -116,4 +307,13 @@ pub enum BinderError {
     BadContinue { location: TokenLocation },
     #[error("Not subscriptable expression")]
     NotSubscriptable { location: TokenLocation },
+    #[error(
+        "Methods can only be called on variables with a struct or struct pointer type annotation"
+    )]
+    InvalidMethodReceiver { location: TokenLocation },
+    #[error("{name:?} shadows a variable read by a deferred expression")]
+    ShadowedDeferredVariable {
+        location: TokenLocation,
+        name: String,
+    },
 }
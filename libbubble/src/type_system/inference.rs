@@ -45,12 +45,39 @@ impl<'ast, 'ty> MutableVisitor<'ast, Infallible> for ExpressionTypeSetter<'ty> {
         Ok(())
     }
 
+    fn visit_group(&mut self, expr: &'ast mut Group) -> Result<(), Infallible> {
+        self.visit_expression(&mut expr.expr)?;
+        expr.set_type(self.new_type.clone());
+        Ok(())
+    }
+
+    /// The type of a dereferenced pointer comes from the pointer which must
+    /// not take the pointee type
+    fn visit_deref(&mut self, _: &'ast mut Deref) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    /// Like a dereferenced pointer, the callee and the arguments of an
+    /// indirect call don't have the type of its result
+    fn visit_indirect_call(&mut self, expr: &'ast mut IndirectCall) -> Result<(), Infallible> {
+        expr.set_type(self.new_type.clone());
+        Ok(())
+    }
+
     fn visit_assignment(&mut self, expr: &'ast mut Assignment) -> Result<(), Infallible> {
         expr.set_type(self.new_type.clone());
         Ok(())
     }
 
     fn visit_call(&mut self, expr: &'ast mut Call) -> Result<(), Infallible> {
+        // Builtins like `min` return a value of their arguments type
+        if matches!(expr.get_definition(), Definition::Builtin(builtin) if builtin.returns_argument_type())
+        {
+            for argument in expr.arguments.iter_mut() {
+                self.visit_expression(argument)?;
+            }
+        }
+
         expr.set_type(self.new_type.clone());
         Ok(())
     }
@@ -70,15 +97,47 @@ impl<'ast, 'ty> MutableVisitor<'ast, Infallible> for ExpressionTypeSetter<'ty> {
 
         Ok(())
     }
+
+    fn visit_tuple(&mut self, expr: &'ast mut Tuple) -> Result<(), Infallible> {
+        // Each element takes the type at its position in the tuple
+        if let Type::Tuple(element_types) = self.new_type {
+            for (value, ty) in expr.values.iter_mut().zip(element_types) {
+                ExpressionTypeSetter::new(ty).set_type_recusively(value);
+            }
+        }
+
+        expr.set_type(self.new_type.clone());
+        Ok(())
+    }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub(crate) struct IntegerInference {
     current_function: Option<Type>,
     is_int: bool,
+    /// Pointer sized integer type given to untyped array indexes and slice
+    /// bounds
+    index_type: Type,
 }
 
 impl IntegerInference {
+    /// Creates an inference pass for a target whose pointers are
+    /// `pointer_width` bits wide.
+    pub fn with_pointer_width(pointer_width: u32) -> Self {
+        let index_type = match pointer_width {
+            16 => Type::I16,
+            32 => Type::I32,
+            64 => Type::I64,
+            _ => unreachable!("Unsupported pointer width: {pointer_width}"),
+        };
+
+        Self {
+            current_function: None,
+            is_int: false,
+            index_type,
+        }
+    }
+
     pub fn infer_statements(
         &mut self,
         stmts: &mut [GlobalStatement],
@@ -118,11 +177,42 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for IntegerInference {
                 Ok(())
             }
             GlobalStatement::Struct(s) => self.visit_struct(s),
+            GlobalStatement::Enum(e) => self.visit_enum(e),
             GlobalStatement::Let(l) => self.visit_let(l),
         }
     }
 
+    fn visit_statements(&mut self, stmts: &'ast mut Statements) -> Result<(), TypeCheckerError> {
+        self.visit_statements_vec(&mut stmts.statements)
+    }
+
+    fn visit_statements_vec(
+        &mut self,
+        stmts: &'ast mut [Statement],
+    ) -> Result<(), TypeCheckerError> {
+        for stmt in stmts {
+            self.visit_statement_kind(&mut stmt.kind)?;
+
+            // The value of an expression statement, deferred or not, is
+            // discarded. Nothing gives it a type so an integer one defaults to
+            // i64 like literal operands
+            if let StatementKind::Expression { expr, .. } | StatementKind::Defer(expr) =
+                &mut stmt.kind
+            {
+                if let Type::Int = expr.get_type() {
+                    ExpressionTypeSetter::new(&Type::I64).set_type_recusively(expr);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn visit_let(&mut self, stmt: &'ast mut LetStatement) -> Result<(), TypeCheckerError> {
+        if stmt.is_extern || stmt.init_exp.is_none() {
+            return Ok(());
+        }
+
         self.visit_expression(
             stmt.init_exp
                 .as_mut()
@@ -155,7 +245,58 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for IntegerInference {
         }
     }
 
+    fn visit_let_tuple(
+        &mut self,
+        stmt: &'ast mut LetTupleStatement,
+    ) -> Result<(), TypeCheckerError> {
+        self.visit_expression(&mut stmt.init_exp)?;
+
+        // Destructured variables have no type annotation an integer literal
+        // could take
+        if self.is_int {
+            return Err(TypeCheckerError::InferenceError(
+                stmt.get_location().clone(),
+            ));
+        }
+
+        Ok(())
+    }
+
     fn visit_call(&mut self, expr: &'ast mut Call) -> Result<(), TypeCheckerError> {
+        // `is_null` and `len` arguments aren't integers but they may contain
+        // expressions to infer, e.g. `is_null(malloc(4))`
+        if let Definition::Builtin(Builtin::IsNull | Builtin::Len) = expr.get_definition() {
+            for argument in expr.arguments.iter_mut() {
+                self.visit_expression(argument)?;
+            }
+
+            self.is_int = false;
+            return Ok(());
+        }
+
+        // Builtins arguments have the same type. An integer literal takes the
+        // type of the other argument.
+        if expr.get_definition().is_builtin() {
+            self.visit_expression(&mut expr.arguments[0])?;
+            let is_int_left = self.is_int;
+            self.visit_expression(&mut expr.arguments[1])?;
+            let is_int_right = self.is_int;
+
+            let (arguments_ty, int_argument) = match (is_int_left, is_int_right) {
+                (true, false) => (expr.arguments[1].get_type().clone(), 0),
+                (false, true) => (expr.arguments[0].get_type().clone(), 1),
+                _ => {
+                    self.is_int = is_int_left && is_int_right;
+                    return Ok(());
+                }
+            };
+
+            let mut setter = ExpressionTypeSetter::new(&arguments_ty);
+            setter.set_type_recusively(&mut expr.arguments[int_argument]);
+            self.is_int = false;
+            return Ok(());
+        }
+
         if expr.get_definition().is_function() {
             for i in 0..expr.arguments.len() {
                 self.visit_expression(expr.arguments.get_mut(i).expect("unreachable"))?;
@@ -165,20 +306,19 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for IntegerInference {
                     continue;
                 }
 
-                let expected_type = expr
-                    .get_function_def()
-                    .parameters
-                    .get(i)
-                    .unwrap()
-                    .ty
-                    .clone()
-                    .unwrap();
+                // Int arguments passed to the variadic part of a function are
+                // given the C `int` type
+                let expected_type = match expr.get_function_def().parameters.get(i) {
+                    Some(parameter) => parameter.ty.clone().unwrap(),
+                    None => Type::I32,
+                };
 
-                if !expected_type.is_compatible_with(&Type::Int) {
+                let argument_type = expr.arguments[i].get_type();
+                if !expected_type.is_compatible_with(argument_type) {
                     return Err(TypeCheckerError::BadParameter {
                         name: expr.get_function_def().name.clone(),
                         expected_type,
-                        got: Type::Int,
+                        got: argument_type.clone(),
                     });
                 }
 
@@ -187,12 +327,44 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for IntegerInference {
                 self.is_int = false;
             }
 
+            // A call without arguments must not keep the state of the
+            // previous expression
+            self.is_int = false;
             Ok(())
         } else {
-            Err(TypeCheckerError::NotCallable(expr.get_definition().clone()))
+            Err(TypeCheckerError::NotCallable {
+                name: expr.callee.clone(),
+                location: expr.get_location().clone(),
+            })
         }
     }
 
+    fn visit_indirect_call(
+        &mut self,
+        expr: &'ast mut IndirectCall,
+    ) -> Result<(), TypeCheckerError> {
+        self.visit_expression(&mut expr.callee)?;
+
+        let parameters = match expr.callee.get_type() {
+            Type::Ptr(pointee) => match pointee.as_ref() {
+                Type::Function { parameters, .. } => parameters.clone(),
+                _ => unreachable!("Indirect call callee is not a function pointer"),
+            },
+            _ => unreachable!("Indirect call callee is not a function pointer"),
+        };
+
+        for (argument, (expected_type, _)) in expr.arguments.iter_mut().zip(&parameters) {
+            self.visit_expression(argument)?;
+
+            if self.is_int {
+                ExpressionTypeSetter::new(expected_type).set_type_recusively(argument);
+            }
+        }
+
+        self.is_int = false;
+        Ok(())
+    }
+
     fn visit_return(&mut self, stmt: &'ast mut ReturnStatement) -> Result<(), TypeCheckerError> {
         // Do nothing it the return type is `void`. Incompatible return types errors
         // are caught by the actual type checker.
@@ -220,21 +392,15 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for IntegerInference {
     }
 
     fn visit_literal(&mut self, expr: &'ast mut Literal) -> Result<(), TypeCheckerError> {
-        match expr.literal_type {
-            LiteralType::Integer(_) | LiteralType::ArrayAccess(_) => {
-                if let Type::Int = expr.get_type() {
-                    self.is_int = true;
-                }
-            }
-            _ => {
-                self.is_int = false;
-            }
-        };
+        // Array accesses are never inferred: the type checker already gave
+        // them the concrete element type of the array they index.
+        self.is_int = matches!(expr.literal_type, LiteralType::Integer(_))
+            && matches!(expr.get_type(), Type::Int);
 
-        // If the index has a type Int. set it to I64.
+        // If the index has a type Int. set it to the pointer sized integer type.
         if let LiteralType::ArrayAccess(ArrayAccess { index, .. }) = &mut expr.literal_type {
             if let Type::Int = index.get_type() {
-                let mut setter = ExpressionTypeSetter::new(&Type::I64);
+                let mut setter = ExpressionTypeSetter::new(&self.index_type);
                 setter.set_type_recusively(index);
             }
         }
@@ -247,7 +413,7 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for IntegerInference {
         expr: &'ast mut BinaryOperation,
     ) -> Result<(), TypeCheckerError> {
         if expr.right.is_none() {
-            self.visit_expression(&mut expr.left)
+            self.visit_expression(&mut expr.left)?;
         } else {
             self.visit_expression(&mut expr.left)?;
             let is_int_left = self.is_int;
@@ -278,8 +444,13 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for IntegerInference {
                     Ok(())
                 }
                 _ => Ok(()),
-            }
+            }?;
         }
+
+        // Only operations still typed `Int` need to be inferred by their
+        // parent. Comparisons are booleans even with integer operands.
+        self.is_int = matches!(expr.get_type(), Type::Int);
+        Ok(())
     }
 
     fn visit_assignment(&mut self, expr: &'ast mut Assignment) -> Result<(), TypeCheckerError> {
@@ -296,6 +467,38 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for IntegerInference {
             setter.set_type_recusively(&mut expr.right);
         }
 
+        self.is_int = false;
+        Ok(())
+    }
+
+    fn visit_slice(&mut self, expr: &'ast mut Slice) -> Result<(), TypeCheckerError> {
+        // Like array indexes, slice bounds with an Int type are set to the
+        // pointer sized integer type
+        for bound in [&mut expr.start, &mut expr.end] {
+            self.visit_expression(bound)?;
+            if let Type::Int = bound.get_type() {
+                let mut setter = ExpressionTypeSetter::new(&self.index_type);
+                setter.set_type_recusively(bound);
+            }
+        }
+
+        self.is_int = false;
+        Ok(())
+    }
+
+    fn visit_alignof(&mut self, _: &'ast mut AlignOf) -> Result<(), TypeCheckerError> {
+        self.is_int = false;
+        Ok(())
+    }
+
+    fn visit_tuple(&mut self, expr: &'ast mut Tuple) -> Result<(), TypeCheckerError> {
+        let mut is_int = false;
+        for value in expr.values.iter_mut() {
+            self.visit_expression(value)?;
+            is_int |= self.is_int;
+        }
+
+        self.is_int = is_int;
         Ok(())
     }
 
@@ -0,0 +1,49 @@
+use crate::ast::{Expression, GlobalStatement, Locatable, Visitor};
+
+use super::{errors::TypeCheckerError, Typable, Type};
+
+/// Checks that the integer inference gave a concrete type to every
+/// expression. The type checker accepts expressions typed `Int` and the
+/// inference sets their type from the context they are used in, so an
+/// expression it missed would reach the codegen without a type.
+///
+/// This pass runs after `IntegerInference` so it doesn't report errors the
+/// type checker or the inference already reported.
+#[derive(Default)]
+pub(crate) struct InferredTypesChecker;
+
+impl InferredTypesChecker {
+    pub fn check_statements(&mut self, stmts: &[GlobalStatement]) -> Result<(), TypeCheckerError> {
+        for stmt in stmts {
+            self.visit_global_statement(stmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'ast> Visitor<'ast, TypeCheckerError> for InferredTypesChecker {
+    fn visit_expression(&mut self, expr: &'ast Expression) -> Result<(), TypeCheckerError> {
+        if let Type::Int = expr.get_type() {
+            return Err(TypeCheckerError::UninferredInteger {
+                location: expr.get_location().clone(),
+            });
+        }
+
+        match expr {
+            Expression::Group(g) => self.visit_group(g),
+            Expression::BinaryOperation(bo) => self.visit_binary_operation(bo),
+            Expression::Literal(l) => self.visit_literal(l),
+            Expression::Call(c) => self.visit_call(c),
+            Expression::IndirectCall(c) => self.visit_indirect_call(c),
+            Expression::Assignment(a) => self.visit_assignment(a),
+            Expression::ArrayInitializer(a) => self.visit_array_initializer(a),
+            Expression::AddrOf(a) => self.visit_addrof(a),
+            Expression::Deref(d) => self.visit_deref(d),
+            Expression::Slice(s) => self.visit_slice(s),
+            Expression::AlignOf(a) => self.visit_alignof(a),
+            Expression::Tuple(t) => self.visit_tuple(t),
+            Expression::TryNull(t) => self.visit_try_null(t),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashSet;
+
+use crate::ast::{
+    AddrOf, Assignment, Bindable, Definition, Expression, ForStatement, FunctionStatement,
+    GlobalStatement, IfStatement, LetStatement, LetTupleStatement, Literal, LiteralType, Locatable,
+    StatementKind, Statements, Visitor, WhileStatement,
+};
+
+use super::errors::TypeCheckerError;
+
+/// Return whether the execution of `stmts` never reaches their end.
+fn diverges(stmts: &Statements) -> bool {
+    stmts.statements.iter().any(|stmt| match &stmt.kind {
+        StatementKind::Return(_) | StatementKind::Break(_) | StatementKind::Continue(_) => true,
+        StatementKind::If(IfStatement {
+            then_clause,
+            else_clause: Some(else_clause),
+            ..
+        }) => diverges(then_clause) && diverges(else_clause),
+        _ => false,
+    })
+}
+
+/// Returns the declaration of a local variable used as an expression.
+fn local_variable(expr: &Expression) -> Option<*const LetStatement> {
+    match expr {
+        Expression::Literal(
+            literal @ Literal {
+                literal_type: LiteralType::Identifier(_),
+                ..
+            },
+        ) => match literal.get_definition() {
+            Definition::LocalVariable(declaration) => Some(*declaration),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Checks that variables declared without an initializer (`let a: i32;`) are
+/// assigned on every path before being read.
+///
+/// The checker tracks the variables that may still be uninitialized, which is
+/// the complement of the definitely initialized ones. The branches of an `if`
+/// are merged by keeping the variables uninitialized in any branch reaching
+/// the end of the statement, i.e. intersecting their initialized variables.
+/// A loop body may never be executed so the variables it assigns are still
+/// uninitialized after it.
+///
+/// Taking the address of a variable counts as an assignment since the
+/// variable may be initialized through the pointer.
+#[derive(Default)]
+pub(crate) struct InitializationChecker {
+    uninitialized: HashSet<*const LetStatement>,
+}
+
+impl InitializationChecker {
+    pub fn check_statements(&mut self, stmts: &[GlobalStatement]) -> Result<(), TypeCheckerError> {
+        for stmt in stmts {
+            self.visit_global_statement(stmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'ast> Visitor<'ast, TypeCheckerError> for InitializationChecker {
+    fn visit_function(&mut self, stmt: &'ast FunctionStatement) -> Result<(), TypeCheckerError> {
+        self.uninitialized.clear();
+
+        if let Some(body) = &stmt.body {
+            self.visit_statements(body)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_let(&mut self, stmt: &'ast LetStatement) -> Result<(), TypeCheckerError> {
+        match &stmt.init_exp {
+            Some(init_exp) => self.visit_expression(init_exp),
+            None => {
+                self.uninitialized.insert(stmt);
+                Ok(())
+            }
+        }
+    }
+
+    /// Destructured variables are initialized by the tuple
+    fn visit_let_tuple(&mut self, stmt: &'ast LetTupleStatement) -> Result<(), TypeCheckerError> {
+        self.visit_expression(&stmt.init_exp)
+    }
+
+    fn visit_if(&mut self, stmt: &'ast IfStatement) -> Result<(), TypeCheckerError> {
+        self.visit_expression(&stmt.condition)?;
+        let before = self.uninitialized.clone();
+
+        self.visit_statements(&stmt.then_clause)?;
+        let after_then =
+            (!diverges(&stmt.then_clause)).then(|| std::mem::take(&mut self.uninitialized));
+
+        self.uninitialized.clone_from(&before);
+        let after_else = match &stmt.else_clause {
+            Some(else_clause) => {
+                self.visit_statements(else_clause)?;
+                (!diverges(else_clause)).then(|| std::mem::take(&mut self.uninitialized))
+            }
+            None => Some(before.clone()),
+        };
+
+        self.uninitialized = match (after_then, after_else) {
+            (Some(then_state), Some(else_state)) => &then_state | &else_state,
+            (Some(state), None) | (None, Some(state)) => state,
+            // The code following the statement is unreachable
+            (None, None) => before,
+        };
+
+        Ok(())
+    }
+
+    fn visit_while(&mut self, stmt: &'ast WhileStatement) -> Result<(), TypeCheckerError> {
+        self.visit_expression(&stmt.condition)?;
+        let before = self.uninitialized.clone();
+
+        self.visit_statements(&stmt.body)?;
+
+        // The loop may be left with `break` without running the else clause
+        if let Some(else_clause) = &stmt.else_clause {
+            self.uninitialized.clone_from(&before);
+            self.visit_statements(else_clause)?;
+        }
+
+        self.uninitialized = before;
+        Ok(())
+    }
+
+    fn visit_for(&mut self, stmt: &'ast ForStatement) -> Result<(), TypeCheckerError> {
+        self.visit_let(&stmt.init_decl)?;
+        self.visit_expression(&stmt.continue_expression)?;
+        let before = self.uninitialized.clone();
+
+        self.visit_statements(&stmt.body)?;
+        self.visit_expression(&stmt.modify_expression)?;
+        self.uninitialized = before;
+
+        Ok(())
+    }
+
+    fn visit_assignment(&mut self, expr: &'ast Assignment) -> Result<(), TypeCheckerError> {
+        self.visit_expression(&expr.right)?;
+
+        match local_variable(&expr.left) {
+            Some(declaration) => {
+                self.uninitialized.remove(&declaration);
+                Ok(())
+            }
+            None => self.visit_expression(&expr.left),
+        }
+    }
+
+    fn visit_addrof(&mut self, expr: &'ast AddrOf) -> Result<(), TypeCheckerError> {
+        match local_variable(&expr.expr) {
+            Some(declaration) => {
+                self.uninitialized.remove(&declaration);
+                Ok(())
+            }
+            None => self.visit_expression(&expr.expr),
+        }
+    }
+
+    fn visit_literal(&mut self, expr: &'ast Literal) -> Result<(), TypeCheckerError> {
+        match &expr.literal_type {
+            LiteralType::Identifier(name) => {
+                if let Definition::LocalVariable(declaration) = expr.get_definition() {
+                    if self.uninitialized.contains(declaration) {
+                        return Err(TypeCheckerError::UseOfUninitialized {
+                            name: name.clone(),
+                            location: expr.get_location().clone(),
+                        });
+                    }
+                }
+
+                Ok(())
+            }
+            LiteralType::ArrayAccess(array_access) => {
+                self.visit_expression(&array_access.identifier)?;
+                self.visit_expression(&array_access.index)
+            }
+            _ => Ok(()),
+        }
+    }
+}
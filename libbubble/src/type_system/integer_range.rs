@@ -0,0 +1,83 @@
+use crate::ast::{
+    BinaryOperation, Expression, GlobalStatement, Literal, LiteralType, OpType, Visitor,
+};
+
+use super::{errors::TypeCheckerError, Typable, Type};
+
+/// Check that every integer literal fits in the type inferred for it.
+/// This pass must run after `IntegerInference` because literals only get
+/// their concrete integer type there.
+///
+/// A unary minus applied to an integer literal (even parenthesized) is checked
+/// as a single negative constant so that `let a: i8 = -128;` is accepted.
+#[derive(Default)]
+pub(crate) struct IntegerRangeChecker;
+
+impl IntegerRangeChecker {
+    pub fn check_statements(&mut self, stmts: &[GlobalStatement]) -> Result<(), TypeCheckerError> {
+        for stmt in stmts {
+            self.visit_global_statement(stmt)?;
+        }
+
+        Ok(())
+    }
+
+    fn skip_groups(expr: &Expression) -> &Expression {
+        match expr {
+            Expression::Group(group) => Self::skip_groups(&group.expr),
+            _ => expr,
+        }
+    }
+
+    fn check_value(value: i128, ty: &Type) -> Result<(), TypeCheckerError> {
+        // Literals that are still `Int` are not bound to a sized type and are
+        // caught by `InferredTypesChecker`.
+        if !ty.is_integer() || *ty == Type::Int || ty.can_hold_integer(value) {
+            Ok(())
+        } else {
+            Err(TypeCheckerError::IntegerLiteralOutOfRange {
+                value,
+                ty: ty.clone(),
+            })
+        }
+    }
+}
+
+impl<'ast> Visitor<'ast, TypeCheckerError> for IntegerRangeChecker {
+    fn visit_binary_operation(
+        &mut self,
+        expr: &'ast BinaryOperation,
+    ) -> Result<(), TypeCheckerError> {
+        match (expr.op, &expr.right, Self::skip_groups(&expr.left)) {
+            (
+                OpType::Minus,
+                None,
+                Expression::Literal(
+                    lit @ Literal {
+                        literal_type: LiteralType::Integer(value),
+                        ..
+                    },
+                ),
+            ) => Self::check_value(-*value, lit.get_type()),
+            _ => {
+                self.visit_expression(&expr.left)?;
+                if let Some(right) = &expr.right {
+                    self.visit_expression(right)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    fn visit_literal(&mut self, expr: &'ast Literal) -> Result<(), TypeCheckerError> {
+        match &expr.literal_type {
+            LiteralType::Integer(value) => Self::check_value(*value, expr.get_type()),
+            LiteralType::ArrayAccess(aa) => {
+                self.visit_expression(&aa.identifier)?;
+                self.visit_expression(&aa.index)
+            }
+            _ => Ok(()),
+        }
+    }
+}
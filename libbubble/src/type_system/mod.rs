@@ -1,13 +1,24 @@
 pub mod binder;
+mod constant_folding;
 mod errors;
 mod inference;
+mod inferred_types;
+mod initialization;
+mod integer_range;
 mod rename;
 mod typables;
 mod type_checker;
+mod type_dumper;
 mod type_setter;
-mod utils;
+pub(crate) mod utils;
+mod warnings;
 
 pub use errors::{BinderError, TypeCheckerError};
 pub use rename::Renamer;
 pub use typables::*;
-pub use type_checker::run_type_checker;
+pub use type_checker::{
+    run_type_checker, run_type_checker_for_target, run_type_checker_with_max_stack_allocation,
+    DEFAULT_MAX_STACK_ALLOCATION, DEFAULT_POINTER_WIDTH,
+};
+pub use type_dumper::{TypeDumper, TypeDumperResult};
+pub use warnings::Warning;
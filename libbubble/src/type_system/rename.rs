@@ -58,7 +58,9 @@ impl<'ast> MutableVisitor<'ast, Infallible> for Renamer {
     fn visit_let(&mut self, stmt: &mut LetStatement) -> Result<(), Infallible> {
         let prev_name = stmt.name.clone();
         stmt.name = self.new_symbol(&stmt.name);
-        self.visit_expression(stmt.init_exp.as_mut().expect("Let has no init statement"))?;
+        if let Some(init_exp) = stmt.init_exp.as_mut() {
+            self.visit_expression(init_exp)?;
+        }
         self.variables.insert_symbol(&prev_name, stmt.clone());
 
         Ok(())
@@ -86,6 +88,12 @@ impl<'ast> MutableVisitor<'ast, Infallible> for Renamer {
         self.visit_statements(&mut stmt.body)?;
         self.variables.delete_scope();
 
+        if let Some(else_clause) = &mut stmt.else_clause {
+            self.variables.new_scope();
+            self.visit_statements(else_clause)?;
+            self.variables.delete_scope();
+        }
+
         Ok(())
     }
 
@@ -1,22 +1,25 @@
-use std::ops::Deref;
+use std::{fmt, ops::Deref};
 
 use crate::ast::{
-    self, ArrayAccess, ArrayInitializer, Assignment, BinaryOperation, Call, Expression,
-    FunctionStatement, LetStatement, Literal, Null, StructStatement,
+    self, AddrOf, AlignOf, ArrayAccess, ArrayInitializer, Assignment, BinaryOperation, Call,
+    EnumStatement, Expression, FunctionStatement, Group, IndirectCall, LetStatement, Literal, Null,
+    Slice, StructStatement, TryNull, Tuple,
 };
 
 pub type FunctionParameter = (Type, String);
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub enum Type {
     U8,
     U16,
     U32,
     U64,
+    U128,
     I8,
     I16,
     I32,
     I64,
+    I128,
     /// This type is for int literal and is supposed to be compatible with any
     /// integer like type (signed and unsigned).
     /// It is only used internaly in the ast.
@@ -28,6 +31,12 @@ pub enum Type {
         name: String,
         fields: Vec<FunctionParameter>,
     },
+    /// Enums are stored as `i32`. Like struct fields, variants are only known
+    /// from the enum declaration.
+    Enum {
+        name: String,
+        variants: Vec<(String, i64)>,
+    },
     Function {
         parameters: Vec<FunctionParameter>,
         return_type: Box<Type>,
@@ -37,12 +46,92 @@ pub enum Type {
         array_type: Box<Type>,
     },
     Ptr(Box<Type>),
+    /// A view over a part of an array. Slices are fat pointers holding the
+    /// address of their first element and their length.
+    Slice(Box<Type>),
+    /// Anonymous product type used to return multiple values
+    Tuple(Vec<Type>),
     Void,
     Null {
         concrete_type: Option<Box<Type>>,
     },
 }
 
+/// Size of a C struct made of `fields`: each field starts at an offset
+/// multiple of its alignment and the size is a multiple of the biggest one.
+fn padded_size<'a>(fields: impl Iterator<Item = &'a Type>) -> u64 {
+    let (size, alignment) = fields.fold((0, 1), |(offset, alignment), ty| {
+        let field_alignment = ty.alignment();
+        let field_offset = u64::next_multiple_of(offset, field_alignment);
+
+        (
+            field_offset.saturating_add(ty.size_in_bytes()),
+            u64::max(alignment, field_alignment),
+        )
+    });
+
+    size.next_multiple_of(alignment)
+}
+
+/// Structs and enums are nominal: a type built from a type annotation doesn't
+/// know the fields of the struct it names.
+/// Function types only compare their parameter types, names aren't part of
+/// the type.
+impl PartialEq for Type {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Type::Struct { name: lname, .. }, Type::Struct { name: rname, .. })
+            | (Type::Enum { name: lname, .. }, Type::Enum { name: rname, .. }) => lname == rname,
+            (
+                Type::Function {
+                    parameters: lparameters,
+                    return_type: lreturn_type,
+                },
+                Type::Function {
+                    parameters: rparameters,
+                    return_type: rreturn_type,
+                },
+            ) => {
+                lparameters.len() == rparameters.len()
+                    && lparameters
+                        .iter()
+                        .zip(rparameters)
+                        .all(|((lty, _), (rty, _))| lty == rty)
+                    && lreturn_type == rreturn_type
+            }
+            (
+                Type::Array {
+                    size: lsize,
+                    array_type: larray_type,
+                },
+                Type::Array {
+                    size: rsize,
+                    array_type: rarray_type,
+                },
+            ) => lsize == rsize && larray_type == rarray_type,
+            (Type::Ptr(l), Type::Ptr(r)) | (Type::Slice(l), Type::Slice(r)) => l == r,
+            (Type::Null { concrete_type: l }, Type::Null { concrete_type: r }) => l == r,
+            (Type::Tuple(l), Type::Tuple(r)) => l == r,
+            (Type::U8, Type::U8)
+            | (Type::U16, Type::U16)
+            | (Type::U32, Type::U32)
+            | (Type::U64, Type::U64)
+            | (Type::U128, Type::U128)
+            | (Type::I8, Type::I8)
+            | (Type::I16, Type::I16)
+            | (Type::I32, Type::I32)
+            | (Type::I64, Type::I64)
+            | (Type::I128, Type::I128)
+            | (Type::Int, Type::Int)
+            | (Type::Float, Type::Float)
+            | (Type::String, Type::String)
+            | (Type::Bool, Type::Bool)
+            | (Type::Void, Type::Void) => true,
+            _ => false,
+        }
+    }
+}
+
 impl Type {
     pub fn is_compatible_with(&self, other: &Type) -> bool {
         match (self, other) {
@@ -52,26 +141,33 @@ impl Type {
             | (Type::Int, Type::U16)
             | (Type::Int, Type::U32)
             | (Type::Int, Type::U64)
+            | (Type::Int, Type::U128)
             | (Type::Int, Type::I8)
             | (Type::Int, Type::I16)
             | (Type::Int, Type::I32)
             | (Type::Int, Type::I64)
+            | (Type::Int, Type::I128)
             | (Type::U8, Type::Int)
             | (Type::U16, Type::Int)
             | (Type::U32, Type::Int)
             | (Type::U64, Type::Int)
+            | (Type::U128, Type::Int)
             | (Type::I8, Type::Int)
             | (Type::I16, Type::Int)
             | (Type::I32, Type::Int)
             | (Type::I64, Type::Int)
+            | (Type::I128, Type::Int)
             | (Type::U8, Type::U8)
             | (Type::U16, Type::U16)
             | (Type::U32, Type::U32)
             | (Type::U64, Type::U64)
+            | (Type::U128, Type::U128)
             | (Type::I8, Type::I8)
             | (Type::I16, Type::I16)
             | (Type::I32, Type::I32)
             | (Type::I64, Type::I64)
+            | (Type::I128, Type::I128)
+            | (Type::Float, Type::Float)
             | (Type::Bool, Type::Bool)
             | (Type::Void, Type::Void)
             | (Type::Ptr(_), Type::Null { .. })
@@ -86,6 +182,31 @@ impl Type {
                     array_type: rarray_rtype,
                 },
             ) => lsize == rsize && larray_type.is_compatible_with(rarray_rtype),
+            (Type::Slice(l), Type::Slice(r)) => l.is_compatible_with(r),
+            (Type::Tuple(l), Type::Tuple(r)) => {
+                l.len() == r.len() && l.iter().zip(r).all(|(l, r)| l.is_compatible_with(r))
+            }
+            // Structs are nominal, fields aren't always known on both sides
+            (Type::Struct { name: lname, .. }, Type::Struct { name: rname, .. }) => lname == rname,
+            (Type::Enum { name: lname, .. }, Type::Enum { name: rname, .. }) => lname == rname,
+            // Parameter names are not part of a function type
+            (
+                Type::Function {
+                    parameters: lparameters,
+                    return_type: lreturn_type,
+                },
+                Type::Function {
+                    parameters: rparameters,
+                    return_type: rreturn_type,
+                },
+            ) => {
+                lparameters.len() == rparameters.len()
+                    && lparameters
+                        .iter()
+                        .zip(rparameters)
+                        .all(|((l, _), (r, _))| l.is_compatible_with(r))
+                    && lreturn_type.is_compatible_with(rreturn_type)
+            }
             // Void pointer is compatible with any pointer type
             (Type::Ptr(l), Type::Ptr(_)) if l.as_ref() == &Type::Void => true,
             (Type::Ptr(_), Type::Ptr(r)) if r.as_ref() == &Type::Void => true,
@@ -101,27 +222,154 @@ impl Type {
                 | Type::U16
                 | Type::U32
                 | Type::U64
+                | Type::U128
                 | Type::I8
                 | Type::I16
                 | Type::I32
                 | Type::I64
+                | Type::I128
                 | Type::Int
         )
     }
 
     pub fn is_signed(&self) -> bool {
-        matches!(self, Type::I8 | Type::I16 | Type::I32 | Type::I64)
+        matches!(
+            self,
+            Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::I128
+        )
+    }
+
+    /// Return whether `value` can be represented by this integer type.
+    /// Non integer types and the `Int` placeholder can't hold any value.
+    pub fn can_hold_integer(&self, value: i128) -> bool {
+        let (min, max) = match self {
+            Type::U8 => (u8::MIN as i128, u8::MAX as i128),
+            Type::U16 => (u16::MIN as i128, u16::MAX as i128),
+            Type::U32 => (u32::MIN as i128, u32::MAX as i128),
+            Type::U64 => (u64::MIN as i128, u64::MAX as i128),
+            // Every value handled here fits below the i128 maximum
+            Type::U128 => (u128::MIN as i128, i128::MAX),
+            Type::I8 => (i8::MIN as i128, i8::MAX as i128),
+            Type::I16 => (i16::MIN as i128, i16::MAX as i128),
+            Type::I32 => (i32::MIN as i128, i32::MAX as i128),
+            Type::I64 => (i64::MIN as i128, i64::MAX as i128),
+            Type::I128 => (i128::MIN, i128::MAX),
+            _ => return false,
+        };
+
+        (min..=max).contains(&value)
+    }
+
+    /// Return the number of bytes a value of this type occupies in memory.
+    /// Structs and tuples are laid out like C structs, padding included.
+    pub fn size_in_bytes(&self) -> u64 {
+        match self {
+            Type::U8 | Type::I8 | Type::Bool => 1,
+            Type::U16 | Type::I16 => 2,
+            Type::U32 | Type::I32 | Type::Enum { .. } => 4,
+            Type::U64 | Type::I64 | Type::Int | Type::Float => 8,
+            Type::U128 | Type::I128 => 16,
+            Type::String | Type::Ptr(_) | Type::Null { .. } | Type::Function { .. } => 8,
+            Type::Slice(_) => 16,
+            Type::Void => 0,
+            Type::Struct { fields, .. } => padded_size(fields.iter().map(|(ty, _)| ty)),
+            Type::Tuple(elements) => padded_size(elements.iter()),
+            Type::Array { size, array_type } => {
+                array_type.size_in_bytes().saturating_mul(*size as u64)
+            }
+        }
+    }
+
+    /// Return the alignment in bytes of a value of this type
+    fn alignment(&self) -> u64 {
+        match self {
+            Type::Struct { fields, .. } => fields
+                .iter()
+                .map(|(ty, _)| ty.alignment())
+                .max()
+                .unwrap_or(1),
+            Type::Tuple(elements) => elements.iter().map(Type::alignment).max().unwrap_or(1),
+            Type::Array { array_type, .. } => array_type.alignment(),
+            // Slices are a pointer and a length
+            Type::Slice(_) => 8,
+            Type::Void => 1,
+            _ => self.size_in_bytes(),
+        }
     }
 
-    /// Return array base type.
+    /// Return array or slice base type.
     /// # Panics
-    /// This method panic if the type is not an array
+    /// This method panic if the type is not an array nor a slice
     pub fn get_base_array_type(&self) -> &Type {
         match self {
-            Type::Array { array_type, .. } => array_type,
+            Type::Array { array_type, .. } | Type::Slice(array_type) => array_type,
             _ => panic!("Call get_base_array_type on a type that is not an array!"),
         }
     }
+
+    /// User facing name of the type, used by every diagnostic. It is the
+    /// `Display` rendering of the type.
+    pub fn display_name(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Renders types as they are written in source code. The internal `Int`
+/// placeholder is rendered as `{integer}` and a `null` whose pointer type is
+/// known is rendered as this pointer type.
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::U8 => write!(f, "u8"),
+            Type::U16 => write!(f, "u16"),
+            Type::U32 => write!(f, "u32"),
+            Type::U64 => write!(f, "u64"),
+            Type::U128 => write!(f, "u128"),
+            Type::I8 => write!(f, "i8"),
+            Type::I16 => write!(f, "i16"),
+            Type::I32 => write!(f, "i32"),
+            Type::I64 => write!(f, "i64"),
+            Type::I128 => write!(f, "i128"),
+            Type::Int => write!(f, "{{integer}}"),
+            Type::Float => write!(f, "float"),
+            Type::String => write!(f, "string"),
+            Type::Bool => write!(f, "bool"),
+            Type::Void => write!(f, "void"),
+            Type::Struct { name, .. } | Type::Enum { name, .. } => write!(f, "{name}"),
+            Type::Function {
+                parameters,
+                return_type,
+            } => {
+                write!(f, "function(")?;
+                for (i, (parameter, _)) in parameters.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{parameter}")?;
+                }
+                write!(f, "): {return_type}")
+            }
+            Type::Array { size, array_type } => write!(f, "[{size}; {array_type}]"),
+            Type::Ptr(pointee) => write!(f, "ptr {pointee}"),
+            Type::Slice(base_type) => write!(f, "[{base_type}]"),
+            Type::Tuple(elements) => {
+                write!(f, "(")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{element}")?;
+                }
+                write!(f, ")")
+            }
+            Type::Null {
+                concrete_type: Some(concrete_type),
+            } => write!(f, "{concrete_type}"),
+            Type::Null {
+                concrete_type: None,
+            } => write!(f, "null"),
+        }
+    }
 }
 
 impl From<ast::TypeKind> for Type {
@@ -131,10 +379,12 @@ impl From<ast::TypeKind> for Type {
             ast::TypeKind::U16 => Type::U16,
             ast::TypeKind::U32 => Type::U32,
             ast::TypeKind::U64 => Type::U64,
+            ast::TypeKind::U128 => Type::U128,
             ast::TypeKind::I8 => Type::I8,
             ast::TypeKind::I16 => Type::I16,
             ast::TypeKind::I32 => Type::I32,
             ast::TypeKind::I64 => Type::I64,
+            ast::TypeKind::I128 => Type::I128,
             ast::TypeKind::String => Type::String,
             ast::TypeKind::Bool => Type::Bool,
             ast::TypeKind::Float => Type::Float,
@@ -142,12 +392,29 @@ impl From<ast::TypeKind> for Type {
                 name,
                 fields: Vec::new(),
             },
+            ast::TypeKind::Enum(name) => Type::Enum {
+                name,
+                variants: Vec::new(),
+            },
             ast::TypeKind::Void => Type::Void,
             ast::TypeKind::Array { size, array_type } => Type::Array {
-                size,
+                size: size.value(),
                 array_type: Box::new(array_type.kind.into()),
             },
             ast::TypeKind::Ptr(ptr) => Type::Ptr(Box::new(ptr.deref().to_owned().kind.into())),
+            ast::TypeKind::Function {
+                parameters,
+                return_type,
+            } => Type::Function {
+                parameters: parameters
+                    .into_iter()
+                    .map(|parameter| (parameter.kind.into(), String::new()))
+                    .collect(),
+                return_type: Box::new(return_type.kind.into()),
+            },
+            ast::TypeKind::Tuple(elements) => {
+                Type::Tuple(elements.into_iter().map(|ty| ty.kind.into()).collect())
+            }
             ast::TypeKind::Null { .. } => Type::Null {
                 concrete_type: None,
             },
@@ -177,16 +444,24 @@ macro_rules! impl_typables {
 }
 
 impl_typables!(
+    AddrOf,
+    AlignOf,
     Assignment,
     BinaryOperation,
     Call,
     FunctionStatement,
+    Group,
+    IndirectCall,
     LetStatement,
     Literal,
     ArrayAccess,
     Null,
     ArrayInitializer,
-    StructStatement
+    Slice,
+    StructStatement,
+    EnumStatement,
+    TryNull,
+    Tuple
 );
 
 impl Typable for Expression {
@@ -196,10 +471,15 @@ impl Typable for Expression {
             Expression::BinaryOperation(bo) => bo.get_type(),
             Expression::Literal(l) => l.get_type(),
             Expression::Call(c) => c.get_type(),
+            Expression::IndirectCall(c) => c.get_type(),
             Expression::Assignment(a) => a.get_type(),
             Expression::ArrayInitializer(a) => a.get_type(),
-            Expression::AddrOf(_) => todo!(),
-            Expression::Deref(_) => todo!(),
+            Expression::AddrOf(a) => a.get_type(),
+            Expression::Deref(d) => d.get_type(),
+            Expression::Slice(s) => s.get_type(),
+            Expression::AlignOf(a) => a.get_type(),
+            Expression::Tuple(t) => t.get_type(),
+            Expression::TryNull(t) => t.get_type(),
         }
     }
 
@@ -1,39 +1,129 @@
-use std::ops::Deref;
-
-use crate::ast::{
-    self, AddrOf, ArrayInitializer, Assignment, BinaryOperation, Bindable, Call, Definition,
-    Expression, ForStatement, FunctionStatement, GlobalStatement, IfStatement, LetStatement,
-    Literal, LiteralType, Locatable, MutableVisitor, OpType, ReturnStatement, StructStatement,
-    WhileStatement,
+use std::{collections::HashMap, ops::Deref};
+
+use crate::{
+    ast::{
+        self, AddrOf, AlignOf, ArrayInitializer, AssertStatement, Assignment, AttributeKind,
+        BinaryOperation, Bindable, Builtin, Call, CallingConvention, Definition, DestructuringKind,
+        EnumStatement, Expression, ForStatement, FunctionStatement, GlobalStatement, Group,
+        IfStatement, IndirectCall, LetStatement, LetTupleStatement, Literal, LiteralType,
+        Locatable, MutableVisitor, OpType, ReturnStatement, Slice, StructStatement, TryNull, Tuple,
+        TypeKind, WhileStatement,
+    },
+    desugar::incompatible_assert_function,
 };
 
 use super::{
-    errors::TypeCheckerError, inference::IntegerInference, type_setter::ExpressionTypeSetter,
+    constant_folding::{ArraySizeFolder, ConstantFolder},
+    errors::TypeCheckerError,
+    inference::IntegerInference,
+    inferred_types::InferredTypesChecker,
+    initialization::InitializationChecker,
+    integer_range::IntegerRangeChecker,
+    type_setter::ExpressionTypeSetter,
     Typable, Type,
 };
 
 pub fn run_type_checker(stmts: &mut [GlobalStatement]) -> Result<(), TypeCheckerError> {
-    let mut type_checker = TypeChecker::default();
-    let mut int_inference = IntegerInference::default();
+    run_type_checker_with_max_stack_allocation(stmts, DEFAULT_MAX_STACK_ALLOCATION)
+}
 
+/// Same as `run_type_checker` but local arrays may take up to
+/// `max_stack_allocation` bytes.
+pub fn run_type_checker_with_max_stack_allocation(
+    stmts: &mut [GlobalStatement],
+    max_stack_allocation: u64,
+) -> Result<(), TypeCheckerError> {
+    run_type_checker_for_target(stmts, max_stack_allocation, DEFAULT_POINTER_WIDTH)
+}
+
+/// Same as `run_type_checker_with_max_stack_allocation` for a target whose
+/// pointers are `pointer_width` bits wide. Untyped array indexes and slice
+/// bounds get the pointer sized integer type.
+pub fn run_type_checker_for_target(
+    stmts: &mut [GlobalStatement],
+    max_stack_allocation: u64,
+    pointer_width: u32,
+) -> Result<(), TypeCheckerError> {
+    let mut array_size_folder = ArraySizeFolder;
+    let mut type_checker = TypeChecker::with_max_stack_allocation(max_stack_allocation);
+    let mut initialization_checker = InitializationChecker::default();
+    let mut int_inference = IntegerInference::with_pointer_width(pointer_width);
+    let mut inferred_types_checker = InferredTypesChecker;
+    let mut range_checker = IntegerRangeChecker;
+    let mut constant_folder = ConstantFolder;
+
+    array_size_folder.fold_statements(stmts)?;
     type_checker.check_statements(stmts)?;
+    initialization_checker.check_statements(stmts)?;
     int_inference.infer_statements(stmts)?;
+    inferred_types_checker.check_statements(stmts)?;
+    range_checker.check_statements(stmts)?;
+    constant_folder.fold_statements(stmts)?;
 
-    // type_checker.check_statements(stmts)
     Ok(())
 }
 
-#[derive(Default)]
+/// Default maximum size in bytes of a local array (1 MiB)
+pub const DEFAULT_MAX_STACK_ALLOCATION: u64 = 1024 * 1024;
+
+/// Pointer width in bits used when the target isn't known
+pub const DEFAULT_POINTER_WIDTH: u32 = 64;
+
 pub struct TypeChecker {
     current_type: Option<Type>,
     current_function: Option<Type>, // current's function type
+    max_stack_allocation: u64,
+    /// Declared structs types, with their fields
+    structs: HashMap<String, Type>,
+    /// Name and expected signature of a C function called by desugared
+    /// asserts but declared with another signature by the program
+    incompatible_assert_function: Option<(String, String)>,
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::with_max_stack_allocation(DEFAULT_MAX_STACK_ALLOCATION)
+    }
 }
 
 impl<'ast> TypeChecker {
+    /// Creates a type checker rejecting local arrays bigger than
+    /// `max_stack_allocation` bytes.
+    pub fn with_max_stack_allocation(max_stack_allocation: u64) -> Self {
+        Self {
+            current_type: None,
+            current_function: None,
+            max_stack_allocation,
+            structs: HashMap::new(),
+            incompatible_assert_function: None,
+        }
+    }
+
     pub fn check_statements(
         &mut self,
         stmts: &'ast mut [GlobalStatement],
     ) -> Result<(), TypeCheckerError> {
+        // Calls may appear before the callee definition so every signature
+        // must be typed before checking bodies. Structs and enums may be used
+        // before their declaration as well.
+        for stmt in stmts.iter_mut() {
+            match stmt {
+                GlobalStatement::Struct(strct) => {
+                    self.structs
+                        .insert(strct.name.clone(), set_struct_type(strct));
+                }
+                GlobalStatement::Enum(enm) => set_enum_type(enm),
+                _ => (),
+            }
+        }
+
+        for stmt in stmts.iter_mut() {
+            if let GlobalStatement::Function(function) = stmt {
+                set_signature_type(function, &self.structs);
+            }
+        }
+        self.incompatible_assert_function = incompatible_assert_function(stmts);
+
         for stmt in stmts.iter_mut() {
             self.visit_global_statement(stmt)?;
             self.current_type = None;
@@ -55,11 +145,337 @@ impl<'ast> TypeChecker {
             .expect("expression should have type")
         {
             Type::Bool => Ok(()),
-            _ => Err(TypeCheckerError::NonBoolCondition(
-                self.current_type.clone().unwrap(),
-            )),
+            _ => Err(TypeCheckerError::NonBoolCondition {
+                ty: self.current_type.clone().unwrap(),
+                location: expr.get_location().clone(),
+            }),
         }
     }
+
+    /// Local arrays are allocated on the stack. Reject the ones that would
+    /// overflow it or that have no element at all.
+    fn check_stack_allocation(&self, name: &str, ty: &Type) -> Result<(), TypeCheckerError> {
+        if self.current_function.is_none() {
+            return Ok(());
+        }
+
+        if let Type::Array { .. } = ty {
+            let size = ty.size_in_bytes();
+
+            if size == 0 {
+                return Err(TypeCheckerError::ZeroSizeArray {
+                    name: name.to_string(),
+                });
+            }
+
+            if size > self.max_stack_allocation {
+                return Err(TypeCheckerError::StackAllocationTooLarge {
+                    name: name.to_string(),
+                    size,
+                    max: self.max_stack_allocation,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Type of a type annotation with the fields of the structs it names
+    fn annotation_type(&self, kind: &TypeKind) -> Type {
+        resolve_struct_types(&self.structs, kind.clone().into())
+    }
+
+    /// Pointers may only be compared for equality with a compatible pointer or
+    /// `null`. A `null` operand is given the type of the other pointer so it
+    /// can be translated.
+    fn check_pointer_comparison(
+        &mut self,
+        expr: &mut BinaryOperation,
+        left_ty: Type,
+        right_ty: Type,
+    ) -> Result<(), TypeCheckerError> {
+        let is_comparable = match (&left_ty, &right_ty) {
+            (Type::Ptr(_), Type::Ptr(_)) => left_ty.is_compatible_with(&right_ty),
+            (Type::Ptr(_), Type::Null { .. }) | (Type::Null { .. }, Type::Ptr(_)) => true,
+            _ => false,
+        };
+
+        if !is_comparable || !matches!(expr.op, OpType::Equal | OpType::Different) {
+            return Err(TypeCheckerError::IncompatibleOperationType {
+                operator: expr.op,
+                left_ty,
+                right_ty,
+            });
+        }
+
+        let (null_operand, pointer_ty) = match (&left_ty, &right_ty) {
+            (Type::Null { .. }, _) => (Some(expr.left.as_mut()), right_ty),
+            (_, Type::Null { .. }) => (expr.right.as_deref_mut(), left_ty),
+            _ => (None, left_ty),
+        };
+
+        if let Some(null_operand) = null_operand {
+            let null_ty = Type::Null {
+                concrete_type: Some(Box::new(pointer_ty)),
+            };
+            ExpressionTypeSetter::new(&null_ty).set_type_recusively(null_operand);
+        }
+
+        expr.set_type(Type::Bool);
+        self.current_type = Some(Type::Bool);
+
+        Ok(())
+    }
+
+    fn check_builtin_call(
+        &mut self,
+        builtin: Builtin,
+        expr: &'ast mut Call,
+    ) -> Result<(), TypeCheckerError> {
+        match builtin {
+            Builtin::Min | Builtin::Max => self.check_min_max_call(builtin, expr),
+            Builtin::IsNull => self.check_is_null_call(builtin, expr),
+            Builtin::Len => self.check_len_call(builtin, expr),
+        }
+    }
+
+    /// `min` and `max` take two numbers of the same type and return a value of
+    /// this type.
+    fn check_min_max_call(
+        &mut self,
+        builtin: Builtin,
+        expr: &'ast mut Call,
+    ) -> Result<(), TypeCheckerError> {
+        if expr.arguments.len() != 2 {
+            return Err(TypeCheckerError::BadParameterCount {
+                expected: 2,
+                got: expr.arguments.len() as u32,
+            });
+        }
+
+        self.visit_expression(&mut expr.arguments[0])?;
+        let left = self.current_type.clone().expect("Argument should be typed");
+        self.visit_expression(&mut expr.arguments[1])?;
+        let right = self.current_type.clone().expect("Argument should be typed");
+
+        let is_number = |ty: &Type| ty.is_integer() || *ty == Type::Float;
+        if !is_number(&left) || !is_number(&right) || !left.is_compatible_with(&right) {
+            return Err(TypeCheckerError::BadBuiltinArguments {
+                name: builtin.name().to_string(),
+                left,
+                right,
+            });
+        }
+
+        // An integer literal takes the type of the other argument
+        let ty = if left == Type::Int { right } else { left };
+        expr.set_type(ty.clone());
+        self.current_type = Some(ty);
+        Ok(())
+    }
+
+    /// `is_null` takes a pointer and returns whether it is null.
+    fn check_is_null_call(
+        &mut self,
+        builtin: Builtin,
+        expr: &'ast mut Call,
+    ) -> Result<(), TypeCheckerError> {
+        if expr.arguments.len() != 1 {
+            return Err(TypeCheckerError::BadParameterCount {
+                expected: 1,
+                got: expr.arguments.len() as u32,
+            });
+        }
+
+        self.visit_expression(&mut expr.arguments[0])?;
+        let ty = self.current_type.clone().expect("Argument should be typed");
+
+        if !matches!(ty, Type::Ptr(_)) {
+            return Err(TypeCheckerError::ExpectedPointerArgument {
+                name: builtin.name().to_string(),
+                ty,
+            });
+        }
+
+        expr.set_type(Type::Bool);
+        self.current_type = Some(Type::Bool);
+        Ok(())
+    }
+
+    /// `len` takes a string literal and returns its size in bytes, known at
+    /// compile time.
+    fn check_len_call(
+        &mut self,
+        builtin: Builtin,
+        expr: &'ast mut Call,
+    ) -> Result<(), TypeCheckerError> {
+        if expr.arguments.len() != 1 {
+            return Err(TypeCheckerError::BadParameterCount {
+                expected: 1,
+                got: expr.arguments.len() as u32,
+            });
+        }
+
+        if !matches!(
+            &expr.arguments[0],
+            Expression::Literal(Literal {
+                literal_type: LiteralType::String(_),
+                ..
+            })
+        ) {
+            return Err(TypeCheckerError::ExpectedStringLiteral {
+                name: builtin.name().to_string(),
+                location: expr.arguments[0].get_location().clone(),
+            });
+        }
+
+        self.visit_expression(&mut expr.arguments[0])?;
+        expr.set_type(Type::U64);
+        self.current_type = Some(Type::U64);
+        Ok(())
+    }
+}
+
+/// A type converted from a type annotation only knows the names of the
+/// structs it uses. Replace them by the declared struct types holding their
+/// fields. Fields of these structs are left as declared, which keeps
+/// recursive structs finite.
+fn resolve_struct_types(structs: &HashMap<String, Type>, ty: Type) -> Type {
+    match ty {
+        Type::Struct { ref name, .. } => structs.get(name).cloned().unwrap_or(ty),
+        Type::Array { size, array_type } => Type::Array {
+            size,
+            array_type: Box::new(resolve_struct_types(structs, *array_type)),
+        },
+        Type::Ptr(pointee) => Type::Ptr(Box::new(resolve_struct_types(structs, *pointee))),
+        Type::Slice(base_type) => Type::Slice(Box::new(resolve_struct_types(structs, *base_type))),
+        Type::Function {
+            parameters,
+            return_type,
+        } => Type::Function {
+            parameters: parameters
+                .into_iter()
+                .map(|(ty, name)| (resolve_struct_types(structs, ty), name))
+                .collect(),
+            return_type: Box::new(resolve_struct_types(structs, *return_type)),
+        },
+        Type::Tuple(elements) => Type::Tuple(
+            elements
+                .into_iter()
+                .map(|ty| resolve_struct_types(structs, ty))
+                .collect(),
+        ),
+        _ => ty,
+    }
+}
+
+/// Set the struct type from its fields declarations and return it.
+fn set_struct_type(stmt: &mut StructStatement) -> Type {
+    let ty = Type::Struct {
+        name: stmt.name.clone(),
+        fields: stmt
+            .fields
+            .iter()
+            .map(|(kind, name)| (Type::from(kind.clone()), name.clone()))
+            .collect(),
+    };
+
+    stmt.set_type(ty.clone());
+    ty
+}
+
+/// Every attribute must be known and `@inline` excludes `@noinline`.
+fn check_attributes(stmt: &FunctionStatement) -> Result<(), TypeCheckerError> {
+    if let Some(unknown) = stmt
+        .attributes
+        .iter()
+        .find(|attribute| attribute.kind().is_none())
+    {
+        return Err(TypeCheckerError::UnknownAttribute {
+            name: unknown.to_string(),
+            location: unknown.get_location().clone(),
+        });
+    }
+
+    if stmt.has_attribute(AttributeKind::Inline) && stmt.has_attribute(AttributeKind::NoInline) {
+        return Err(TypeCheckerError::ConflictingAttributes {
+            name: stmt.name.clone(),
+            location: stmt.get_location().clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Set the function and its parameters types from their declarations and
+/// return the function type.
+fn set_signature_type(stmt: &mut FunctionStatement, structs: &HashMap<String, Type>) -> Type {
+    // Set parameters type
+    for parameter in stmt.parameters.iter_mut() {
+        parameter.set_type(resolve_struct_types(
+            structs,
+            parameter
+                .declaration_type
+                .clone()
+                .expect("Parameter has no type hint!")
+                .into(),
+        ))
+    }
+
+    let function_type = Type::Function {
+        parameters: stmt
+            .parameters
+            .iter()
+            .map(|let_stmt| (let_stmt.get_type().clone(), let_stmt.name.clone()))
+            .collect(),
+        return_type: Box::new(resolve_struct_types(
+            structs,
+            stmt.return_type.clone().into(),
+        )),
+    };
+
+    stmt.set_type(function_type.clone());
+    function_type
+}
+
+/// Set the enum type, variants are numbered in declaration order.
+fn set_enum_type(stmt: &mut EnumStatement) {
+    let variants = stmt
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(discriminant, variant)| (variant.clone(), discriminant as i64))
+        .collect();
+
+    stmt.set_type(Type::Enum {
+        name: stmt.name.clone(),
+        variants,
+    });
+}
+
+/// Return whether every path through `stmts` ends with a return statement.
+/// Loop bodies may never be executed so they are not taken into account.
+fn always_returns(stmts: &ast::Statements) -> bool {
+    stmts.statements.iter().any(|stmt| match &stmt.kind {
+        ast::StatementKind::Return(_) => true,
+        ast::StatementKind::If(IfStatement {
+            then_clause,
+            else_clause: Some(else_clause),
+            ..
+        }) => always_returns(then_clause) && always_returns(else_clause),
+        _ => false,
+    })
+}
+
+/// Like in C, an array variable passed where a pointer to its elements is
+/// expected decays to the address of its first element
+fn decays_to(argument: &Expression, argument_type: &Type, expected_type: &Type) -> bool {
+    match (argument, argument_type, expected_type) {
+        (Expression::Literal(_), Type::Array { array_type, .. }, Type::Ptr(pointee)) => {
+            array_type == pointee
+        }
+        _ => false,
+    }
 }
 
 impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
@@ -67,30 +483,26 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
         &mut self,
         stmt: &'ast mut FunctionStatement,
     ) -> Result<(), TypeCheckerError> {
-        // Set parameters type
-        for parameter in stmt.parameters.iter_mut() {
-            parameter.set_type(Type::from(
-                parameter
-                    .declaration_type
-                    .clone()
-                    .expect("Parameter has no type hint!"),
-            ))
+        self.current_function = Some(set_signature_type(stmt, &self.structs));
+        check_attributes(stmt)?;
+
+        // LLVM only supports variadic functions using the C calling convention
+        if stmt.is_variadic && stmt.calling_convention != CallingConvention::C {
+            return Err(TypeCheckerError::NonCVariadicFunction {
+                name: stmt.name.clone(),
+                abi: stmt.calling_convention.abi(),
+                location: stmt.get_location().clone(),
+            });
         }
 
-        let function_type = Type::Function {
-            parameters: stmt
-                .parameters
-                .iter()
-                .map(|let_stmt| (let_stmt.get_type().clone(), let_stmt.name.clone()))
-                .collect(),
-            return_type: Box::new(stmt.return_type.clone().into()),
-        };
-
-        self.current_function = Some(function_type.clone());
-        stmt.set_type(function_type);
-
         if let Some(body) = stmt.body.as_mut() {
             self.visit_statements(body)?;
+
+            if !matches!(stmt.return_type, ast::TypeKind::Void) && !always_returns(body) {
+                return Err(TypeCheckerError::MissingReturn {
+                    name: stmt.name.clone(),
+                });
+            }
         }
 
         self.current_function = None;
@@ -118,8 +530,20 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
                     Err(TypeCheckerError::ReturnTypeMismatch {
                         got: self.current_type.clone().unwrap(),
                         expected: return_type.deref().clone(),
+                        location: stmt.get_location().clone(),
                     })
                 } else {
+                    // A returned null takes the function return type
+                    if let Some(Type::Null { .. }) = self.current_type {
+                        let set_ty = Type::Null {
+                            concrete_type: Some(return_type.clone()),
+                        };
+                        let mut setter = ExpressionTypeSetter::new(&set_ty);
+                        setter.set_type_recusively(
+                            stmt.exp.as_mut().expect("A null return has an expression"),
+                        );
+                    }
+
                     Ok(())
                 }
             }
@@ -127,20 +551,35 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
         }
     }
 
-    fn visit_struct(&mut self, stmt: &'ast mut StructStatement) -> Result<(), TypeCheckerError> {
-        stmt.set_type(Type::Struct {
-            name: stmt.name.clone(),
-            fields: stmt
-                .fields
-                .iter()
-                .map(|(kind, name)| (Type::from(kind.clone()), name.clone()))
-                .collect(),
-        });
+    fn visit_let(&mut self, stmt: &'ast mut LetStatement) -> Result<(), TypeCheckerError> {
+        // Extern variables have no initializer, their type is the declared one
+        if stmt.is_extern {
+            let ty = self.annotation_type(
+                stmt.declaration_type
+                    .as_ref()
+                    .expect("Extern variable has no type"),
+            );
+            stmt.set_type(ty);
+            return Ok(());
+        }
 
-        Ok(())
-    }
+        // Check the declared type first, there is no need to type the
+        // initializer of an array that can't be allocated anyway
+        if let Some(ty) = &stmt.declaration_type {
+            self.check_stack_allocation(&stmt.name, &self.annotation_type(ty))?;
+        }
+
+        // Uninitialized variables can only get their type from the annotation
+        if stmt.init_exp.is_none() {
+            let ty =
+                self.annotation_type(stmt.declaration_type.as_ref().ok_or_else(|| {
+                    TypeCheckerError::InferenceError(stmt.get_location().clone())
+                })?);
+            self.current_type = Some(ty.clone());
+            stmt.set_type(ty);
+            return Ok(());
+        }
 
-    fn visit_let(&mut self, stmt: &'ast mut LetStatement) -> Result<(), TypeCheckerError> {
         self.visit_expression(
             stmt.init_exp
                 .as_mut()
@@ -149,7 +588,7 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
 
         match &stmt.declaration_type {
             Some(ty) => {
-                let real_type: Type = ty.clone().into();
+                let real_type = self.annotation_type(ty);
 
                 if !real_type
                     .is_compatible_with(self.current_type.as_ref().expect("let init has no type"))
@@ -185,8 +624,45 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
                     ));
                 }
 
-                stmt.set_type(self.current_type.as_ref().unwrap().clone());
+                let ty = self.current_type.clone().unwrap();
+                self.check_stack_allocation(&stmt.name, &ty)?;
+                stmt.set_type(ty);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_let_tuple(
+        &mut self,
+        stmt: &'ast mut LetTupleStatement,
+    ) -> Result<(), TypeCheckerError> {
+        self.visit_expression(&mut stmt.init_exp)?;
+
+        let init_type = self.current_type.clone().expect("let init has no type");
+        let element_types = match (stmt.kind, init_type) {
+            (DestructuringKind::Tuple, Type::Tuple(element_types))
+                if element_types.len() == stmt.declarations.len() =>
+            {
+                element_types
+            }
+            (DestructuringKind::Array, Type::Array { size, array_type })
+                if size as usize == stmt.declarations.len() =>
+            {
+                vec![*array_type; stmt.declarations.len()]
+            }
+            (_, init_type) => {
+                return Err(TypeCheckerError::BadDestructuring {
+                    expected: stmt.declarations.len(),
+                    ty: init_type,
+                    location: stmt.get_location().clone(),
+                })
             }
+        };
+
+        for (declaration, ty) in stmt.declarations.iter_mut().zip(element_types) {
+            self.check_stack_allocation(&declaration.name, &ty)?;
+            declaration.set_type(ty);
         }
 
         Ok(())
@@ -207,9 +683,26 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
         self.check_bool_expression(&mut stmt.condition)?;
         self.visit_statements(&mut stmt.body)?;
 
+        if let Some(else_clause) = &mut stmt.else_clause {
+            self.visit_statements(else_clause)?;
+        }
+
         Ok(())
     }
 
+    fn visit_assert(&mut self, stmt: &'ast mut AssertStatement) -> Result<(), TypeCheckerError> {
+        self.check_bool_expression(&mut stmt.condition)?;
+
+        match &self.incompatible_assert_function {
+            Some((name, expected)) => Err(TypeCheckerError::IncompatibleAssertFunction {
+                name: name.clone(),
+                expected: expected.clone(),
+                location: stmt.get_location().clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
     fn visit_for(&mut self, stmt: &'ast mut ForStatement) -> Result<(), TypeCheckerError> {
         self.visit_let(&mut stmt.init_decl)?;
         self.check_bool_expression(&mut stmt.continue_expression)?;
@@ -222,6 +715,22 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
     fn visit_assignment(&mut self, expr: &'ast mut Assignment) -> Result<(), TypeCheckerError> {
         self.visit_expression(&mut expr.left)?;
 
+        if let Expression::Literal(
+            literal @ Literal {
+                literal_type: LiteralType::Identifier(name),
+                ..
+            },
+        ) = expr.left.as_ref()
+        {
+            // Functions can be read as pointers but never assigned
+            if literal.get_definition().is_function()
+                || (literal.get_definition().is_local_variable()
+                    && literal.get_local_variable_def().is_const)
+            {
+                return Err(TypeCheckerError::ConstantAssignment { name: name.clone() });
+            }
+        }
+
         let lhs_ty = self
             .current_type
             .clone()
@@ -241,14 +750,30 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
             });
         }
 
+        // An assignment yields the assigned value. The left hand side type is
+        // used as the right one may still be an integer literal.
+        expr.set_type(lhs_ty.clone());
         self.current_type = Some(lhs_ty);
 
         Ok(())
     }
 
     fn visit_call(&mut self, expr: &'ast mut Call) -> Result<(), TypeCheckerError> {
+        if let Definition::Builtin(builtin) = expr.get_definition() {
+            let builtin = *builtin;
+            return self.check_builtin_call(builtin, expr);
+        }
+
         if expr.get_definition().is_function() {
-            if expr.arguments.len() != expr.get_function_def().parameters.len() {
+            let expected_count = expr.get_function_def().parameters.len();
+            // Variadic functions accept extra arguments after their fixed parameters
+            let valid_count = if expr.get_function_def().is_variadic {
+                expr.arguments.len() >= expected_count
+            } else {
+                expr.arguments.len() == expected_count
+            };
+
+            if !valid_count {
                 return Err(TypeCheckerError::BadParameterCount {
                     expected: expr.get_function_def().parameters.len() as u32,
                     got: expr.arguments.len() as u32,
@@ -266,8 +791,10 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
                 );
             }
 
-            for (expr_type, function_parameter) in parameter_types
+            for ((argument, expr_type), function_parameter) in expr
+                .arguments
                 .iter()
+                .zip(parameter_types.iter())
                 .zip(expr.get_function_def().parameters.iter())
             {
                 let expected_type = function_parameter
@@ -275,25 +802,101 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
                     .clone()
                     .expect("Parameter should be typed");
 
-                if !expr_type.is_compatible_with(&expected_type) {
+                if !expr_type.is_compatible_with(&expected_type)
+                    && !decays_to(argument, expr_type, &expected_type)
+                {
                     return Err(TypeCheckerError::BadParameter {
                         name: function_parameter.name.clone(),
                         expected_type,
-                        got: self.current_type.clone().unwrap(),
+                        got: expr_type.clone(),
                     });
                 }
             }
 
             // A call expression type is the function return type
-            self.current_type = Some(expr.get_function_def().return_type.clone().into());
+            let return_type = self.annotation_type(&expr.get_function_def().return_type);
+            expr.set_type(return_type.clone());
+            self.current_type = Some(return_type);
             Ok(())
         } else {
-            Err(TypeCheckerError::NotCallable(expr.get_definition().clone()))
+            Err(TypeCheckerError::NotCallable {
+                name: expr.callee.clone(),
+                location: expr.get_location().clone(),
+            })
+        }
+    }
+
+    fn visit_indirect_call(
+        &mut self,
+        expr: &'ast mut IndirectCall,
+    ) -> Result<(), TypeCheckerError> {
+        self.visit_expression(&mut expr.callee)?;
+        let (parameters, return_type) = match expr.callee.get_type() {
+            Type::Ptr(pointee) => match pointee.as_ref() {
+                Type::Function {
+                    parameters,
+                    return_type,
+                } => (parameters.clone(), return_type.as_ref().clone()),
+                _ => {
+                    return Err(TypeCheckerError::NotAFunctionPointer {
+                        ty: expr.callee.get_type().clone(),
+                        location: expr.callee.get_location().clone(),
+                    })
+                }
+            },
+            ty => {
+                return Err(TypeCheckerError::NotAFunctionPointer {
+                    ty: ty.clone(),
+                    location: expr.callee.get_location().clone(),
+                })
+            }
+        };
+
+        if expr.arguments.len() != parameters.len() {
+            return Err(TypeCheckerError::BadParameterCount {
+                expected: parameters.len() as u32,
+                got: expr.arguments.len() as u32,
+            });
+        }
+
+        // Parameters of a function pointer type have no name, they are
+        // reported by position
+        for (i, (argument, (expected_type, _))) in
+            expr.arguments.iter_mut().zip(parameters).enumerate()
+        {
+            self.visit_expression(argument)?;
+            let got = self
+                .current_type
+                .clone()
+                .expect("Parameter expression should be typed");
+
+            if !got.is_compatible_with(&expected_type) && !decays_to(argument, &got, &expected_type)
+            {
+                return Err(TypeCheckerError::BadParameter {
+                    name: i.to_string(),
+                    expected_type,
+                    got,
+                });
+            }
         }
+
+        expr.set_type(return_type.clone());
+        self.current_type = Some(return_type);
+        Ok(())
+    }
+
+    fn visit_group(&mut self, expr: &'ast mut Group) -> Result<(), TypeCheckerError> {
+        self.visit_expression(&mut expr.expr)?;
+        expr.set_type(
+            self.current_type
+                .clone()
+                .expect("Group expression should have a type"),
+        );
+        Ok(())
     }
 
     fn visit_type(&mut self, ty: &'ast mut crate::ast::Type) -> Result<(), TypeCheckerError> {
-        self.current_type = Some(ty.kind.clone().into());
+        self.current_type = Some(self.annotation_type(&ty.kind));
         Ok(())
     }
 
@@ -317,7 +920,15 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
                     .as_ref()
                     .expect("No right type in binary operation!");
 
-                if !left_ty.is_compatible_with(right_ty) {
+                if matches!(left_ty, Type::Ptr(_) | Type::Null { .. })
+                    || matches!(right_ty, Type::Ptr(_) | Type::Null { .. })
+                {
+                    let right_ty = right_ty.clone();
+                    return self.check_pointer_comparison(expr, left_ty, right_ty);
+                }
+
+                // Tuples can only be built and destructured
+                if !left_ty.is_compatible_with(right_ty) || matches!(left_ty, Type::Tuple(_)) {
                     return Err(TypeCheckerError::IncompatibleOperationType {
                         operator: expr.op,
                         left_ty,
@@ -334,6 +945,22 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
                         | OpType::Divide
                         | OpType::Modulo
                 ) {
+                    // Concatenating strings would require to allocate the result
+                    match (expr.op, right_ty) {
+                        (OpType::Plus, Type::String) => {
+                            return Err(TypeCheckerError::StringConcatenation)
+                        }
+                        // Booleans and enum variants can only be compared
+                        (_, Type::String | Type::Bool | Type::Enum { .. }) => {
+                            return Err(TypeCheckerError::IncompatibleOperationType {
+                                operator: expr.op,
+                                left_ty,
+                                right_ty: right_ty.clone(),
+                            })
+                        }
+                        _ => (),
+                    }
+
                     expr.set_type(right_ty.clone());
                     self.current_type = Some(right_ty.clone());
                 } else {
@@ -401,14 +1028,29 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
                         literal.set_type(literal.get_local_variable_def().get_type().clone());
                     }
                     Definition::Function(_) => {
-                        self.current_type = Some(literal.get_function_def().get_type().clone());
-                        literal.set_type(literal.get_function_def().get_type().clone());
+                        // Indirect calls use the C calling convention
+                        let function = literal.get_function_def();
+                        if function.calling_convention != CallingConvention::C {
+                            return Err(TypeCheckerError::NonCFunctionPointer {
+                                name: function.name.clone(),
+                                abi: function.calling_convention.abi(),
+                                location: literal.get_location().clone(),
+                            });
+                        }
+
+                        let ty = Type::Ptr(Box::new(function.get_type().clone()));
+                        self.current_type = Some(ty.clone());
+                        literal.set_type(ty);
                     }
+                    Definition::Builtin(_) => unreachable!("Builtins can only be called"),
+                    Definition::Enum(_) => unreachable!("Enums are only used by their variants"),
                 }
             }
             LiteralType::ArrayAccess(_) => {
                 let ty = match literal.get_definition() {
-                    Definition::Struct(_) => unreachable!(),
+                    Definition::Struct(_) | Definition::Enum(_) | Definition::Builtin(_) => {
+                        unreachable!()
+                    }
                     Definition::LocalVariable(_) => {
                         literal.get_local_variable_def().get_type().clone()
                     }
@@ -423,12 +1065,17 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
                     }
                 };
                 match ty {
-                    Type::Array { array_type, .. } => {
+                    Type::Array { array_type, .. } | Type::Slice(array_type) => {
                         literal.set_type(array_type.clone().deref().to_owned());
                     }
                     _ => return Err(TypeCheckerError::NonSubscriptable { ty }),
                 }
             }
+            LiteralType::EnumVariant { .. } => {
+                let ty = literal.get_enum_def().get_type().clone();
+                self.current_type = Some(ty.clone());
+                literal.set_type(ty);
+            }
             LiteralType::Null(_) => {
                 self.current_type = Some(Type::Null {
                     concrete_type: None,
@@ -505,16 +1152,113 @@ impl<'ast> MutableVisitor<'ast, TypeCheckerError> for TypeChecker {
         Ok(())
     }
 
+    fn visit_tuple(&mut self, expr: &'ast mut Tuple) -> Result<(), TypeCheckerError> {
+        let mut element_types = Vec::with_capacity(expr.values.len());
+        for value in expr.values.iter_mut() {
+            self.visit_expression(value)?;
+
+            // Unlike a variable, a tuple element has no declared type a
+            // `null` could take
+            match self
+                .current_type
+                .clone()
+                .expect("Tuple element has no type")
+            {
+                Type::Null { .. } => {
+                    return Err(TypeCheckerError::InferenceError(
+                        value.get_location().clone(),
+                    ))
+                }
+                ty => element_types.push(ty),
+            }
+        }
+
+        let ty = Type::Tuple(element_types);
+        expr.set_type(ty.clone());
+        self.current_type = Some(ty);
+
+        Ok(())
+    }
+
+    /// `addrof arr` is a pointer to the whole array (`ptr [3; i32]`). The
+    /// decayed form pointing to the first element is written `addrof arr[0]`
+    /// and has the `ptr i32` type. Arrays only decay implicitly when passed
+    /// to a pointer parameter.
     fn visit_addrof(&mut self, expr: &'ast mut AddrOf) -> Result<(), TypeCheckerError> {
         self.visit_expression(&mut expr.expr)?;
 
-        self.current_type = Some(Type::Ptr(Box::new(
+        let ty = Type::Ptr(Box::new(
             self.current_type.clone().expect("Should have a type"),
-        )));
+        ));
+        expr.set_type(ty.clone());
+        self.current_type = Some(ty);
+
+        Ok(())
+    }
+
+    fn visit_slice(&mut self, expr: &'ast mut Slice) -> Result<(), TypeCheckerError> {
+        self.visit_expression(&mut expr.expr)?;
+
+        let base_type = match self.current_type.as_ref().expect("Should have a type") {
+            Type::Array { array_type, .. } | Type::Slice(array_type) => array_type.clone(),
+            ty => return Err(TypeCheckerError::NonSubscriptable { ty: ty.clone() }),
+        };
+
+        for bound in [&mut expr.start, &mut expr.end] {
+            self.visit_expression(bound)?;
+            let bound_type = self.current_type.as_ref().expect("Should have a type");
+            if !bound_type.is_integer() {
+                return Err(TypeCheckerError::IndexNotInteger {
+                    got: bound_type.clone(),
+                });
+            }
+        }
+
+        expr.set_type(Type::Slice(base_type.clone()));
+        self.current_type = Some(Type::Slice(base_type));
+
+        Ok(())
+    }
+
+    fn visit_alignof(&mut self, expr: &'ast mut AlignOf) -> Result<(), TypeCheckerError> {
+        let aligned_type = self.annotation_type(&expr.aligned_type.kind);
+        if matches!(aligned_type, Type::Void | Type::Function { .. }) {
+            return Err(TypeCheckerError::NoAlignment {
+                ty: aligned_type,
+                location: expr.get_location().clone(),
+            });
+        }
+
+        expr.set_type(Type::U64);
+        self.current_type = Some(Type::U64);
 
         Ok(())
     }
 
+    /// `expr?` yields the pointer `expr` and returns null from the enclosing
+    /// function, which must return a pointer as well
+    fn visit_try_null(&mut self, expr: &'ast mut TryNull) -> Result<(), TypeCheckerError> {
+        self.visit_expression(&mut expr.expr)?;
+
+        let ty = self.current_type.clone().expect("Should have a type");
+        if !matches!(ty, Type::Ptr(_)) {
+            return Err(TypeCheckerError::TryNullNonPointer {
+                ty,
+                location: expr.get_location().clone(),
+            });
+        }
+
+        match &self.current_function {
+            Some(Type::Function { return_type, .. }) if matches!(**return_type, Type::Ptr(_)) => {
+                expr.set_type(ty);
+                Ok(())
+            }
+            _ => Err(TypeCheckerError::TryNullOutsidePointerFunction {
+                location: expr.get_location().clone(),
+            }),
+        }
+    }
+
     fn visit_deref(&mut self, expr: &'ast mut ast::Deref) -> Result<(), TypeCheckerError> {
         self.visit_expression(&mut expr.expr)?;
 
@@ -0,0 +1,154 @@
+use std::io;
+
+use crate::ast::{
+    EnumStatement, Expression, FunctionStatement, GlobalStatement, LetStatement, Locatable,
+    StructStatement, Visitor,
+};
+
+use super::Typable;
+
+pub type TypeDumperResult = Result<(), io::Error>;
+
+/// Prints the type resolved for each declaration and expression of a type
+/// checked AST. Expressions are shown as they are written in the source code
+/// and their sub expressions are indented below them.
+///
+/// ```text
+/// let a: i64
+///   1 + 2: i64
+///     1: i64
+///     2: i64
+/// ```
+pub struct TypeDumper<'src, Writer: io::Write> {
+    source_code: &'src str,
+    indent_level: usize,
+    writer: Writer,
+}
+
+impl<'src, T: io::Write> TypeDumper<'src, T> {
+    pub fn new(writer: T, source_code: &'src str) -> Self {
+        Self {
+            source_code,
+            indent_level: 0,
+            writer,
+        }
+    }
+
+    pub fn dump(&mut self, statements: &[GlobalStatement]) -> TypeDumperResult {
+        for stmt in statements {
+            self.visit_global_statement(stmt)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn into_writer(self) -> T {
+        self.writer
+    }
+
+    fn write_line(&mut self, content: &str) -> TypeDumperResult {
+        writeln!(
+            self.writer,
+            "{}{content}",
+            " ".repeat(self.indent_level * 2)
+        )
+    }
+
+    fn source_of(&self, expr: &Expression) -> String {
+        let location = expr.get_location();
+
+        // Expressions built by the compiler may not map to the source code
+        self.source_code
+            .get(location.begin..location.end)
+            .map(|source| source.split_whitespace().collect::<Vec<_>>().join(" "))
+            .unwrap_or_else(|| String::from("<generated>"))
+    }
+}
+
+impl<'ast, T: io::Write> Visitor<'ast, io::Error> for TypeDumper<'_, T> {
+    fn visit_function(&mut self, stmt: &'ast FunctionStatement) -> TypeDumperResult {
+        self.write_line(&format!("function {}: {}", stmt.name, stmt.get_type()))?;
+        self.indent_level += 1;
+
+        for parameter in &stmt.parameters {
+            self.visit_let(parameter)?;
+        }
+
+        if let Some(body) = &stmt.body {
+            self.visit_statements(body)?;
+        }
+
+        self.indent_level -= 1;
+        Ok(())
+    }
+
+    fn visit_struct(&mut self, stmt: &'ast StructStatement) -> TypeDumperResult {
+        self.write_line(&format!("struct {}", stmt.name))?;
+        self.indent_level += 1;
+
+        if let super::Type::Struct { fields, .. } = stmt.get_type() {
+            for (ty, name) in fields {
+                self.write_line(&format!("{name}: {ty}"))?;
+            }
+        }
+
+        self.indent_level -= 1;
+        Ok(())
+    }
+
+    fn visit_enum(&mut self, stmt: &'ast EnumStatement) -> TypeDumperResult {
+        self.write_line(&format!("enum {}", stmt.name))?;
+        self.indent_level += 1;
+
+        if let super::Type::Enum { variants, .. } = stmt.get_type() {
+            for (variant, discriminant) in variants {
+                self.write_line(&format!("{variant} = {discriminant}"))?;
+            }
+        }
+
+        self.indent_level -= 1;
+        Ok(())
+    }
+
+    fn visit_let(&mut self, stmt: &'ast LetStatement) -> TypeDumperResult {
+        let keyword = match (stmt.is_extern, stmt.is_const, stmt.is_static) {
+            (true, _, _) => "extern let",
+            (false, true, _) => "const",
+            (false, false, true) => "static",
+            (false, false, false) => "let",
+        };
+        self.write_line(&format!("{keyword} {}: {}", stmt.name, stmt.get_type()))?;
+
+        if let Some(init_exp) = &stmt.init_exp {
+            self.indent_level += 1;
+            self.visit_expression(init_exp)?;
+            self.indent_level -= 1;
+        }
+
+        Ok(())
+    }
+
+    fn visit_expression(&mut self, expr: &'ast Expression) -> TypeDumperResult {
+        self.write_line(&format!("{}: {}", self.source_of(expr), expr.get_type()))?;
+        self.indent_level += 1;
+
+        match expr {
+            Expression::Group(g) => self.visit_expression(&g.expr)?,
+            Expression::BinaryOperation(bo) => self.visit_binary_operation(bo)?,
+            Expression::Literal(l) => self.visit_literal(l)?,
+            Expression::Call(c) => self.visit_call(c)?,
+            Expression::IndirectCall(c) => self.visit_indirect_call(c)?,
+            Expression::Assignment(a) => self.visit_assignment(a)?,
+            Expression::ArrayInitializer(a) => self.visit_array_initializer(a)?,
+            Expression::AddrOf(a) => self.visit_addrof(a)?,
+            Expression::Deref(d) => self.visit_deref(d)?,
+            Expression::Slice(s) => self.visit_slice(s)?,
+            Expression::AlignOf(a) => self.visit_alignof(a)?,
+            Expression::Tuple(t) => self.visit_tuple(t)?,
+            Expression::TryNull(t) => self.visit_try_null(t)?,
+        }
+
+        self.indent_level -= 1;
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
 use std::convert::Infallible;
 
 use crate::ast::{
-    ArrayInitializer, Assignment, BinaryOperation, Call, Expression, Literal, LiteralType,
-    MutableVisitor,
+    ArrayInitializer, Assignment, BinaryOperation, Bindable, Call, Definition, Expression, Group,
+    IndirectCall, Literal, LiteralType, MutableVisitor, Tuple,
 };
 
 use super::{Typable, Type};
@@ -48,12 +48,33 @@ impl<'ast, 'ty> MutableVisitor<'ast, Infallible> for ExpressionTypeSetter<'ty> {
         Ok(())
     }
 
+    fn visit_group(&mut self, expr: &'ast mut Group) -> Result<(), Infallible> {
+        self.visit_expression(&mut expr.expr)?;
+        expr.set_type(self.new_type.clone());
+        Ok(())
+    }
+
     fn visit_assignment(&mut self, expr: &'ast mut Assignment) -> Result<(), Infallible> {
         expr.set_type(self.new_type.clone());
         Ok(())
     }
 
+    /// The callee and the arguments of an indirect call don't have the type
+    /// of its result
+    fn visit_indirect_call(&mut self, expr: &'ast mut IndirectCall) -> Result<(), Infallible> {
+        expr.set_type(self.new_type.clone());
+        Ok(())
+    }
+
     fn visit_call(&mut self, expr: &'ast mut Call) -> Result<(), Infallible> {
+        // Builtins like `min` return a value of their arguments type
+        if matches!(expr.get_definition(), Definition::Builtin(builtin) if builtin.returns_argument_type())
+        {
+            for argument in expr.arguments.iter_mut() {
+                self.visit_expression(argument)?;
+            }
+        }
+
         expr.set_type(self.new_type.clone());
         Ok(())
     }
@@ -73,4 +94,16 @@ impl<'ast, 'ty> MutableVisitor<'ast, Infallible> for ExpressionTypeSetter<'ty> {
 
         Ok(())
     }
+
+    fn visit_tuple(&mut self, expr: &'ast mut Tuple) -> Result<(), Infallible> {
+        // Each element takes the type at its position in the tuple
+        if let Type::Tuple(element_types) = self.new_type {
+            for (value, ty) in expr.values.iter_mut().zip(element_types) {
+                ExpressionTypeSetter::new(ty).set_type_recusively(value);
+            }
+        }
+
+        expr.set_type(self.new_type.clone());
+        Ok(())
+    }
 }
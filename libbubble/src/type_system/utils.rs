@@ -32,6 +32,20 @@ impl<T> ScopedMap<T> {
         self.0.iter().rev().find_map(|scope| scope.get(symbol))
     }
 
+    /// Symbols declared in the innermost scope
+    pub fn current_scope_symbols(&self) -> impl Iterator<Item = (&String, &T)> {
+        self.0
+            .last()
+            .expect("current scope of an empty scoped map!")
+            .iter()
+    }
+
+    /// Symbols of every scope from the outermost to the innermost one.
+    /// Shadowed symbols are included.
+    pub fn all_symbols(&self) -> impl Iterator<Item = (&String, &T)> {
+        self.0.iter().flat_map(|scope| scope.iter())
+    }
+
     #[cfg(test)] // it is only used to tests the implementation
     pub fn len(&self) -> usize {
         self.0.len()
@@ -197,6 +211,38 @@ mod tests {
         assert!(a.is_none());
     }
 
+    #[test]
+    fn scoped_map_current_scope_symbols() {
+        let mut def: ScopedMap<u32> = ScopedMap::default();
+        def.insert_symbol("a", 1);
+        def.new_scope();
+        def.insert_symbol("b", 2);
+        def.insert_symbol("c", 3);
+
+        let mut symbols: Vec<(&String, &u32)> = def.current_scope_symbols().collect();
+        symbols.sort();
+        assert_eq!(symbols, [(&"b".to_string(), &2), (&"c".to_string(), &3)]);
+
+        def.delete_scope();
+        let symbols: Vec<(&String, &u32)> = def.current_scope_symbols().collect();
+        assert_eq!(symbols, [(&"a".to_string(), &1)]);
+    }
+
+    #[test]
+    fn scoped_map_all_symbols() {
+        let mut def: ScopedMap<u32> = ScopedMap::default();
+        def.insert_symbol("a", 1);
+        def.new_scope();
+        def.insert_symbol("a", 2);
+        def.insert_symbol("b", 3);
+
+        let symbols: Vec<(&String, &u32)> = def.all_symbols().collect();
+        assert_eq!(symbols.len(), 3);
+        assert_eq!(symbols[0], (&"a".to_string(), &1));
+        assert!(symbols[1..].contains(&(&"a".to_string(), &2)));
+        assert!(symbols[1..].contains(&(&"b".to_string(), &3)));
+    }
+
     #[test]
     fn scope_map_not_inserted() {
         let def: ScopedMap<ast::LetStatement> = ScopedMap::default();
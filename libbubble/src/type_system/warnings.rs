@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+use crate::ast::TokenLocation;
+
+/// Diagnostics that don't prevent the compilation. Each warning must be
+/// enabled by the pass producing it.
+#[derive(Error, Debug)]
+pub enum Warning {
+    #[error(
+        "variable '{name}' at offset {} shadows the declaration at offset {}",
+        location.begin,
+        shadowed.begin
+    )]
+    ShadowedVariable {
+        name: String,
+        location: TokenLocation,
+        shadowed: TokenLocation,
+    },
+}
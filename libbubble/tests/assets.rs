@@ -2,19 +2,25 @@ use std::{path::Path, process::Command};
 
 use inkwell::{
     context::Context,
-    targets::{CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine},
+    module::Module,
+    targets::{
+        CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple,
+    },
     OptimizationLevel,
 };
 use libbubble::{
-    ast::{GlobalStatement, Statements},
-    codegen::build_module,
+    ast::{GlobalStatement, SourceFile, Statements},
+    codegen::{build_module, find_crt_directory, HardeningOptions},
     desugar::desugar_ast,
     parser::{
         grammar::{GlobalStatementsParser, StatementsParser},
         lexer::Lexer,
         StatementsParserResult,
     },
-    type_system::{binder::*, run_type_checker as type_check, TypeCheckerError},
+    type_system::{
+        binder::*, run_type_checker as type_check, run_type_checker_for_target, TypeCheckerError,
+        DEFAULT_MAX_STACK_ALLOCATION,
+    },
 };
 
 const LD_LOADER_PATH: &str = "/lib64/ld-linux-x86-64.so.2";
@@ -32,47 +38,138 @@ pub fn parse_global_statements_input(code: &str) -> StatementsParserResult<Vec<G
 }
 
 pub fn run_type_checker(code: &str) -> Result<(), TypeCheckerError> {
+    type_check_code(code).map(|_| ())
+}
+
+pub fn type_check_code(code: &str) -> Result<Vec<GlobalStatement>, TypeCheckerError> {
     let mut stmts = parse_global_statements_input(code).expect("Failed to parse code");
     let mut binder = Binder::default();
     binder.bind_statements(&mut stmts).expect("Binder failed");
-    type_check(&mut stmts)
+    type_check(&mut stmts)?;
+
+    Ok(stmts)
 }
 
-pub fn build_and_link(code: &str, outname: &str, executable_name: &str) {
+pub fn translate_code<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    code: &str,
+    print_code: bool,
+) {
+    translate_code_with_hardening(
+        context,
+        module,
+        code,
+        HardeningOptions::default(),
+        print_code,
+    );
+}
+
+pub fn translate_code_with_hardening<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    code: &str,
+    hardening: HardeningOptions,
+    print_code: bool,
+) {
     let mut stmts = parse_global_statements_input(code).expect("Failed to parse code");
     let mut binder = Binder::default();
     binder.bind_statements(&mut stmts).expect("Binder failed");
     type_check(&mut stmts).expect("Type checker failed");
-    stmts = desugar_ast(stmts);
+    stmts = desugar_ast(stmts, &SourceFile::new("test.blb", code));
 
-    let context = Context::create();
-    let module = context.create_module("module");
+    build_module(
+        context,
+        module,
+        &create_target_machine().get_target_data(),
+        &stmts,
+        hardening,
+        print_code,
+    );
+}
+
+/// Same as `translate_code` but the code is type checked and translated for
+/// `target_machine`
+pub fn translate_code_for_target<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    code: &str,
+    target_machine: &TargetMachine,
+) {
+    let target_data = target_machine.get_target_data();
+    let mut stmts = parse_global_statements_input(code).expect("Failed to parse code");
+    let mut binder = Binder::default();
+    binder.bind_statements(&mut stmts).expect("Binder failed");
+    run_type_checker_for_target(
+        &mut stmts,
+        DEFAULT_MAX_STACK_ALLOCATION,
+        target_data.get_pointer_byte_size(None) * 8,
+    )
+    .expect("Type checker failed");
+    stmts = desugar_ast(stmts, &SourceFile::new("test.blb", code));
 
-    build_module(&context, &module, &stmts, true);
+    build_module(
+        context,
+        module,
+        &target_data,
+        &stmts,
+        HardeningOptions::default(),
+        false,
+    );
+}
+
+pub fn create_target_machine() -> TargetMachine {
     Target::initialize_x86(&InitializationConfig::default());
     let target = Target::from_name("x86-64").unwrap();
-    let target_machine = target
+    target
         .create_target_machine(
             &TargetMachine::get_default_triple(),
             "x86-64",
             "",
             OptimizationLevel::None,
-            RelocMode::Default,
+            RelocMode::PIC,
             CodeModel::Default,
         )
-        .unwrap();
+        .unwrap()
+}
+
+/// 32-bit x86 target machine, code built for it is only compiled
+pub fn create_i686_target_machine() -> TargetMachine {
+    Target::initialize_x86(&InitializationConfig::default());
+    let triple = TargetTriple::create("i686-unknown-linux-gnu");
+    Target::from_triple(&triple)
+        .unwrap()
+        .create_target_machine(
+            &triple,
+            "i686",
+            "",
+            OptimizationLevel::None,
+            RelocMode::PIC,
+            CodeModel::Default,
+        )
+        .unwrap()
+}
+
+pub fn build_and_link(code: &str, outname: &str, executable_name: &str) {
+    let context = Context::create();
+    let module = context.create_module("module");
+
+    translate_code(&context, &module, code, true);
+    let target_machine = create_target_machine();
 
     target_machine
         .write_to_file(&module, FileType::Object, Path::new(outname))
         .expect("Failed to build object file");
 
+    let crt_directory = find_crt_directory();
     let status_code = Command::new("ld")
         .arg("-m")
         .arg("elf_x86_64")
+        .arg("-pie")
         .arg(outname)
-        .arg("/usr/lib64/crt1.o") // C runtime
-        .arg("/usr/lib64/crti.o") // C runtime
-        .arg("/usr/lib64/crtn.o") // C runtime
+        .arg(crt_directory.join("Scrt1.o")) // C runtime (PIE variant)
+        .arg(crt_directory.join("crti.o")) // C runtime
+        .arg(crt_directory.join("crtn.o")) // C runtime
         .arg("-lc") // Link Lib C
         // Use ld-linux-* this is needed because we're linking against the C library
         .arg("-dynamic-linker")
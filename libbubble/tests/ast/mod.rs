@@ -0,0 +1,3 @@
+mod test_extern_symbols;
+mod test_printer;
+mod test_statements;
@@ -0,0 +1,77 @@
+use libbubble::ast::ExternSymbols;
+use rstest::rstest;
+
+use crate::assets::{parse_global_statements_input, type_check_code};
+
+#[rstest]
+#[case::no_extern("function f(): i32 { return 0; }", &[])]
+#[case::called_extern(
+    r#"
+    extern function puts(msg: string): i32;
+    function main(): i32 {
+        puts("hello");
+        return 0;
+    }"#,
+    &["puts"]
+)]
+#[case::declared_but_not_called(
+    r#"
+    extern function puts(msg: string): i32;
+    extern function malloc(size: u64): ptr void;
+    function main(): i32 {
+        if true { puts("hello"); }
+        return 0;
+    }"#,
+    &["puts"]
+)]
+#[case::call_in_argument(
+    r#"
+    extern function abs(n: i32): i32;
+    extern function exit(code: i32): void;
+    function main(): i32 {
+        exit(abs(42));
+        return 0;
+    }"#,
+    &["abs", "exit"]
+)]
+fn unresolved_extern_symbols(#[case] code: &str, #[case] expected: &[&str]) {
+    let stmts = parse_global_statements_input(code).expect("Failed to parse code");
+    let symbols = ExternSymbols::collect(&stmts);
+
+    assert_eq!(symbols.unresolved().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn extern_defined_in_another_module() {
+    let main_module = parse_global_statements_input(
+        r#"
+        extern function helper(): i32;
+        function main(): i32 { return helper(); }"#,
+    )
+    .expect("Failed to parse code");
+    let helper_module = parse_global_statements_input("function helper(): i32 { return 42; }")
+        .expect("Failed to parse code");
+
+    let mut symbols = ExternSymbols::collect(&main_module);
+    assert_eq!(symbols.unresolved().collect::<Vec<_>>(), ["helper"]);
+
+    symbols.add_statements(&helper_module);
+    assert_eq!(symbols.unresolved().count(), 0);
+}
+
+#[test]
+fn extern_used_as_function_pointer() {
+    // Functions used as values are only known once bound
+    let stmts = type_check_code(
+        r#"
+        extern function abs(n: i32): i32;
+        function main(): i32 {
+            let table: [1; ptr function(i32): i32] = [abs];
+            return table[0](-42);
+        }"#,
+    )
+    .expect("Failed to type check code");
+
+    let symbols = ExternSymbols::collect(&stmts);
+    assert_eq!(symbols.unresolved().collect::<Vec<_>>(), ["abs"]);
+}
@@ -0,0 +1,65 @@
+use libbubble::ast::{Expression, Printer, StatementKind, Visitor};
+
+use crate::assets::parse_statements_input;
+
+#[test]
+fn test_print_for_statement() {
+    let stmts = parse_statements_input("for i: i32 = 0; i < 10; i = i + 1 { break; }")
+        .expect("Failed to parse");
+    let StatementKind::For(for_stmt) = &stmts.statements[0].kind else {
+        panic!("Statement is not a for loop");
+    };
+
+    let mut output = Vec::new();
+    Printer::new(&mut output)
+        .visit_for(for_stmt)
+        .expect("Failed to print");
+    let output = String::from_utf8(output).expect("Output is not UTF-8");
+
+    assert_eq!(output, "for i: i32 = 0; i<10; i = i+1 {\n  break;\n}");
+    assert!(
+        parse_statements_input(&output).is_ok(),
+        "Printed for loop does not parse: {output}"
+    );
+}
+
+#[test]
+fn test_print_escaped_string() {
+    let source_code = r#""a\0b\n\"c\"\\";"#;
+    let stmts = parse_statements_input(source_code).expect("Failed to parse");
+    let StatementKind::Expression { expr, .. } = &stmts.statements[0].kind else {
+        panic!("Statement is not an expression");
+    };
+    let Expression::Literal(literal) = expr.as_ref() else {
+        panic!("Expression is not a literal");
+    };
+
+    let mut output = Vec::new();
+    Printer::new(&mut output)
+        .visit_literal(literal)
+        .expect("Failed to print");
+    let output = String::from_utf8(output).expect("Output is not UTF-8");
+
+    assert_eq!(output, r#""a\0b\n\"c\"\\""#);
+}
+
+#[test]
+fn test_print_function_type() {
+    let stmts = parse_statements_input("let f: ptr function(i32, bool): void = null;")
+        .expect("Failed to parse");
+    let StatementKind::Let(let_stmt) = &stmts.statements[0].kind else {
+        panic!("Statement is not a let");
+    };
+    let declaration_type = let_stmt
+        .declaration_type
+        .as_ref()
+        .expect("Let has no declared type");
+
+    let mut output = Vec::new();
+    Printer::new(&mut output)
+        .visit_type_kind(declaration_type)
+        .expect("Failed to print");
+    let output = String::from_utf8(output).expect("Output is not UTF-8");
+
+    assert_eq!(output, "pointer of function(i32, bool): <void>");
+}
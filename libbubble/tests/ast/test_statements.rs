@@ -0,0 +1,74 @@
+use libbubble::ast::{BreakStatement, ContinueStatement, Statement, StatementKind, Statements};
+
+use crate::assets::parse_statements_input;
+
+fn break_statement() -> Statement {
+    Statement::new(0, 0, StatementKind::Break(BreakStatement::new(0, 0)))
+}
+
+fn continue_statement() -> Statement {
+    Statement::new(0, 0, StatementKind::Continue(ContinueStatement::new(0, 0)))
+}
+
+#[test]
+fn test_append_statement() {
+    let mut stmts = Statements::new(0, 0, vec![break_statement()]);
+    stmts.append_statement(continue_statement());
+
+    assert!(matches!(
+        stmts.statements.as_slice(),
+        [
+            Statement {
+                kind: StatementKind::Break(_),
+                ..
+            },
+            Statement {
+                kind: StatementKind::Continue(_),
+                ..
+            }
+        ]
+    ));
+}
+
+#[test]
+fn test_prepend_statement() {
+    let mut stmts = Statements::new(0, 0, vec![break_statement()]);
+    stmts.prepend_statement(continue_statement());
+
+    assert!(matches!(
+        stmts.statements.as_slice(),
+        [
+            Statement {
+                kind: StatementKind::Continue(_),
+                ..
+            },
+            Statement {
+                kind: StatementKind::Break(_),
+                ..
+            }
+        ]
+    ));
+}
+
+#[test]
+fn test_parsed_statements_order() {
+    let stmts = parse_statements_input("break; continue; return;").expect("Failed to parse");
+
+    assert!(matches!(
+        stmts.statements.as_slice(),
+        [
+            Statement {
+                kind: StatementKind::Break(_),
+                ..
+            },
+            Statement {
+                kind: StatementKind::Continue(_),
+                ..
+            },
+            Statement {
+                kind: StatementKind::Return(_),
+                ..
+            }
+        ]
+    ));
+}
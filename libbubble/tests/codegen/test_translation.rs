@@ -3,9 +3,23 @@ use std::{
     process::{Command, Stdio},
 };
 
+use inkwell::{
+    attributes::{Attribute, AttributeLoc},
+    context::Context,
+    module::{Linkage, Module},
+    targets::{FileType, TargetMachine},
+    values::InstructionOpcode,
+};
+use libbubble::{
+    codegen::{run_optimization_passes, Collector, HardeningOptions, StackVariable},
+    type_system,
+};
 use rstest::rstest;
 
-use crate::assets::build_and_link;
+use crate::assets::{
+    build_and_link, create_i686_target_machine, create_target_machine, translate_code,
+    translate_code_for_target, translate_code_with_hardening, type_check_code,
+};
 
 #[rstest]
 #[case::main_return_0(
@@ -205,182 +219,1865 @@ use crate::assets::build_and_link;
     "/tmp/malloc_and_free_single_int",
     0
 )]
-fn test_translation(
-    #[case] code: &str,
-    #[case] executable_path: &str,
-    #[case] expected_return_code: i32,
-) {
-    build_and_link(code, &format!("{}.o", executable_path), executable_path);
-
-    let result = Command::new(executable_path)
-        .status()
-        .expect("Failed to invoke executable");
-    assert_eq!(result.code().unwrap(), expected_return_code, "{:?}", result);
-}
+#[case::return_const(
+    r#"
+    const ANSWER: i32 = 40 + 2;
+    function main(): i32 {
+        return ANSWER;
+}"#,
+    "/tmp/return_const",
+    42
+)]
+#[case::const_array_size(
+    r#"
+    const SIZE: u32 = 2 + 1;
+    function main(): i32 {
+        let arr: [SIZE; i32] = [10, 20, 12];
+        let first: i32 = arr[0];
+        let second: i32 = arr[1];
+        let third: i32 = arr[2];
+        return first + second + third;
+}"#,
+    "/tmp/const_array_size",
+    42
+)]
+#[case::slice_access(
+    r#"
+    function main(): i32 {
+        let arr: [5; i32] = [1, 10, 20, 12, 3];
+        let s = arr[1..4];
+        let t = s[1..3];
+        let first: i32 = s[0];
+        let second: i32 = t[0];
+        let third: i32 = t[1];
+        return first + second + third;
+}"#,
+    "/tmp/slice_access",
+    42
+)]
+#[case::extern_variable(
+    r#"
+    extern let optind: i32;
+    function main(): i32 {
+        optind = optind + 41;
+        return optind;
+}"#,
+    "/tmp/extern_variable",
+    42
+)]
+#[case::string_comparison(
+    r#"
+    function main(): i32 {
+        let name: string = "bubble";
+        if name == "bubble" and name != "lang" and "abc" < "abd" and "b" >= "a" {
+            return 42;
+        }
 
-#[rstest]
-#[case::while_loop_five_times(
-    r#"extern function puts(msg: string): i32;
+        return 1;
+}"#,
+    "/tmp/string_comparison",
+    42
+)]
+#[case::negative_literals(
+    r#"
     function main(): i64 {
-        let a: i64 = 0;
-        while a < 5 {
-            puts("hey");
-            a = a + 1;
-        }
-        return 0;
+        let x: i64 = -9;
+        return x * -5 - 3;
 }"#,
-    "/tmp/while_loop_five_times",
-    0,
-    "hey\nhey\nhey\nhey\nhey\n"
+    "/tmp/negative_literals",
+    42
 )]
-#[case::for_i_0_to_5(
+#[case::assert_success(
     r#"
-    extern function puts(msg: string): i32;
     function main(): i32 {
-        for i: i32 = 0; i < 5; i = i + 1 {
-            puts("hey");
+        let a: i32 = 42;
+        assert(a == 42);
+        return a;
+    }"#,
+    "/tmp/assert_success",
+    42
+)]
+#[case::addrof_array_element_decay(
+    r#"
+    function main(): i32 {
+        let arr: [3; i32] = [1, 42, 3];
+        let p: ptr i32 = addrof arr[1];
+        return deref p;
+    }"#,
+    "/tmp/addrof_array_element_decay",
+    42
+)]
+#[case::addrof_whole_array(
+    r#"
+    function main(): i32 {
+        let arr: [3; i32] = [1, 42, 3];
+        let p: ptr [3; i32] = addrof arr;
+        let first: ptr i32 = addrof arr[0];
+        deref first = 42;
+        return arr[0];
+    }"#,
+    "/tmp/addrof_whole_array",
+    42
+)]
+#[case::array_argument_decay(
+    r#"
+    function set_first(p: ptr i32) {
+        deref p = 42;
+    }
+    function main(): i32 {
+        let arr: [3; i32] = [1, 2, 3];
+        set_first(arr);
+        return arr[0];
+    }"#,
+    "/tmp/array_argument_decay",
+    42
+)]
+#[case::write_through_addrof_array_element(
+    r#"
+    function main(): i32 {
+        let arr: [3; i32] = [1, 2, 3];
+        let i: i64 = 1;
+        let p: ptr i32 = addrof arr[i];
+        deref p = 42;
+        return arr[1];
+    }"#,
+    "/tmp/write_through_addrof_array_element",
+    42
+)]
+#[case::return_inside_if(
+    r#"
+    function f(c: bool): i32 {
+        if c { return 1; }
+        return 2;
+    }
+    function main(): i32 {
+        return f(true) * 10 + f(false);
+    }"#,
+    "/tmp/return_inside_if",
+    12
+)]
+#[case::return_inside_while(
+    r#"
+    function main(): i32 {
+        let i: i32 = 0;
+        while true {
+            if i == 42 { return i; }
+            i = i + 1;
         }
-
         return 0;
     }"#,
-    "/tmp/for_i_0_10",
-    0,
-    "hey\nhey\nhey\nhey\nhey\n"
+    "/tmp/return_inside_while",
+    42
 )]
-#[case::for_never_loop(
+#[case::return_inside_if_and_else(
     r#"
-    extern function puts(msg: string): i32;
+    function f(c: bool): i32 {
+        if c {
+            return 40;
+        } else {
+            return 2;
+        }
+    }
     function main(): i32 {
-        for i: i32 = 0; false; i = i + 1 {
-            puts("hey");
+        return f(true) + f(false);
+    }"#,
+    "/tmp/return_inside_if_and_else",
+    42
+)]
+#[case::comparison_stored_in_bool(
+    r#"
+    function main(): i32 {
+        let a: i32 = 3;
+        let smaller: bool = a < 10;
+        let bigger: bool = a > 10;
+        if bigger {
+            return 1;
         }
-
-        return 0;
+        if smaller {
+            return 42;
+        }
+        return 2;
     }"#,
-    "/tmp/for_never_loop",
-    0,
-    ""
+    "/tmp/comparison_stored_in_bool",
+    42
 )]
-#[case::for_condition_not_in_modify_expression(
-    // i is useless here
+#[case::mutual_recursion(
     r#"
-    extern function puts(msg: string): i32;
     function main(): i32 {
-        let j: i32 = 0;
-        for i: i32 = 0; j < 5; i = i + 1 {
-            puts("hey");
-            j = j + 1;
+        if is_even(10) {
+            return 42;
         }
-
-        return 0;
+        return 1;
+    }
+    function is_even(n: u32): bool {
+        if n == 0 {
+            return true;
+        }
+        return is_odd(n - 1);
+    }
+    function is_odd(n: u32): bool {
+        if n == 0 {
+            return false;
+        }
+        return is_even(n - 1);
     }"#,
-    "/tmp/for_condition_not_in_modify_expression",
-    0,
-    "hey\nhey\nhey\nhey\nhey\n"
+    "/tmp/mutual_recursion",
+    42
 )]
-#[case::for_i_is_bool(
+#[case::array_of_pointers(
     r#"
-    extern function puts(msg: string): i32;
     function main(): i32 {
-        for i: bool = true; i; i = false {
-            puts("Only once");
+        let a: i32 = 10;
+        let b: i32 = 0;
+        let c: i32 = 12;
+        let pointers: [3; ptr i32] = [addrof a, addrof b, addrof c];
+        deref pointers[1] = 20;
+        let first: i32 = deref pointers[0];
+        let third: i32 = deref pointers[2];
+        return first + b + third;
+    }"#,
+    "/tmp/array_of_pointers",
+    42
+)]
+#[case::array_of_pointers_in_loop(
+    r#"
+    function main(): i64 {
+        let a: i64 = 1;
+        let b: i64 = 2;
+        let c: i64 = 3;
+        let pointers: [3; ptr i64] = [addrof a, addrof b, addrof c];
+        for i: i64 = 0; i < 3; i = i + 1 {
+            let value: i64 = deref pointers[i];
+            deref pointers[i] = value * 7;
         }
-
-        return 0;
+        return a + b + c;
     }"#,
-    "/tmp/for_i_is_bool",
-    0,
-    "Only once\n"
+    "/tmp/array_of_pointers_in_loop",
+    42
 )]
-#[case::for_variable_condition_always_false(
+#[case::min_max_signed(
     r#"
-    extern function puts(msg: string): i32;
     function main(): i32 {
-        for i: i32 = 42; i < 10; i = i + 1 {
-            puts("unreachable!");
+        let a: i32 = -5;
+        let b: i32 = 40;
+        let smallest: i32 = min(a, b);
+        let biggest: i32 = max(a, b);
+        return biggest - smallest - 3;
+    }"#,
+    "/tmp/min_max_signed",
+    42
+)]
+#[case::min_max_unsigned(
+    r#"
+    function main(): i32 {
+        let big: u32 = 4000000000;
+        let small: u32 = 42;
+        if max(big, small) != big {
+            return 1;
         }
-
-        return 0;
+        if min(big, small) != small {
+            return 2;
+        }
+        return 42;
     }"#,
-    "/tmp/for_variable_condition_always_false",
-    0,
-    ""
+    "/tmp/min_max_unsigned",
+    42
 )]
-#[case::print_array_content(
+#[case::min_max_float(
     r#"
-    extern function printf(msg: string, value: i32): i32;
     function main(): i32 {
-        let arr: [3; i32] = [1, 2, 3];
-        for i: i32 = 0; i < 3; i = i + 1 {
-            printf("%d", arr[i]);
+        if min(1.5, 2.5) > 2.0 {
+            return 1;
         }
-        return 0;
+        if max(1.5, 2.5) < 2.0 {
+            return 2;
+        }
+        return 42;
     }"#,
-    "/tmp/print_array_content",
-    0,
-    "123"
+    "/tmp/min_max_float",
+    42
 )]
-#[case::deref_value(
+#[case::is_null_builtin(
     r#"
-    extern function printf(msg: string, value: i32): i32;
+    extern function malloc(size: u64): ptr void;
     function main(): i32 {
-        let x: i32 = 42;
-        let x_ptr: ptr i32 = addrof x;
-        printf("%d", deref x_ptr);
-        return 0;
+        let p: ptr i32 = null;
+        if not is_null(p) {
+            return 1;
+        }
+        if is_null(malloc(4)) {
+            return 2;
+        }
+        return 42;
     }"#,
-    "/tmp/deref_value",
-    0,
-    "42"
+    "/tmp/is_null_builtin",
+    42
 )]
-#[case::deref_pointer_of_pointer_value(
+#[case::len_string_literal(
     r#"
-    extern function printf(msg: string, value: i32): i32;
     function main(): i32 {
-        let x: i32 = 42;
-        let x_ptr: ptr i32 = addrof x;
-        let x_ptr_ptr: ptr ptr i32 = addrof x_ptr;
-        printf("%d", deref (deref x_ptr_ptr));
-        return 0;
+        if len("abc") != 3 {
+            return 1;
+        }
+        // Escape sequences count as a single byte, even the null byte
+        if len("a\0b\n") != 4 {
+            return 2;
+        }
+        return 42;
     }"#,
-    "/tmp/deref_pointer_of_pointer_value",
-    0,
-    "42"
+    "/tmp/len_string_literal",
+    42
 )]
-#[case::deref_expression_as_lvalue_and_init(
+#[case::if_assignment_select(
     r#"
-    extern function printf(msg: string, value: i32): i32;
+    function pick(c: bool): i32 {
+        let x: i32 = 0;
+        if c {
+            x = 42;
+        } else {
+            x = 1;
+        }
+        return x;
+    }
     function main(): i32 {
-        let x: i32 = 42;
-        let int_ptr: ptr i32 = addrof x;
-        deref int_ptr = 51;
-        printf("%d", x);
-        return 0;
+        return pick(true) + pick(false) - 1;
     }"#,
-    "/tmp/deref_expression_as_lvalue_and_init",
-    0,
-    "51"
+    "/tmp/if_assignment_select",
+    42
 )]
-fn test_translation_with_stdout(
-    #[case] code: &str,
-    #[case] executable_path: &str,
-    #[case] expected_return_code: i32,
-    #[case] expected_stdout: &str,
-) {
-    build_and_link(code, &format!("{}.o", executable_path), executable_path);
-
-    let mut cmd = Command::new(executable_path)
-        .stdout(Stdio::piped())
-        .spawn()
-        .expect("Failed to spawn task");
-
-    let mut read_string = String::new();
-    let mut stdout = cmd.stdout.take().expect("Failed to get executable stdout");
-    let result = cmd.wait().expect("Failed to wait child process");
-    stdout
-        .read_to_string(&mut read_string)
-        .expect("Failed to read stdout");
-
-    assert_eq!(result.code().unwrap(), expected_return_code);
-    assert_eq!(read_string, expected_stdout);
+#[case::addition_overflowing_64_bits(
+    r#"
+    function main(): i32 {
+        let a: i128 = 9223372036854775807;
+        let b: i128 = a + a;
+        if b < a {
+            return 1;
+        }
+        let c: u128 = 9223372036854775807;
+        let d: u128 = c * 4;
+        if d / 4 != c {
+            return 2;
+        }
+        return 42;
+    }"#,
+    "/tmp/addition_overflowing_64_bits",
+    42
+)]
+#[case::literals_above_64_bits(
+    r#"
+    function main(): i32 {
+        let a: u128 = 18446744073709551616;
+        let b: u128 = a / 65536;
+        if b != 281474976710656 {
+            return 1;
+        }
+        let c: i128 = -18446744073709551617;
+        let d: i128 = c + 18446744073709551616;
+        if d != -1 {
+            return 2;
+        }
+        return 42;
+    }"#,
+    "/tmp/literals_above_64_bits",
+    42
+)]
+#[case::array_access_plus_literal(
+    r#"
+    function main(): i32 {
+        let arr: [3; i32] = [41, 2, 3];
+        return (arr[0]) + 1;
+    }"#,
+    "/tmp/array_access_plus_literal",
+    42
+)]
+#[case::deferred_initialization(
+    r#"
+    function pick(c: bool): i32 {
+        let value: i32;
+        if c {
+            value = 40;
+        } else {
+            value = 2;
+        }
+        return value;
+    }
+    function main(): i32 {
+        return pick(true) + pick(false);
+    }"#,
+    "/tmp/deferred_initialization",
+    42
+)]
+#[case::assign_array_element(
+    r#"
+    function main(): i32 {
+        let arr: [3; i32] = [1, 2, 3];
+        let i: i64 = 2;
+        arr[0] = 40;
+        arr[i] = 2;
+        let first = arr[0];
+        let last = arr[2];
+        return first + last;
+    }"#,
+    "/tmp/assign_array_element",
+    42
+)]
+#[case::chained_assignment(
+    r#"
+    function main(): i32 {
+        let a: i32 = 0;
+        let b: i32 = 0;
+        let c: i32 = 0;
+        a = b = c = 14;
+        return a + b + c;
+    }"#,
+    "/tmp/chained_assignment",
+    42
+)]
+#[case::method_call(
+    r#"
+    struct Counter { value: i32 }
+    function Counter.answer(self, offset: i32): i32 {
+        return 40 + offset;
+    }
+    function Counter.call_through_self(self): i32 {
+        return self.answer(1) + 1;
+    }
+    function main(): i32 {
+        let counter: Counter;
+        return counter.call_through_self();
+    }"#,
+    "/tmp/method_call",
+    42
+)]
+#[case::enum_variants(
+    r#"
+    enum Color { Red, Green, Blue }
+    function next(c: Color): Color {
+        if c == Color.Red {
+            return Color.Green;
+        }
+        return Color.Blue;
+    }
+    function main(): i32 {
+        let c: Color = next(Color.Red);
+        if c == Color.Green {
+            return 42;
+        }
+        return 1;
+    }"#,
+    "/tmp/enum_variants",
+    42
+)]
+#[case::while_else(
+    r#"
+    function find(limit: i32): i32 {
+        let i: i32 = 0;
+        let result: i32 = 0;
+        while i < 10 {
+            if i == limit {
+                result = 1;
+                break;
+            }
+            i = i + 1;
+        } else {
+            result = 2;
+        }
+        return result;
+    }
+    function main(): i32 {
+        return find(3) * 10 + find(20);
+    }"#,
+    "/tmp/while_else",
+    12
+)]
+#[case::while_break(
+    r#"
+    function main(): i32 {
+        let i: i32 = 0;
+        while true {
+            i = i + 1;
+            if i == 42 {
+                break;
+            }
+        }
+        return i;
+    }"#,
+    "/tmp/while_break",
+    42
+)]
+#[case::for_break(
+    r#"
+    function main(): i32 {
+        let result: i32 = 0;
+        for i: i32 = 0; i < 100; i = i + 1 {
+            if i == 42 {
+                break;
+            }
+            result = result + 1;
+        }
+        return result;
+    }"#,
+    "/tmp/for_break",
+    42
+)]
+#[case::sibling_blocks_locals(
+    r#"
+    function main(): i32 {
+        let found: bool = false;
+        let result: i32 = 0;
+        if true {
+            let a: bool = true;
+            found = a;
+        }
+        if found {
+            let a: i32 = 42;
+            result = a;
+        }
+        return result;
+    }"#,
+    "/tmp/sibling_blocks_locals",
+    42
+)]
+#[case::shadowed_local(
+    r#"
+    function main(): i32 {
+        let a: i32 = 40;
+        if true {
+            let a: i32 = 100;
+            a = a + 1;
+        }
+        return a + 2;
+    }"#,
+    "/tmp/shadowed_local",
+    42
+)]
+#[case::return_in_for(
+    r#"
+    function main(): i32 {
+        let a: i32 = 40;
+        for i: i32 = 0; i < 10; i = i + 1 {
+            a = a + 2;
+            return a;
+        }
+        return 0;
+    }"#,
+    "/tmp/return_in_for",
+    42
+)]
+#[case::alignof_i64(
+    r#"
+    function main(): i32 {
+        if alignof(i64) == 8 {
+            return 42;
+        }
+        return 0;
+    }"#,
+    "/tmp/alignof_i64",
+    42
+)]
+#[case::alignof_i8(
+    r#"
+    function main(): i32 {
+        if alignof(i8) == 1 {
+            return 42;
+        }
+        return 0;
+    }"#,
+    "/tmp/alignof_i8",
+    42
+)]
+#[case::alignof_array(
+    r#"
+    function main(): i32 {
+        if alignof([4; i64]) == alignof(i64) {
+            return 42;
+        }
+        return 0;
+    }"#,
+    "/tmp/alignof_array",
+    42
+)]
+#[case::pointer_null_comparison(
+    r#"
+    function value_or(p: ptr i32, default: i32): i32 {
+        if p == null {
+            return default;
+        }
+        return deref p;
+    }
+    function main(): i32 {
+        let a: i32 = 40;
+        let p: ptr i32 = addrof a;
+        let q: ptr i32 = p;
+        let n: ptr i32 = null;
+        if p != q {
+            return 1;
+        }
+        return value_or(p, 0) + value_or(n, 2);
+    }"#,
+    "/tmp/pointer_null_comparison",
+    42
+)]
+#[case::return_void_ptr_null(
+    r#"
+    function null_ptr(): ptr void {
+        return null;
+    }
+    function main(): i32 {
+        let p: ptr void = null_ptr();
+        if p == null {
+            return 42;
+        }
+        return 0;
+    }"#,
+    "/tmp/return_void_ptr_null",
+    42
+)]
+#[case::void_ptr_conversions(
+    r#"
+    function erase(p: ptr i32): ptr void {
+        return p;
+    }
+    function read(v: ptr void): i32 {
+        let p: ptr i32 = v;
+        return deref p;
+    }
+    function main(): i32 {
+        let a: i32 = 42;
+        let p: ptr i32 = addrof a;
+        let v: ptr void = p;
+        let q: ptr i32 = v;
+        v = q;
+        q = v;
+        return read(erase(q));
+    }"#,
+    "/tmp/void_ptr_conversions",
+    42
+)]
+#[case::fast_calling_convention(
+    r#"
+    extern "fast" function add(a: i32, b: i32): i32 {
+        return a + b;
+    }
+    function main(): i32 {
+        return add(40, 2);
+    }"#,
+    "/tmp/fast_calling_convention",
+    42
+)]
+#[case::tuple_return(
+    r#"
+    function divmod(a: i32, b: i32): (i32, i32) {
+        return (a / b, a % b);
+    }
+    function main(): i32 {
+        let (q, r) = divmod(17, 5);
+        return q * 10 + r;
+    }"#,
+    "/tmp/tuple_return",
+    32
+)]
+#[case::tuple_variable(
+    r#"
+    function swap(t: (i32, bool)): (bool, i32) {
+        let (a, b) = t;
+        return (b, a);
+    }
+    function main(): i32 {
+        let t: (i32, bool) = (42, true);
+        let (b, a) = swap(t);
+        if b {
+            return a;
+        }
+        return 0;
+    }"#,
+    "/tmp/tuple_variable",
+    42
+)]
+#[case::array_destructuring(
+    r#"
+    function main(): i32 {
+        let values: [3; i32] = [4, 30, 8];
+        let [a, b, c] = values;
+        let [d, e] = [true, false];
+        if d and not e {
+            return a + b + c;
+        }
+        return 0;
+    }"#,
+    "/tmp/array_destructuring",
+    42
+)]
+#[case::bool_equality(
+    r#"
+    function same(a: bool, b: bool): bool {
+        return a == b;
+    }
+    function main(): i32 {
+        if (true == true) and same(false, false) and (true != false) {
+            return 1;
+        }
+        return 0;
+    }"#,
+    "/tmp/bool_equality",
+    1
+)]
+#[case::dispatch_table(
+    r#"
+    function forty(): i32 {
+        return 40;
+    }
+    function two(): i32 {
+        return 2;
+    }
+    function main(): i32 {
+        let table: [2; ptr function(): i32] = [forty, two];
+        let total: i32 = 0;
+        for i: u8 = 0; i < 2; i = i + 1 {
+            let value: i32 = table[i]();
+            total = total + value;
+        }
+        return total;
+    }"#,
+    "/tmp/dispatch_table",
+    42
+)]
+#[case::static_counter(
+    r#"
+    function counter(): i32 {
+        static count: i32 = 0;
+        count = count + 1;
+        return count;
+    }
+    function main(): i32 {
+        let first: i32 = counter();
+        let second: i32 = counter();
+        return first * 10 + second;
+    }"#,
+    "/tmp/static_counter",
+    12
+)]
+#[case::static_float(
+    r#"
+    function next(): float {
+        static x: float = 1.5;
+        x = x + 1.0;
+        return x;
+    }
+    function main(): i32 {
+        next();
+        if next() == 3.5 {
+            return 42;
+        }
+        return 0;
+    }"#,
+    "/tmp/static_float",
+    42
+)]
+#[case::static_null(
+    r#"
+    function unset(): bool {
+        static p: ptr i32 = null;
+        return is_null(p);
+    }
+    function main(): i32 {
+        if unset() {
+            return 42;
+        }
+        return 0;
+    }"#,
+    "/tmp/static_null",
+    42
+)]
+#[case::defer_after_return_value(
+    r#"
+    function take(p: ptr i32): i32 {
+        defer deref p = 0;
+        return deref p;
+    }
+    function main(): i32 {
+        let x: i32 = 42;
+        let taken = take(addrof x);
+        return taken + x;
+    }"#,
+    "/tmp/defer_after_return_value",
+    42
+)]
+#[case::for_loop_after_desugar(
+    r#"
+    enum Parity { Even, Odd }
+    function parity(n: i32): Parity {
+        if n % 2 == 0 {
+            return Parity.Even;
+        }
+        return Parity.Odd;
+    }
+    function main(): i32 {
+        let evens: i32 = 0;
+        for i: i32 = 0; i < 9; i = i + 1 {
+            if parity(i) == Parity.Even {
+                evens = evens + 1;
+            }
+        }
+        assert(evens == 5);
+        return evens * 8 + 2;
+    }"#,
+    "/tmp/for_loop_after_desugar",
+    42
+)]
+#[case::try_null_short_circuits(
+    r#"
+    extern function malloc(size: u64): ptr void;
+    extern function free(p: ptr void): void;
+    function counted_malloc(size: u64, calls: ptr i32): ptr void {
+        deref calls = (deref calls) + 1;
+        return malloc(size);
+    }
+    function alloc_pair(first: u64, second: u64, calls: ptr i32): ptr void {
+        let a: ptr void = counted_malloc(first, calls)?;
+        let b: ptr void = counted_malloc(second, calls)?;
+        free(b);
+        return a;
+    }
+    function main(): i32 {
+        let calls: i32 = 0;
+        if not is_null(alloc_pair(4611686018427387904, 8, addrof calls)) {
+            return 1;
+        }
+        if calls != 1 {
+            return 2;
+        }
+        let pair: ptr void = alloc_pair(8, 8, addrof calls);
+        if is_null(pair) {
+            return 3;
+        }
+        free(pair);
+        return calls * 14;
+    }"#,
+    "/tmp/try_null_short_circuits",
+    42
+)]
+fn test_translation(
+    #[case] code: &str,
+    #[case] executable_path: &str,
+    #[case] expected_return_code: i32,
+) {
+    build_and_link(code, &format!("{}.o", executable_path), executable_path);
+
+    let result = Command::new(executable_path)
+        .status()
+        .expect("Failed to invoke executable");
+    assert_eq!(result.code().unwrap(), expected_return_code, "{:?}", result);
+}
+
+#[rstest]
+#[case::while_loop_five_times(
+    r#"extern function puts(msg: string): i32;
+    function main(): i64 {
+        let a: i64 = 0;
+        while a < 5 {
+            puts("hey");
+            a = a + 1;
+        }
+        return 0;
+}"#,
+    "/tmp/while_loop_five_times",
+    0,
+    "hey\nhey\nhey\nhey\nhey\n"
+)]
+#[case::for_i_0_to_5(
+    r#"
+    extern function puts(msg: string): i32;
+    function main(): i32 {
+        for i: i32 = 0; i < 5; i = i + 1 {
+            puts("hey");
+        }
+
+        return 0;
+    }"#,
+    "/tmp/for_i_0_10",
+    0,
+    "hey\nhey\nhey\nhey\nhey\n"
+)]
+#[case::for_never_loop(
+    r#"
+    extern function puts(msg: string): i32;
+    function main(): i32 {
+        for i: i32 = 0; false; i = i + 1 {
+            puts("hey");
+        }
+
+        return 0;
+    }"#,
+    "/tmp/for_never_loop",
+    0,
+    ""
+)]
+#[case::for_condition_not_in_modify_expression(
+    // i is useless here
+    r#"
+    extern function puts(msg: string): i32;
+    function main(): i32 {
+        let j: i32 = 0;
+        for i: i32 = 0; j < 5; i = i + 1 {
+            puts("hey");
+            j = j + 1;
+        }
+
+        return 0;
+    }"#,
+    "/tmp/for_condition_not_in_modify_expression",
+    0,
+    "hey\nhey\nhey\nhey\nhey\n"
+)]
+#[case::for_i_is_bool(
+    r#"
+    extern function puts(msg: string): i32;
+    function main(): i32 {
+        for i: bool = true; i; i = false {
+            puts("Only once");
+        }
+
+        return 0;
+    }"#,
+    "/tmp/for_i_is_bool",
+    0,
+    "Only once\n"
+)]
+#[case::for_variable_condition_always_false(
+    r#"
+    extern function puts(msg: string): i32;
+    function main(): i32 {
+        for i: i32 = 42; i < 10; i = i + 1 {
+            puts("unreachable!");
+        }
+
+        return 0;
+    }"#,
+    "/tmp/for_variable_condition_always_false",
+    0,
+    ""
+)]
+#[case::print_array_content(
+    r#"
+    extern function printf(msg: string, value: i32): i32;
+    function main(): i32 {
+        let arr: [3; i32] = [1, 2, 3];
+        for i: i32 = 0; i < 3; i = i + 1 {
+            printf("%d", arr[i]);
+        }
+        return 0;
+    }"#,
+    "/tmp/print_array_content",
+    0,
+    "123"
+)]
+#[case::deref_value(
+    r#"
+    extern function printf(msg: string, value: i32): i32;
+    function main(): i32 {
+        let x: i32 = 42;
+        let x_ptr: ptr i32 = addrof x;
+        printf("%d", deref x_ptr);
+        return 0;
+    }"#,
+    "/tmp/deref_value",
+    0,
+    "42"
+)]
+#[case::deref_pointer_of_pointer_value(
+    r#"
+    extern function printf(msg: string, value: i32): i32;
+    function main(): i32 {
+        let x: i32 = 42;
+        let x_ptr: ptr i32 = addrof x;
+        let x_ptr_ptr: ptr ptr i32 = addrof x_ptr;
+        printf("%d", deref (deref x_ptr_ptr));
+        return 0;
+    }"#,
+    "/tmp/deref_pointer_of_pointer_value",
+    0,
+    "42"
+)]
+#[case::store_through_addrof(
+    r#"
+    function main(): i32 {
+        let x: i32 = 1;
+        let p: ptr i32 = addrof x;
+        deref p = 42;
+        let q: ptr i32 = addrof deref p;
+        deref q = (deref q) + 1;
+        return x - 1;
+    }"#,
+    "/tmp/store_through_addrof",
+    42
+)]
+#[case::write_through_deref_of_deref(
+    r#"
+    function main(): i32 {
+        let x: i32 = 1;
+        let x_ptr: ptr i32 = addrof x;
+        let x_ptr_ptr: ptr ptr i32 = addrof x_ptr;
+        deref (deref x_ptr_ptr) = 42;
+        return deref (deref x_ptr_ptr);
+    }"#,
+    "/tmp/write_through_deref_of_deref",
+    42
+)]
+#[case::deref_expression_as_lvalue_and_init(
+    r#"
+    extern function printf(msg: string, value: i32): i32;
+    function main(): i32 {
+        let x: i32 = 42;
+        let int_ptr: ptr i32 = addrof x;
+        deref int_ptr = 51;
+        printf("%d", x);
+        return 0;
+    }"#,
+    "/tmp/deref_expression_as_lvalue_and_init",
+    0,
+    "51"
+)]
+#[case::variadic_printf(
+    r#"
+    extern function printf(msg: string, ...): i32;
+    function main(): i32 {
+        let x: i32 = 2;
+        printf("%d %d", 1, x);
+        return 0;
+    }"#,
+    "/tmp/variadic_printf",
+    0,
+    "1 2"
+)]
+#[case::variable_loads_exact_stdout(
+    r#"
+    extern function printf(msg: string, value: i32): i32;
+    function main(): i32 {
+        let a: i32 = 1;
+        let b: i32 = a + a;
+        let c: i32 = b * a + b;
+        printf("%d", a);
+        printf("%d", b);
+        printf("%d", c);
+        return c - b - b;
+    }"#,
+    "/tmp/variable_loads_exact_stdout",
+    0,
+    "124"
+)]
+#[case::defer_reverse_order(
+    r#"
+    extern function printf(msg: string, ...): i32;
+    function main(): i32 {
+        defer printf("3");
+        defer printf("2");
+        printf("1");
+        return 0;
+    }"#,
+    "/tmp/defer_reverse_order",
+    0,
+    "123"
+)]
+#[case::defer_in_loop(
+    r#"
+    extern function printf(msg: string, ...): i32;
+    function main(): i32 {
+        let i: i32 = 0;
+        while i < 10 {
+            defer printf("%d", i);
+            i = i + 1;
+            if i == 3 {
+                break;
+            }
+        }
+        return i;
+    }"#,
+    "/tmp/defer_in_loop",
+    3,
+    "123"
+)]
+#[case::defer_with_continue(
+    r#"
+    extern function printf(msg: string, ...): i32;
+    function main(): i32 {
+        let i: i32 = 0;
+        while i < 4 {
+            defer printf("%d", i);
+            i = i + 1;
+            if i % 2 == 0 {
+                continue;
+            }
+            printf("-");
+        }
+        let odd: i32 = 0;
+        for j: i32 = 0; j < 5; j = j + 1 {
+            if j % 2 == 0 {
+                continue;
+            }
+            odd = odd + 1;
+        }
+        return odd;
+    }"#,
+    "/tmp/defer_with_continue",
+    2,
+    "-12-34"
+)]
+#[case::try_null_runs_deferred(
+    r#"
+    extern function printf(msg: string, ...): i32;
+    function first(p: ptr i32): ptr i32 {
+        defer printf("1");
+        if true {
+            defer printf("2");
+            let value = deref p?;
+        }
+        printf("3");
+        return p;
+    }
+    function main(): i32 {
+        let value: i32 = 42;
+        let none: ptr i32 = null;
+        first(none);
+        return deref first(addrof value);
+    }"#,
+    "/tmp/try_null_runs_deferred",
+    42,
+    "21231"
+)]
+fn test_translation_with_stdout(
+    #[case] code: &str,
+    #[case] executable_path: &str,
+    #[case] expected_return_code: i32,
+    #[case] expected_stdout: &str,
+) {
+    build_and_link(code, &format!("{}.o", executable_path), executable_path);
+
+    let mut cmd = Command::new(executable_path)
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn task");
+
+    let mut read_string = String::new();
+    let mut stdout = cmd.stdout.take().expect("Failed to get executable stdout");
+    let result = cmd.wait().expect("Failed to wait child process");
+    stdout
+        .read_to_string(&mut read_string)
+        .expect("Failed to read stdout");
+
+    assert_eq!(result.code().unwrap(), expected_return_code);
+    assert_eq!(read_string, expected_stdout);
+}
+
+#[rstest]
+#[case::if_true(
+    r#"
+    function main(): i32 {
+        if true {
+            return 1;
+        }
+
+        return 0;
+    }"#
+)]
+#[case::if_else_condition(
+    r#"
+    function main(): i32 {
+        let a: i32 = 3;
+        if a > 2 {
+            return 1;
+        } else {
+            return 0;
+        }
+    }"#
+)]
+#[case::while_condition(
+    r#"
+    function main(): i32 {
+        let a: i32 = 0;
+        while a < 10 {
+            a = a + 1;
+        }
+
+        return a;
+    }"#
+)]
+#[case::assert_in_loop(
+    r#"
+    function main(): i32 {
+        let a: i32 = 0;
+        while a < 10 {
+            assert(a < 10);
+            a = a + 1;
+        }
+
+        return a;
+    }"#
+)]
+#[case::return_inside_then(
+    r#"
+    function main(): i32 {
+        let a: i32 = 1;
+        if a == 1 {
+            return 42;
+        }
+
+        return a;
+    }"#
+)]
+#[case::return_inside_while_body(
+    r#"
+    function main(): i32 {
+        while true {
+            return 42;
+        }
+
+        return 0;
+    }"#
+)]
+#[case::return_inside_else(
+    r#"
+    function main(): i32 {
+        let a: i32 = 1;
+        if a == 1 {
+            a = 42;
+        } else {
+            return 0;
+        }
+
+        return a;
+    }"#
+)]
+#[case::void_function_without_return(
+    r#"
+    function f(c: bool) {
+        if c {
+            return;
+        }
+    }
+    function main(): i32 {
+        f(true);
+        return 0;
+    }"#
+)]
+#[case::empty_void_function(
+    r#"
+    function f() { }
+    function main(): i32 {
+        f();
+        return 0;
+    }"#
+)]
+#[case::mutually_recursive_structs(
+    r#"
+    struct A {
+        value: i32,
+        b: ptr B
+    }
+    struct B {
+        a: ptr A
+    }
+    function f(a: ptr A, b: ptr B): i32 {
+        return 0;
+    }
+    function main(): i32 {
+        return 0;
+    }"#
+)]
+#[case::empty_struct(
+    r#"
+    struct Empty {}
+    function f(e: ptr Empty): ptr Empty {
+        let other: ptr Empty = e;
+        return other;
+    }
+    function main(): i32 {
+        let e: ptr Empty = null;
+        f(e);
+        return 0;
+    }"#
+)]
+#[case::function_before_struct(
+    r#"
+    function copy(point: Point): Point {
+        let copied: Point = point;
+        return copied;
+    }
+    struct Point {
+        x: i32,
+        y: i32
+    }
+    function main(): i32 {
+        return 0;
+    }"#
+)]
+#[case::void_ptr_conversions(
+    r#"
+    extern function free(p: ptr void): void;
+    function f(p: ptr i32): ptr i32 {
+        let v: ptr void = p;
+        free(p);
+        p = v;
+        return v;
+    }
+    function main(): i32 {
+        return 0;
+    }"#
+)]
+#[case::freestanding_entry(
+    r#"
+    extern function exit(code: i32): void;
+    function start(): void {
+        exit(42);
+    }"#
+)]
+fn test_module_verification(#[case] code: &str) {
+    let context = Context::create();
+    let module = context.create_module("module");
+
+    translate_code(&context, &module, code, false);
+
+    if let Err(msg) = module.verify() {
+        panic!("Module verification failed: {}", msg.to_string());
+    }
+}
+
+#[test]
+fn test_failing_assert() {
+    let code = r#"
+    function main(): i32 {
+        let a: i32 = 41;
+        assert(a == 42);
+        return 0;
+    }"#;
+    let executable_path = "/tmp/assert_failure";
+    build_and_link(code, &format!("{}.o", executable_path), executable_path);
+
+    let result = Command::new(executable_path)
+        .stdout(Stdio::piped())
+        .output()
+        .expect("Failed to invoke executable");
+
+    assert!(!result.status.success(), "{:?}", result.status);
+    assert_eq!(
+        String::from_utf8_lossy(&result.stdout),
+        "test.blb:4:9: assertion failed\n"
+    );
+}
+
+#[test]
+fn test_failing_assert_with_declared_printf() {
+    let code = r#"
+    extern "C" function printf(format: string, ...): i32;
+    function main(): i32 {
+        printf("a = %d\n", 41);
+        assert(41 == 42);
+        return 0;
+    }"#;
+    let executable_path = "/tmp/assert_failure_declared_printf";
+    build_and_link(code, &format!("{}.o", executable_path), executable_path);
+
+    let result = Command::new(executable_path)
+        .stdout(Stdio::piped())
+        .output()
+        .expect("Failed to invoke executable");
+
+    assert!(!result.status.success(), "{:?}", result.status);
+    assert_eq!(
+        String::from_utf8_lossy(&result.stdout),
+        "a = 41\ntest.blb:5:9: assertion failed\n"
+    );
+}
+
+#[rstest]
+#[case::same_variable(
+    r#"
+    function f(c: bool, a: i32): i32 {
+        let x: i32 = 0;
+        if c {
+            x = a + 1;
+        } else {
+            x = 2;
+        }
+        return x;
+    }"#,
+    true
+)]
+#[case::different_variables(
+    r#"
+    function f(c: bool): i32 {
+        let x: i32 = 0;
+        let y: i32 = 0;
+        if c {
+            x = 1;
+        } else {
+            y = 2;
+        }
+        return x + y;
+    }"#,
+    false
+)]
+#[case::call_in_branch(
+    r#"
+    function g(): i32 {
+        return 2;
+    }
+    function f(c: bool): i32 {
+        let x: i32 = 0;
+        if c {
+            x = 1;
+        } else {
+            x = g();
+        }
+        return x;
+    }"#,
+    false
+)]
+#[case::division_in_branch(
+    r#"
+    function f(c: bool, a: i32): i32 {
+        let x: i32 = 0;
+        if c {
+            x = 1;
+        } else {
+            x = 10 / a;
+        }
+        return x;
+    }"#,
+    false
+)]
+fn test_if_assignment_select(#[case] code: &str, #[case] expect_select: bool) {
+    let context = Context::create();
+    let module = context.create_module("module");
+
+    translate_code(&context, &module, code, false);
+
+    if let Err(msg) = module.verify() {
+        panic!("Module verification failed: {}", msg.to_string());
+    }
+
+    let ir = module.print_to_string().to_string();
+    assert_eq!(ir.contains("select"), expect_select, "{ir}");
+}
+
+#[rstest]
+#[case::big_struct(
+    r#"
+    struct Big {
+        a: i64,
+        b: i64,
+        c: i64,
+        d: i64
+    }
+    function id(value: Big): Big {
+        return value;
+    }
+    function copy(value: Big): Big {
+        let copied: Big = id(value);
+        return copied;
+    }
+    function main(): i32 {
+        return 0;
+    }"#,
+    true
+)]
+#[case::padded_struct(
+    r#"
+    struct Padded {
+        a: i32,
+        b: i64,
+        c: i32
+    }
+    function id(value: Padded): Padded {
+        return value;
+    }
+    function main(): i32 {
+        return 0;
+    }"#,
+    true
+)]
+#[case::register_sized_struct(
+    r#"
+    struct Small {
+        a: i64,
+        b: i64
+    }
+    function id(value: Small): Small {
+        return value;
+    }
+    function main(): i32 {
+        return 0;
+    }"#,
+    false
+)]
+fn test_struct_return_slot(#[case] code: &str, #[case] expect_sret: bool) {
+    let context = Context::create();
+    let module = context.create_module("module");
+
+    translate_code(&context, &module, code, false);
+
+    if let Err(msg) = module.verify() {
+        panic!("Module verification failed: {}", msg.to_string());
+    }
+
+    let ir = module.print_to_string().to_string();
+    assert_eq!(ir.contains("sret"), expect_sret, "{ir}");
+}
+
+#[test]
+fn test_struct_local_alloca() {
+    let code = r#"
+    struct Point {
+        x: i32,
+        y: i32
+    }
+    function copy(point: Point): Point {
+        let copied: Point = point;
+        return copied;
+    }
+    function main(): i32 {
+        return 0;
+    }"#;
+
+    let stmts = type_check_code(code).expect("Type checker failed");
+    let mut collector = Collector::default();
+    let symbols = collector.dump_global_statements(&stmts).unwrap();
+    let locals = symbols.get("copy").expect("copy locals are not collected");
+    assert!(matches!(
+        locals.as_slice(),
+        [StackVariable {
+            name: "copied",
+            kind: type_system::Type::Struct { .. },
+            ..
+        }]
+    ));
+
+    let context = Context::create();
+    let module = context.create_module("module");
+    translate_code(&context, &module, code, false);
+
+    if let Err(msg) = module.verify() {
+        panic!("Module verification failed: {}", msg.to_string());
+    }
+
+    let ir = module.print_to_string().to_string();
+    assert!(ir.contains("%copied = alloca %Point"), "{ir}");
+}
+
+#[test]
+fn test_collect_shadowed_locals() {
+    let code = r#"
+    function f(c: bool): i32 {
+        let x: i32 = 1;
+        let x: i32 = 2;
+        if c {
+            let x: i32 = 3;
+            return x;
+        }
+        return x;
+    }"#;
+
+    let stmts = type_check_code(code).expect("Type checker failed");
+    let mut collector = Collector::default();
+    let symbols = collector.dump_global_statements(&stmts).unwrap();
+    let locals = symbols.get("f").expect("f locals are not collected");
+
+    // Every declaration has its own alloca even if they share the same name
+    assert_eq!(locals.len(), 3);
+    assert!(locals.iter().all(|local| local.name == "x"));
+    for (i, local) in locals.iter().enumerate() {
+        assert!(locals[i + 1..]
+            .iter()
+            .all(|other| other.declaration != local.declaration));
+    }
+}
+
+#[rstest]
+#[case::x86_64(create_target_machine(), "i64")]
+#[case::i686(create_i686_target_machine(), "i32")]
+fn test_array_index_pointer_width(#[case] target_machine: TargetMachine, #[case] index_type: &str) {
+    let context = Context::create();
+    let module = context.create_module("module");
+    translate_code_for_target(
+        &context,
+        &module,
+        r#"
+    function main(): i32 {
+        let values: [4; i32] = [1, 2, 3, 42];
+        return values[3];
+    }"#,
+        &target_machine,
+    );
+
+    if let Err(msg) = module.verify() {
+        panic!("Module verification failed: {}", msg.to_string());
+    }
+
+    let ir = module.print_to_string().to_string();
+    let gep = ir
+        .lines()
+        .find(|line| line.contains("%load_ptr_array_access = getelementptr"))
+        .expect("Array access has no getelementptr");
+    assert!(gep.ends_with(&format!(", {index_type} 3")), "{gep}");
+
+    target_machine
+        .write_to_memory_buffer(&module, FileType::Object)
+        .expect("Failed to compile module");
+}
+
+#[test]
+fn test_exported_function_symbol() {
+    let context = Context::create();
+    let module = context.create_module("module");
+
+    translate_code(
+        &context,
+        &module,
+        r#"
+    export function bubble_add(a: i32, b: i32): i32 {
+        return a + b;
+    }"#,
+        false,
+    );
+
+    let function = module
+        .get_function("bubble_add")
+        .expect("Exported function is not in the module");
+    assert_eq!(function.get_name().to_str(), Ok("bubble_add"));
+    assert_eq!(function.get_linkage(), Linkage::External);
+}
+
+#[test]
+fn test_static_variable_global() {
+    let context = Context::create();
+    let module = context.create_module("module");
+
+    translate_code(
+        &context,
+        &module,
+        r#"
+    function counter(): i32 {
+        static count: i32 = 0;
+        count = count + 1;
+        return count;
+    }"#,
+        false,
+    );
+
+    let global = module
+        .get_global("counter.count")
+        .expect("Static variable is not a global");
+    assert_eq!(global.get_linkage(), Linkage::Internal);
+}
+
+#[rstest]
+#[case::float("static x: float = 1.5;", "f.x")]
+#[case::string("static message: string = \"hello\";", "f.message")]
+#[case::null("static p: ptr i32 = null;", "f.p")]
+fn test_static_constant_initializer(#[case] declaration: &str, #[case] global_name: &str) {
+    let context = Context::create();
+    let module = context.create_module("module");
+
+    translate_code(
+        &context,
+        &module,
+        &format!("function f() {{ {declaration} }}"),
+        false,
+    );
+
+    let global = module
+        .get_global(global_name)
+        .expect("Static variable is not a global");
+    assert!(
+        global.get_initializer().is_some(),
+        "Static variable has no initializer"
+    );
+}
+
+#[rstest]
+#[case::inline("inline", "alwaysinline")]
+#[case::noinline("noinline", "noinline")]
+fn test_function_attribute(#[case] attribute: &str, #[case] llvm_attribute: &str) {
+    let context = Context::create();
+    let module = context.create_module("module");
+
+    translate_code(
+        &context,
+        &module,
+        &format!(
+            r#"
+    @{attribute}
+    function add(a: i32, b: i32): i32 {{
+        return a + b;
+    }}
+    function main(): i32 {{
+        return add(40, 2);
+    }}"#
+        ),
+        false,
+    );
+
+    let function = module
+        .get_function("add")
+        .expect("Function is not in the module");
+    let kind_id = Attribute::get_named_enum_kind_id(llvm_attribute);
+    assert!(function
+        .get_enum_attribute(AttributeLoc::Function, kind_id)
+        .is_some());
+    assert!(module
+        .print_to_string()
+        .to_string()
+        .contains(llvm_attribute));
+
+    let main = module
+        .get_function("main")
+        .expect("main is not in the module");
+    assert!(main
+        .get_enum_attribute(AttributeLoc::Function, kind_id)
+        .is_none());
+}
+
+#[rstest]
+#[case::disabled(false, false)]
+#[case::stack_protector(true, false)]
+#[case::frame_pointer(false, true)]
+#[case::both(true, true)]
+fn test_hardening_attributes(#[case] stack_protector: bool, #[case] frame_pointer: bool) {
+    let context = Context::create();
+    let module = context.create_module("module");
+
+    translate_code_with_hardening(
+        &context,
+        &module,
+        r#"
+    extern function puts(s: string): i32;
+    function add(a: i32, b: i32): i32 {
+        return a + b;
+    }
+    function main(): i32 {
+        puts("hello");
+        return add(40, 2);
+    }"#,
+        HardeningOptions {
+            stack_protector,
+            frame_pointer,
+        },
+        false,
+    );
+
+    let sspstrong = Attribute::get_named_enum_kind_id("sspstrong");
+    for name in ["add", "main"] {
+        let function = module
+            .get_function(name)
+            .expect("Function is not in the module");
+        assert_eq!(
+            function
+                .get_enum_attribute(AttributeLoc::Function, sspstrong)
+                .is_some(),
+            stack_protector
+        );
+        assert_eq!(
+            function
+                .get_string_attribute(AttributeLoc::Function, "frame-pointer")
+                .is_some(),
+            frame_pointer
+        );
+    }
+
+    let ir = module.print_to_string().to_string();
+    assert_eq!(ir.contains("sspstrong"), stack_protector);
+    assert_eq!(ir.contains(r#""frame-pointer"="all""#), frame_pointer);
+
+    // Declarations have no body to protect
+    let puts = module
+        .get_function("puts")
+        .expect("puts is not in the module");
+    assert!(puts
+        .get_enum_attribute(AttributeLoc::Function, sspstrong)
+        .is_none());
+}
+
+#[test]
+fn test_fast_calling_convention() {
+    let context = Context::create();
+    let module = context.create_module("module");
+
+    translate_code(
+        &context,
+        &module,
+        r#"
+    extern "fast" function add(a: i32, b: i32): i32 {
+        return a + b;
+    }
+    extern "C" function puts(s: string): i32;
+    function main(): i32 {
+        puts("hello");
+        return add(40, 2);
+    }"#,
+        false,
+    );
+
+    let add = module
+        .get_function("add")
+        .expect("Function is not in the module");
+    assert_eq!(add.get_call_conventions(), 8);
+    let puts = module
+        .get_function("puts")
+        .expect("Function is not in the module");
+    assert_eq!(puts.get_call_conventions(), 0);
+
+    let ir = module.print_to_string().to_string();
+    assert!(ir.contains("define fastcc i32 @add("), "{ir}");
+    assert!(ir.contains("call fastcc i32 @add("), "{ir}");
+}
+
+/// Number of instructions of the module, and how many of them are loads
+fn count_instructions(module: &Module) -> (usize, usize) {
+    let mut instructions = 0;
+    let mut loads = 0;
+
+    for function in module.get_functions() {
+        for block in function.get_basic_blocks() {
+            let mut instruction = block.get_first_instruction();
+            while let Some(current) = instruction {
+                instructions += 1;
+                if current.get_opcode() == InstructionOpcode::Load {
+                    loads += 1;
+                }
+                instruction = current.get_next_instruction();
+            }
+        }
+    }
+
+    (instructions, loads)
+}
+
+#[test]
+fn test_optimization_passes_remove_loads() {
+    let context = Context::create();
+    let module = context.create_module("module");
+
+    translate_code(
+        &context,
+        &module,
+        r#"
+    function main(): i32 {
+        let a: i32 = 1;
+        let b: i32 = a + 41;
+        return b;
+    }"#,
+        false,
+    );
+
+    let (instructions_before, loads_before) = count_instructions(&module);
+    assert!(loads_before > 0);
+
+    assert!(run_optimization_passes(&module));
+    if let Err(msg) = module.verify() {
+        panic!("Module verification failed: {}", msg.to_string());
+    }
+
+    let (instructions_after, loads_after) = count_instructions(&module);
+    assert_eq!(loads_after, 0);
+    assert!(instructions_after < instructions_before);
 }
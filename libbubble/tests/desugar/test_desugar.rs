@@ -0,0 +1,287 @@
+use std::{collections::HashSet, convert::Infallible};
+
+use libbubble::{
+    ast::{
+        Bindable, Definition, Expression, FunctionStatement, GlobalStatement, LetStatement,
+        Literal, LiteralType, Locatable, SourceFile, StatementKind, TryNull, Visitor,
+    },
+    desugar::{desugar_ast, strip_cfg},
+};
+use rstest::rstest;
+
+use crate::assets::{parse_global_statements_input, type_check_code};
+
+/// Collects the local variables declared in an AST and the declarations
+/// bound to the identifiers using them.
+#[derive(Default)]
+struct LocalVariables {
+    declarations: HashSet<*const LetStatement>,
+    uses: Vec<(String, *const LetStatement)>,
+}
+
+impl<'ast> Visitor<'ast, Infallible> for LocalVariables {
+    fn visit_function(&mut self, stmt: &'ast FunctionStatement) -> Result<(), Infallible> {
+        for parameter in &stmt.parameters {
+            self.visit_let(parameter)?;
+        }
+
+        if let Some(body) = &stmt.body {
+            self.visit_statements(body)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_let(&mut self, stmt: &'ast LetStatement) -> Result<(), Infallible> {
+        self.declarations.insert(stmt);
+
+        match &stmt.init_exp {
+            Some(init_exp) => self.visit_expression(init_exp),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_literal(&mut self, expr: &'ast Literal) -> Result<(), Infallible> {
+        match &expr.literal_type {
+            LiteralType::Identifier(name) => {
+                if let Definition::LocalVariable(declaration) = expr.get_definition() {
+                    self.uses.push((name.clone(), *declaration));
+                }
+
+                Ok(())
+            }
+            LiteralType::ArrayAccess(array_access) => {
+                self.visit_expression(&array_access.identifier)?;
+                self.visit_expression(&array_access.index)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[rstest]
+#[case::for_loop(
+    r#"
+    function main(): i32 {
+        let sum: i32 = 0;
+        for i: i32 = 0; i < 5; i = i + 1 {
+            sum = sum + i;
+        }
+        return sum;
+    }"#
+)]
+#[case::array_in_for_loop(
+    r#"
+    function main(): i32 {
+        let values: [3; i32] = [1, 2, 3];
+        let total: i32 = 0;
+        for i: i64 = 0; i < 3; i = i + 1 {
+            let value: i32 = values[i];
+            total = total + value;
+        }
+        return total;
+    }"#
+)]
+#[case::defer_in_for_loop(
+    r#"
+    function main(): i32 {
+        let count: i32 = 0;
+        for i: i32 = 0; i < 3; i = i + 1 {
+            defer count = count + 1;
+            if i == 1 {
+                continue;
+            }
+        }
+        return count;
+    }"#
+)]
+#[case::deferred_return_value(
+    r#"
+    function main(): i32 {
+        let value: i32 = 42;
+        defer value = 0;
+        assert(value == 42);
+        return value;
+    }"#
+)]
+fn test_desugared_definitions_are_valid(#[case] code: &str) {
+    let stmts = desugar_ast(
+        type_check_code(code).expect("Type checker failed"),
+        &SourceFile::new("test.blb", code),
+    );
+
+    let mut local_variables = LocalVariables::default();
+    for stmt in &stmts {
+        local_variables.visit_global_statement(stmt).unwrap();
+    }
+
+    assert!(!local_variables.uses.is_empty());
+    for (name, declaration) in local_variables.uses {
+        assert!(
+            local_variables.declarations.contains(&declaration),
+            "`{name}` is bound to a declaration outside of the desugared AST"
+        );
+
+        let declaration = unsafe { &*declaration };
+        assert_eq!(declaration.name, name);
+    }
+}
+
+#[rstest]
+#[case::undefined(&[], &["main"])]
+#[case::defined(&["TEST"], &["run_tests", "main"])]
+#[case::other_symbol(&["DEBUG"], &["main"])]
+fn test_strip_cfg(#[case] defines: &[&str], #[case] expected_functions: &[&str]) {
+    let code = r#"
+    @cfg(TEST)
+    function run_tests(): i32 {
+        return 0;
+    }
+    function main(): i32 {
+        return 0;
+    }"#;
+
+    let defined: HashSet<String> = defines.iter().map(|symbol| symbol.to_string()).collect();
+    let stmts = strip_cfg(
+        parse_global_statements_input(code).expect("Failed to parse code"),
+        &defined,
+    );
+
+    let functions: Vec<&str> = stmts
+        .iter()
+        .map(|stmt| match stmt {
+            GlobalStatement::Function(function) => function.name.as_str(),
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(functions, expected_functions);
+}
+
+/// Counts the deferred statements run by each `?` expression
+#[derive(Default)]
+struct TryNullDeferred {
+    deferred: Vec<usize>,
+}
+
+impl<'ast> Visitor<'ast, Infallible> for TryNullDeferred {
+    fn visit_try_null(&mut self, expr: &'ast TryNull) -> Result<(), Infallible> {
+        self.deferred.push(expr.deferred.len());
+        self.visit_expression(&expr.expr)
+    }
+}
+
+#[test]
+fn test_try_null_runs_deferred_expressions() {
+    let code = r#"
+    extern function free(p: ptr void): void;
+    function f(p: ptr void, q: ptr void): ptr void {
+        let a = p?;
+        defer free(a);
+        if true {
+            defer free(q);
+            let b = q?;
+        }
+        return q?;
+    }"#;
+    let stmts = desugar_ast(
+        type_check_code(code).expect("Type checker failed"),
+        &SourceFile::new("test.blb", code),
+    );
+
+    let mut try_null_deferred = TryNullDeferred::default();
+    for stmt in &stmts {
+        try_null_deferred.visit_global_statement(stmt).unwrap();
+    }
+
+    assert_eq!(try_null_deferred.deferred, [0, 2, 1]);
+}
+
+#[test]
+fn test_desugared_for_keeps_locations() {
+    let code = r#"
+    function main(): i32 {
+        let total: i32 = 0;
+        for i: i32 = 0; i < 3; i = i + 1 {
+            total = total + i;
+        }
+        return total;
+    }"#;
+    let stmts = desugar_ast(
+        type_check_code(code).expect("Type checker failed"),
+        &SourceFile::new("test.blb", code),
+    );
+
+    let [GlobalStatement::Function(main)] = stmts.as_slice() else {
+        panic!("Expected a single function");
+    };
+    let body = &main.body.as_ref().expect("main has no body").statements;
+    let source_of = |stmt_index: usize| {
+        let location = body[stmt_index].get_location();
+        &code[location.begin..location.end]
+    };
+
+    assert_eq!(source_of(1), "i: i32 = 0");
+    assert!(source_of(2).starts_with("for i: i32 = 0;"));
+
+    let StatementKind::While(while_stmt) = &body[2].kind else {
+        panic!("The for loop is not desugared to a while loop");
+    };
+    let modify = while_stmt
+        .body
+        .statements
+        .last()
+        .expect("The while loop has no statement")
+        .get_location();
+    assert_eq!(&code[modify.begin..modify.end], "i = i + 1");
+}
+
+#[test]
+fn test_desugared_assert_reports_line() {
+    let code = r#"
+    function main(): i32 {
+        let a: i32 = 41;
+        assert(a == 42);
+        return 0;
+    }"#;
+    let stmts = desugar_ast(
+        type_check_code(code).expect("Type checker failed"),
+        &SourceFile::new("test.blb", code),
+    );
+
+    let main = stmts
+        .iter()
+        .find_map(|stmt| match stmt {
+            GlobalStatement::Function(function) if function.name == "main" => Some(function),
+            _ => None,
+        })
+        .expect("main is not declared");
+    let body = &main.body.as_ref().expect("main has no body").statements;
+    let StatementKind::If(if_stmt) = &body[1].kind else {
+        panic!("The assert is not desugared to an if statement");
+    };
+    let StatementKind::Expression { expr, .. } = &if_stmt.then_clause.statements[0].kind else {
+        panic!("The assert failure is not reported by a call");
+    };
+    let Expression::Call(report) = expr.as_ref() else {
+        panic!("The assert failure is not reported by a call");
+    };
+
+    let arguments: Vec<&LiteralType> = report
+        .arguments
+        .iter()
+        .map(|argument| match argument {
+            Expression::Literal(literal) => &literal.literal_type,
+            _ => panic!("Report arguments are literals"),
+        })
+        .collect();
+    assert_eq!(report.callee, "printf");
+    assert!(matches!(
+        arguments.as_slice(),
+        [
+            LiteralType::String(_),
+            LiteralType::String(path),
+            LiteralType::Integer(4),
+            LiteralType::Integer(9),
+        ] if path == "test.blb"
+    ));
+}
@@ -1,4 +1,4 @@
-use libbubble::parser::lexer::Token;
+use libbubble::parser::lexer::{Lexer, LexicalError, Token};
 use logos::Logos;
 use rstest::rstest;
 
@@ -29,24 +29,48 @@ use rstest::rstest;
 )]
 #[case::break_kw("break break", &[Token::Break, Token::Break])]
 #[case::continue_kw("continue continue", &[Token::Continue, Token::Continue])]
+#[case::assert_kw("assert assert", &[Token::Assert, Token::Assert])]
+#[case::defer_kw("defer", &[Token::Defer])]
 #[case::true_kw("true true", &[Token::True, Token::True])]
 #[case::true_kw("false false", &[Token::False, Token::False])]
 #[case::for_kw("for for", &[Token::For, Token::For])]
+#[case::enum_kw("enum", &[Token::Enum])]
 #[case::type_unsigned("u8 u16 u32 u64", &[Token::U8Ty, Token::U16Ty, Token::U32Ty, Token::U64Ty])]
 #[case::type_unsigned("i8 i16 i32 i64", &[Token::I8Ty, Token::I16Ty, Token::I32Ty, Token::I64Ty])]
+#[case::type_128_bits("u128 i128", &[Token::U128Ty, Token::I128Ty])]
 #[case::type_string("string", &[Token::StringTy])]
 #[case::type_string("extern function", &[Token::Extern, Token::Function])]
+#[case::export_function("export function", &[Token::Export, Token::Function])]
 #[case::type_bool("bool", &[Token::BoolTy])]
+#[case::type_float("float", &[Token::FloatTy])]
 #[case::case_string_literal(r#""Salut les amis""#, &[Token::String("Salut les amis".to_string())])]
+#[case::case_string_literal_non_ascii(r#""héllo" 42"#, &[Token::String("héllo".to_string()), Token::Integer(42)])]
+#[case::case_string_literal_escapes(
+    r#""a\0b\n\t\\\"""#,
+    &[Token::String("a\0b\n\t\\\"".to_string())]
+)]
 #[case::identifier("my_var", &[Token::Identifier("my_var".to_string())])]
+#[case::raw_identifier("r#for", &[Token::Identifier("for".to_string())])]
+#[case::raw_identifier_type_name("r#string", &[Token::Identifier("string".to_string())])]
 #[case::integer("42", &[Token::Integer(42)])]
+#[case::integer_above_u64("18446744073709551616", &[Token::Integer(18446744073709551616)])]
 #[case::integer_too_big(
     "38574895743859734589347589347598340853495873409584389573489574389574389573",
     &[Token::Error]
 )]
+#[case::integer_then_identifier(
+    "123 abc",
+    &[Token::Integer(123), Token::Identifier("abc".to_string())]
+)]
+#[case::integer_with_identifier_suffix("123abc", &[Token::Error])]
+#[case::integer_with_incomplete_exponent("1e", &[Token::Error])]
 #[allow(clippy::approx_constant)] // it's ok for a lexer test
 #[case::float("3.14", &[Token::Real(3.14)])]
 #[case::nothing_before_dot_float(".032", &[Token::Real(0.032)])]
+#[case::exponent_float("1e10", &[Token::Real(1e10)])]
+#[case::negative_exponent_float("1.5e-3", &[Token::Real(1.5e-3)])]
+#[case::nothing_before_dot_exponent_float(".5e2", &[Token::Real(50.0)])]
+#[case::uppercase_exponent_float("2E+3", &[Token::Real(2000.0)])]
 #[case::let_declaration(
     "let var = 42;",
     &[
@@ -98,6 +122,13 @@ use rstest::rstest;
 #[case::ptr_lex("deref", &[ Token::Deref ])]
 #[case::addrof_var("deref x", &[ Token::Deref, Token::Identifier("x".to_string()) ])]
 #[case::null("null", &[ Token::Null ])]
+#[case::alignof("alignof(i64)", &[ Token::Alignof, Token::LeftParen, Token::I64Ty, Token::RightParen ])]
+#[case::const_kw("const", &[ Token::Const ])]
+#[case::static_kw("static", &[ Token::Static ])]
+#[case::slice_range("1..3", &[ Token::Integer(1), Token::DotDot, Token::Integer(3) ])]
+#[case::ellipsis("...", &[ Token::Ellipsis ])]
+#[case::attribute("@inline", &[ Token::At, Token::Identifier("inline".to_string()) ])]
+#[case::try_null("p?", &[ Token::Identifier("p".to_string()), Token::Question ])]
 fn test_code_lexing(#[case] source_code: &str, #[case] expected: &[Token]) {
     let lexer = Token::lexer(source_code);
     let tokens: Vec<Token> = lexer.collect();
@@ -110,3 +141,96 @@ fn test_code_lexing(#[case] source_code: &str, #[case] expected: &[Token]) {
         expected
     );
 }
+
+#[test]
+fn test_tokenize_spans() {
+    let tokens = Lexer::tokenize("let a = 42;").expect("Failed to tokenize");
+
+    assert_eq!(
+        tokens,
+        vec![
+            (Token::Let, 0..3),
+            (Token::Identifier("a".to_string()), 4..5),
+            (Token::Equal, 6..7),
+            (Token::Integer(42), 8..10),
+            (Token::Semicolon, 10..11),
+        ]
+    );
+}
+
+#[test]
+fn test_tokenize_stops_at_error() {
+    assert!(Lexer::tokenize("let a = $;").is_err());
+}
+
+#[test]
+fn test_unknown_string_escape() {
+    assert!(Lexer::tokenize(r#"let a = "\q";"#).is_err());
+}
+
+#[test]
+fn test_float_overflow() {
+    let error = Lexer::new("let a = 1e400;")
+        .find_map(Result::err)
+        .expect("Lexer should fail");
+
+    assert!(matches!(
+        error,
+        LexicalError::InvalidFloatLiteral { span, .. } if span == (8..13)
+    ));
+}
+
+#[test]
+fn test_integer_with_identifier_suffix() {
+    let error = Lexer::new("let a = 123abc;")
+        .find_map(Result::err)
+        .expect("Lexer should fail");
+
+    assert_eq!(
+        error,
+        LexicalError::InvalidIntegerLiteral {
+            msg: "invalid suffix `abc`".to_string(),
+            span: 8..14
+        }
+    );
+}
+
+#[test]
+fn test_invalid_token_span() {
+    let error = Lexer::new("let a = $;")
+        .find_map(Result::err)
+        .expect("Lexer should fail");
+
+    assert_eq!(error, LexicalError::InvalidToken { span: 8..9 });
+    assert_eq!(error.to_string(), "Invalid token at 8:9");
+}
+
+#[rstest]
+#[case::line_comment("// hi\n42", &[Token::LineComment(" hi".to_string()), Token::Integer(42)])]
+#[case::block_comment(
+    "/* a\n * b **/ 42",
+    &[Token::BlockComment(" a\n * b *".to_string()), Token::Integer(42)]
+)]
+#[case::comment_after_code(
+    "42 // answer",
+    &[Token::Integer(42), Token::LineComment(" answer".to_string())]
+)]
+fn test_comments_preserved(#[case] source_code: &str, #[case] expected: &[Token]) {
+    let tokens: Vec<Token> = Lexer::new_with_comments(source_code)
+        .map(|spanned| spanned.expect("Failed to lex comment").1)
+        .collect();
+
+    assert_eq!(tokens, expected);
+}
+
+#[rstest]
+#[case::line_comment("// hi\n42", &[Token::Integer(42)])]
+#[case::block_comment("/* hi */ 42", &[Token::Integer(42)])]
+#[case::divide_is_not_a_comment("1 / 2", &[Token::Integer(1), Token::Slash, Token::Integer(2)])]
+fn test_comments_skipped(#[case] source_code: &str, #[case] expected: &[Token]) {
+    let tokens: Vec<Token> = Lexer::new(source_code)
+        .map(|spanned| spanned.expect("Failed to lex comment").1)
+        .collect();
+
+    assert_eq!(tokens, expected);
+}
@@ -1,5 +1,7 @@
 mod assets;
+mod ast;
 mod codegen;
+mod desugar;
 mod lexer;
 mod parser;
 mod type_checking;
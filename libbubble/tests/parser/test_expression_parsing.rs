@@ -47,6 +47,13 @@ use rstest::rstest;
 #[case::array_access_with_call("array[f(12)]")]
 #[case::null_expr("null")]
 #[case::deref("deref x")]
+#[case::slice("array[1..3]")]
+#[case::slice_with_sub_expr("array[i + 1..n * 2]")]
+#[case::try_null("p?")]
+#[case::try_null_call("malloc(8)?")]
+#[case::try_null_deref("deref p?")]
+#[case::indirect_call("table[0]()")]
+#[case::indirect_call_args("table[i + 1](42, x)")]
 fn test_valid_expression(#[case] code: &str) {
     let parser_result = parse_statements_input(code);
     assert!(
@@ -1,3 +1,4 @@
+use libbubble::parser::lexer::Token;
 use rstest::rstest;
 
 use crate::assets::{parse_global_statements_input, parse_statements_input};
@@ -11,6 +12,17 @@ use crate::assets::{parse_global_statements_input, parse_statements_input};
 #[case::for_statement_increment("for id = 0; id != 10; id + 1 { 32; }")]
 #[case::for_statement_with_typename("for id: u32 = 32; id != 32; id + 2 { 42 }")]
 #[case::valid_assignment("a = 2;")]
+#[case::chained_assignment("a = b = c = 2;")]
+#[case::method_call("p.get(1, 2) + 1;")]
+#[case::enum_variant("c == Color.Red;")]
+#[case::while_else("while a < 3 { a = a + 1; } else { b = 2; }")]
+#[case::uninitialized_let("let a: i32; a = 2;")]
+#[case::raw_identifier_let("let r#for: i32 = 2; r#for = r#for + 1;")]
+#[case::tuple_expression("(a, b + 1);")]
+#[case::let_tuple("let (q, r) = divmod(17, 5);")]
+#[case::let_array("let [a, b, c] = values;")]
+#[case::defer_call("defer free(p);")]
+#[case::static_variable("static count: i32 = 0; count = count + 1;")]
 fn test_valid_statements(#[case] code: &str) {
     let parser_result = parse_statements_input(code);
     if parser_result.is_err() {
@@ -190,6 +202,14 @@ fn test_valid_statements(#[case] code: &str) {
     }
 "#
 )]
+#[case::alignof(
+    r#"
+    function f(): i64 {
+        let a: u64 = alignof(ptr i32) + alignof([4; i8]);
+        return 0;
+    }
+"#
+)]
 #[case::deref_pointer(
     r#"
     function f(): i64 {
@@ -215,6 +235,154 @@ fn test_valid_statements(#[case] code: &str) {
     }
 "#
 )]
+#[case::const_declaration(
+    r#"
+    const MAX: i32 = 100;
+"#
+)]
+#[case::const_used_in_function(
+    r#"
+    const MAX: i32 = 2 * 50;
+
+    function f(): i32 {
+        return MAX;
+    }
+"#
+)]
+#[case::array_size_expression(
+    r#"
+    const SIZE: u32 = 4;
+
+    function f(): i32 {
+        let a: [SIZE; i32] = [1, 2, 3, 4];
+        let b: [2 * 3; u8] = [1, 2, 3, 4, 5, 6];
+        return 0;
+    }
+"#
+)]
+#[case::extern_variable(
+    r#"
+    extern let errno: i32;
+
+    function f(): i32 {
+        return errno;
+    }
+"#
+)]
+#[case::variadic_extern_function(
+    r#"
+    extern function printf(msg: string, ...): i32;
+"#
+)]
+#[case::variadic_extern_function_no_fixed_parameter(
+    r#"
+    extern function f(...): void;
+"#
+)]
+#[case::exported_functions(
+    r#"
+    export function f() { }
+    export function add(a: i32, b: i32): i32 {
+        return a + b;
+    }
+"#
+)]
+#[case::assert_in_function(
+    r#"
+    function f(a: i32) {
+        assert(a == 42);
+    }
+"#
+)]
+#[case::function_pointer_declaration(
+    r#"
+    function f() {
+        let callback: ptr function(i32, bool): i32 = null;
+    }
+"#
+)]
+#[case::function_pointer_parameter(
+    r#"
+    function apply(f: ptr function(i32): i32, x: i32): i32 {
+        return x;
+    }
+"#
+)]
+#[case::parenthesized_type(
+    r#"
+    extern let p: ptr (ptr i32);
+"#
+)]
+#[case::empty_function_body(
+    r#"
+    function f() { }
+    function g(): i32 { }
+"#
+)]
+#[case::raw_identifier_names(
+    r#"
+    function r#while(r#string: string): string {
+        return r#string;
+    }
+"#
+)]
+#[case::comments(
+    r#"
+    // The answer
+    function f(): i32 {
+        /* to everything */
+        return 42; // really
+    }
+"#
+)]
+#[case::methods(
+    r#"
+    struct Point { x: i32 }
+    function Point.reset(self) { }
+    function Point.add(self, value: i32): i32 {
+        return self.get() + value;
+    }
+"#
+)]
+#[case::enums(
+    r#"
+    enum Empty {}
+    enum Color { Red, Green, Blue, }
+    function f(c: Color): Color {
+        return Color.Green;
+    }
+"#
+)]
+#[case::attributes(
+    r#"
+    @inline
+    function f(): i32 {
+        return 0;
+    }
+    @noinline @custom
+    export function g() {}
+    @cfg(TEST)
+    function h() {}
+"#
+)]
+#[case::calling_conventions(
+    r#"
+    extern "C" function puts(s: string): i32;
+    extern "C" function printf(format: string, ...): i32;
+    extern "fast" function add(a: i32, b: i32): i32;
+    @inline
+    extern "fast" function sub(a: i32, b: i32): i32 {
+        return a - b;
+    }
+"#
+)]
+#[case::tuple_return_type(
+    r#"
+    function divmod(a: i32, b: i32): (i32, i32) {
+        return (a / b, a % b);
+    }
+"#
+)]
 fn test_valid_global_statements(#[case] code: &str) {
     let parser_result = parse_global_statements_input(code);
     assert!(
@@ -223,3 +391,40 @@ fn test_valid_global_statements(#[case] code: &str) {
         parser_result.unwrap_err()
     );
 }
+
+#[test]
+fn test_lexical_error_span() {
+    match parse_statements_input("let a = $;") {
+        Err(lalrpop_util::ParseError::User { error }) => assert_eq!(error.span(), &(8..9)),
+        result => panic!("Expected a lexical error, got: {:?}", result),
+    }
+}
+
+#[test]
+fn test_unknown_calling_convention() {
+    match parse_global_statements_input(r#"extern "rust" function f(): i32;"#) {
+        Err(lalrpop_util::ParseError::UnrecognizedToken { token, .. }) => {
+            assert_eq!(token, (7, Token::String("rust".to_string()), 13))
+        }
+        result => panic!("Expected an unrecognized token, got: {:?}", result),
+    }
+}
+
+#[rstest]
+#[case::empty_input("")]
+#[case::unclosed_string(r#"function f(): string { return "abc; }"#)]
+#[case::unclosed_string_non_ascii(r#"function f(): string { return "é"#)]
+#[case::unclosed_block_comment("/* function f(): i32 { return 0; }")]
+#[case::integer_overflow("function f(): i64 { return 99999999999999999999; }")]
+#[case::invalid_token("function f(): i32 { return $; }")]
+#[case::truncated_function("function f(")]
+#[case::unbalanced_braces("}}}{{{")]
+fn test_try_parse_never_panics(#[case] input: &str) {
+    let _ = libbubble::try_parse(input);
+}
+
+#[test]
+fn test_try_parse_non_ascii_string() {
+    let result = libbubble::try_parse(r#"function f(): string { return "héllo"; }"#);
+    assert!(result.is_ok(), "{:?}", result.unwrap_err());
+}
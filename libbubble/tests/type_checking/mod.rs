@@ -1,2 +1,3 @@
 mod test_binder;
 mod test_type_checker;
+mod test_type_dumper;
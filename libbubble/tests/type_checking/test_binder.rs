@@ -1,8 +1,11 @@
 use rstest::rstest;
 
-use libbubble::type_system::binder;
+use libbubble::{
+    ast::{Bindable, Definition, Expression, GlobalStatement, MutableVisitor, StatementKind},
+    type_system::{binder, BinderError, Warning},
+};
 
-use crate::assets::parse_global_statements_input;
+use crate::assets::{parse_global_statements_input, parse_statements_input};
 
 #[rstest]
 #[case::variable_binding(
@@ -99,6 +102,86 @@ use crate::assets::parse_global_statements_input;
        }
    "#
 )]
+#[case::call_before_definition(
+    r#"
+    function main(): i32 {
+        return f();
+    }
+    function f(): i32 {
+        return 42;
+    }
+ "#
+)]
+#[case::mutual_recursion(
+    r#"
+    function a(n: i32) {
+        if n > 0 {
+            b(n - 1);
+        }
+    }
+    function b(n: i32) {
+        if n > 0 {
+            a(n - 1);
+        }
+    }
+ "#
+)]
+#[case::mutually_recursive_structs(
+    r#"
+    struct A {
+        b: ptr B
+    }
+    struct B {
+        a: ptr A
+    }
+ "#
+)]
+#[case::method_call(
+    r#"
+    struct Point { x: i32 }
+    function Point.get(self): i32 { return 0; }
+    function f(p: Point, q: ptr Point): i32 {
+        return p.get() + q.get();
+    }
+"#
+)]
+#[case::method_call_on_self(
+    r#"
+    struct Point { x: i32 }
+    function Point.get(self): i32 { return 0; }
+    function Point.get_twice(self): i32 { return self.get() + self.get(); }
+"#
+)]
+#[case::enum_variant(
+    r#"
+    function f(): Color {
+        return Color.Red;
+    }
+    enum Color { Red }
+"#
+)]
+// Calling a variable is reported by the type checker
+#[case::local_variable_call(
+    r#"
+       function f() {
+           let a = 2;
+           a();
+       }
+   "#
+)]
+#[case::shadow_after_deferred_scope(
+    r#"
+    function f(c: bool) {
+        let x: i32 = 1;
+        if c {
+            defer x + 1;
+        }
+        if c {
+            let x: bool = true;
+        }
+    }
+"#
+)]
 fn test_binding_good(#[case] code: &str) {
     let mut stmts = parse_global_statements_input(code).expect("Failed to parse code");
     let mut binder = binder::Binder::default();
@@ -157,14 +240,6 @@ fn test_binding_good(#[case] code: &str) {
     }
 "#
 )]
-#[case::local_variable_not_callable(
-    r#"
-       function f() {
-           let a = 2;
-           a();
-       }
-   "#
-)]
 #[case::expr_subscriptable(
     r#"
        function f() {
@@ -172,8 +247,213 @@ fn test_binding_good(#[case] code: &str) {
        }
    "#
 )]
+#[case::undeclared_method(
+    r#"
+    struct Point { x: i32 }
+    function f(p: Point) {
+        p.get();
+    }
+"#
+)]
+#[case::method_call_on_non_struct(
+    r#"
+    struct Point { x: i32 }
+    function Point.get(self): i32 { return 0; }
+    function f(p: i32) {
+        p.get();
+    }
+"#
+)]
+#[case::method_call_without_annotation(
+    r#"
+    struct Point { x: i32 }
+    function Point.get(self): i32 { return 0; }
+    function f(p: Point) {
+        let q = p;
+        q.get();
+    }
+"#
+)]
+#[case::undeclared_enum(
+    r#"
+    function f() {
+        Color.Red;
+    }
+"#
+)]
+#[case::undeclared_enum_variant(
+    r#"
+    enum Color { Red }
+    function f() {
+        Color.Blue;
+    }
+"#
+)]
+#[case::method_call_on_enum(
+    r#"
+    enum Color { Red }
+    function Color.get(self): i32 { return 0; }
+    function f(c: Color) {
+        c.get();
+    }
+"#
+)]
+#[case::shadowed_deferred_variable(
+    r#"
+    extern function printf(msg: string, ...): i32;
+    function f(c: bool): i32 {
+        let x: i32 = 1;
+        defer printf("%d", x);
+        if c {
+            let x: bool = true;
+            return 0;
+        }
+        return 1;
+    }
+"#
+)]
+#[case::deferred_variable_redeclared(
+    r#"
+    function f(x: i32) {
+        defer x + 1;
+        let x: i32 = 2;
+    }
+"#
+)]
 fn test_binding_bad(#[case] code: &str) {
     let mut stmts = parse_global_statements_input(code).expect("Failed to parse code");
     let mut binder = binder::Binder::default();
     assert!(binder.bind_statements(&mut stmts).is_err());
 }
+
+#[rstest]
+#[case::shadow_in_inner_scope(
+    r#"
+    function f(c: bool) {
+        let a: i32 = 1;
+        if c {
+            let a: i32 = 2;
+        }
+    }
+ "#,
+    1
+)]
+#[case::shadow_parameter(
+    r#"
+    function f(a: i32) {
+        while true {
+            let a: i32 = 2;
+        }
+    }
+ "#,
+    1
+)]
+#[case::shadow_parameter_in_body(
+    r#"
+    function f(a: i32) {
+        let a: i32 = 2;
+    }
+ "#,
+    1
+)]
+#[case::sibling_scopes(
+    r#"
+    function f(c: bool) {
+        if c {
+            let a: i32 = 1;
+        } else {
+            let a: i32 = 2;
+        }
+    }
+ "#,
+    0
+)]
+fn test_shadow_warnings(#[case] code: &str, #[case] expected_warnings: usize) {
+    let mut stmts = parse_global_statements_input(code).expect("Failed to parse code");
+    let mut binder = binder::Binder::with_shadow_warnings();
+    binder
+        .bind_statements(&mut stmts)
+        .expect("Failed to bind code");
+
+    assert_eq!(binder.warnings().len(), expected_warnings);
+    assert!(binder
+        .warnings()
+        .iter()
+        .all(|w| matches!(w, Warning::ShadowedVariable { name, .. } if name == "a")));
+}
+
+#[test]
+fn test_shadow_warnings_disabled_by_default() {
+    let mut stmts = parse_global_statements_input(
+        r#"
+    function f(a: i32) {
+        let a: i32 = 2;
+    }
+ "#,
+    )
+    .expect("Failed to parse code");
+    let mut binder = binder::Binder::default();
+    binder
+        .bind_statements(&mut stmts)
+        .expect("Failed to bind code");
+
+    assert!(binder.warnings().is_empty());
+}
+
+#[test]
+fn test_bad_return_location() {
+    let code = "let a = 1;\nreturn a;";
+    let mut stmts = parse_statements_input(code).expect("Failed to parse code");
+    let mut binder = binder::Binder::default();
+
+    match binder.visit_statements(&mut stmts) {
+        Err(BinderError::BadReturn { location }) => {
+            let begin = code.find("return").unwrap();
+            assert_eq!(&code[location.begin..location.end], "return");
+            assert_eq!(location.begin, begin);
+        }
+        result => panic!("Expected a BadReturn error, got {:?}", result),
+    }
+}
+
+#[test]
+fn test_parameter_reachable_before_shadowing_let() {
+    let mut stmts = parse_global_statements_input(
+        r#"
+    function f(x: i32): i32 {
+        let y: i32 = x;
+        let x: i32 = y;
+        return x;
+    }
+ "#,
+    )
+    .expect("Failed to parse code");
+    let mut binder = binder::Binder::default();
+    binder
+        .bind_statements(&mut stmts)
+        .expect("Failed to bind code");
+
+    let GlobalStatement::Function(function) = &stmts[0] else {
+        panic!("Statement is not a function");
+    };
+    let body = &function.body.as_ref().unwrap().statements;
+    let (StatementKind::Let(y), StatementKind::Let(x), StatementKind::Return(ret)) =
+        (&body[0].kind, &body[1].kind, &body[2].kind)
+    else {
+        panic!("Unexpected function body");
+    };
+    let definition = |expr: &Expression| match expr {
+        Expression::Literal(literal) => match literal.get_definition() {
+            Definition::LocalVariable(declaration) => *declaration,
+            definition => panic!("Unexpected definition {:?}", definition),
+        },
+        expr => panic!("Expression is not a literal: {:?}", expr),
+    };
+
+    // `y` is initialized with the parameter, the return uses the local
+    assert_eq!(
+        definition(y.init_exp.as_ref().unwrap()),
+        &function.parameters[0] as *const _
+    );
+    assert_eq!(definition(ret.exp.as_ref().unwrap()), x as *const _);
+}
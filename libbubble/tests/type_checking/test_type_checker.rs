@@ -1,10 +1,10 @@
 use libbubble::{
     ast,
-    type_system::{self, TypeCheckerError},
+    type_system::{self, Typable, TypeCheckerError},
 };
 use rstest::rstest;
 
-use crate::assets::run_type_checker;
+use crate::assets::{run_type_checker, type_check_code};
 
 #[rstest]
 #[case::valid_variable_init_with_type_hint(
@@ -200,6 +200,51 @@ use crate::assets::run_type_checker;
         return 0;
     }"#
 )]
+#[case::method_call(
+    r#"
+    struct Point { x: i32 }
+    function Point.scale(self, factor: i32): i32 { return factor; }
+    function main(): i32 {
+        let p: Point;
+        let q: ptr Point = addrof p;
+        return p.scale(2) + q.scale(3);
+    }"#
+)]
+#[case::enum_variants(
+    r#"
+    function is_red(c: Color): bool {
+        return c == Color.Red;
+    }
+    enum Color { Red, Green }
+    function main(): i32 {
+        let c: Color = Color.Green;
+        c = Color.Red;
+        if is_red(c) and c != Color.Green {
+            return 1;
+        }
+        return 0;
+    }"#
+)]
+#[case::while_else(
+    r#"
+    function main(): i32 {
+        let i: i32 = 0;
+        let found: bool;
+        while i < 3 {
+            i = i + 1;
+        } else {
+            found = false;
+        }
+        return i;
+    }"#
+)]
+#[case::constant_non_negative_array_index(
+    r#"
+    function main(): i32 {
+        let arr: [3; i32] = [1, 2, 3];
+        return arr[2 - 2];
+    }"#
+)]
 #[case::array_init_bool_inference(
     r#"
     function main(): i32 {
@@ -383,6 +428,530 @@ use crate::assets::run_type_checker;
         return 0;
     }"#
 )]
+#[case::signed_literal_lower_bound(
+    r#"
+    function f(): i32 {
+        let x: i8 = -128;
+        return 0;
+    }"#
+)]
+#[case::unsigned_literal_upper_bound(
+    r#"
+    function f(): i32 {
+        let x: u8 = 255;
+        return 0;
+    }"#
+)]
+#[case::const_in_expression(
+    r#"
+    const MAX: i32 = 100;
+    function f(): i32 {
+        let x: i32 = MAX - 1;
+        return MAX * 2;
+    }"#
+)]
+#[case::const_from_other_const(
+    r#"
+    const WIDTH: u64 = 8;
+    const AREA: u64 = WIDTH * WIDTH;
+    function f(): u64 {
+        return AREA;
+    }"#
+)]
+#[case::array_size_from_const(
+    r#"
+    const SIZE: u32 = 3;
+    function f(): i32 {
+        let arr: [SIZE; i32] = [1, 2, 3];
+        return arr[0];
+    }"#
+)]
+#[case::array_size_expression(
+    r#"
+    const SIZE: u32 = 2;
+    function f(): i32 {
+        let arr: [SIZE * 3 - 1; i32] = [1, 2, 3, 4, 5];
+        return arr[0];
+    }"#
+)]
+#[case::slice_of_array(
+    r#"
+    function f(): i32 {
+        let arr: [4; i32] = [1, 2, 3, 4];
+        let s = arr[1..3];
+        return s[0];
+    }"#
+)]
+#[case::slice_of_slice(
+    r#"
+    function f(): i32 {
+        let arr: [4; i32] = [1, 2, 3, 4];
+        let start: u32 = 1;
+        let s = arr[start..4];
+        let t = s[0..2];
+        return t[1];
+    }"#
+)]
+#[case::extern_variable(
+    r#"
+    extern let errno: i32;
+    function f(): i32 {
+        errno = 0;
+        return errno + 1;
+    }"#
+)]
+#[case::variadic_call(
+    r#"
+    extern function printf(msg: string, ...): i32;
+    function f(): i32 {
+        let x: i64 = 3;
+        printf("%d %ld %s", 1, x, "str");
+        return printf("no variadic arguments");
+    }"#
+)]
+#[case::string_comparison(
+    r#"
+    extern function getenv(name: string): string;
+    function f(): bool {
+        let a: string = "a";
+        return a == "b" or getenv("HOME") != a;
+    }"#
+)]
+#[case::negative_literal_lower_bounds(
+    r#"
+    const MIN: i8 = -128;
+    function g(a: i8): i8 {
+        return -128;
+    }
+    function f(): i32 {
+        let x: i8 = -(128);
+        let y: i32 = -2147483648;
+        g(-128);
+        return 0;
+    }"#
+)]
+#[case::assert_bool_condition(
+    r#"
+       function f(a: i64): i64 {
+           assert(a > 0 and a != 42);
+           return a;
+       }
+   "#
+)]
+#[case::assert_with_declared_printf(
+    r#"
+       extern "C" function printf(format: string, ...): i32;
+       function f(a: i64): i64 {
+           printf("%ld\n", a);
+           assert(a > 0);
+           return a;
+       }
+   "#
+)]
+#[case::global_array_bigger_than_stack_limit(
+    r#"
+       extern let big: [1000000; i64];
+   "#
+)]
+#[case::addrof_array(
+    r#"
+       function f() {
+           let arr: [3; i32] = [1, 2, 3];
+           let p: ptr [3; i32] = addrof arr;
+       }
+   "#
+)]
+#[case::addrof_array_element_decay(
+    r#"
+       function f(): i32 {
+           let arr: [3; i32] = [1, 2, 3];
+           let p: ptr i32 = addrof arr[0];
+           return deref p;
+       }
+   "#
+)]
+#[case::array_argument_decay(
+    r#"
+       function first(p: ptr i32): i32 { return deref p; }
+       function f(): i32 {
+           let arr: [3; i32] = [1, 2, 3];
+           return first(arr);
+       }
+   "#
+)]
+#[case::array_argument_decay_indirect_call(
+    r#"
+       function first(p: ptr i32): i32 { return deref p; }
+       function f(): i32 {
+           let arr: [3; i32] = [1, 2, 3];
+           let table: [1; ptr function(ptr i32): i32] = [first];
+           return table[0](arr);
+       }
+   "#
+)]
+#[case::function_pointer_null_init(
+    r#"
+       function f() {
+           let callback: ptr function(i32): void = null;
+       }
+   "#
+)]
+#[case::function_pointer_compatible_init(
+    r#"
+       extern let g: ptr function(i32): i32;
+       function f() {
+           let callback: ptr function(i32): i32 = g;
+       }
+   "#
+)]
+#[case::empty_void_function(
+    r#"
+        function f() { }
+   "#
+)]
+#[case::every_branch_returns(
+    r#"
+        function f(c: bool): i32 {
+            if c {
+                return 1;
+            } else {
+                return 2;
+            }
+        }
+   "#
+)]
+#[case::call_before_definition(
+    r#"
+        function is_even(n: u32): bool {
+            if n == 0 {
+                return true;
+            }
+            return is_odd(n - 1);
+        }
+        function is_odd(n: u32): bool {
+            if n == 0 {
+                return false;
+            }
+            return is_even(n - 1);
+        }
+   "#
+)]
+#[case::min_max_builtins(
+    r#"
+        function f(a: i32, b: i32): i32 {
+            let smallest: i32 = min(a, b);
+            return max(smallest, 3);
+        }
+   "#
+)]
+#[case::min_max_builtins_integer_literals(
+    r#"
+        function f(): u8 {
+            return max(min(1, 2), 3);
+        }
+   "#
+)]
+#[case::is_null_builtin(
+    r#"
+        extern function malloc(size: u64): ptr void;
+        function f(p: ptr i32): bool {
+            return is_null(p) or is_null(malloc(4));
+        }
+   "#
+)]
+#[case::len_builtin(
+    r#"
+        function f(): u64 {
+            let a: u64 = len("abc");
+            return a + len("a\0b");
+        }
+   "#
+)]
+#[case::builtin_shadowed_by_function(
+    r#"
+        function min(a: bool): bool {
+            return a;
+        }
+        function f(): bool {
+            return min(true);
+        }
+   "#
+)]
+#[case::integers_128_bits(
+    r#"
+        function f(a: i128): u128 {
+            let b: i128 = a + 9223372036854775807;
+            let c: u128 = 18;
+            return c * 2;
+        }
+   "#
+)]
+#[case::literals_above_64_bits(
+    r#"
+        function f(): i128 {
+            let a: u128 = 18446744073709551616;
+            let b: i128 = -170141183460469231731687303715884105727;
+            return b;
+        }
+   "#
+)]
+#[case::struct_variable_init(
+    r#"
+    struct Point { x: i32, y: i32 }
+    function f(p: Point): i32 {
+        let copy: Point = p;
+        let other: Point = copy;
+        return 0;
+    }
+    "#
+)]
+#[case::struct_return(
+    r#"
+    struct Point { x: i32, y: i32 }
+    function f(p: Point): Point {
+        let copy: Point = p;
+        return copy;
+    }
+    "#
+)]
+#[case::empty_struct_local(
+    r#"
+        struct Empty {}
+        function f(e: Empty) {
+            let p: ptr Empty = addrof e;
+        }
+   "#
+)]
+#[case::empty_struct_uninitialized_local(
+    r#"
+        struct Empty {}
+        function f(other: Empty) {
+            let e: Empty;
+            e = other;
+            let p: ptr Empty = addrof e;
+        }
+   "#
+)]
+#[case::deferred_initialization(
+    r#"
+        function f(c: bool): i32 {
+            let a: i32;
+            a = 1;
+            let b: i32;
+            if c {
+                b = a;
+            } else {
+                b = 2;
+            }
+            return b;
+        }
+   "#
+)]
+#[case::nested_branches_initialize(
+    r#"
+        function f(c: bool, d: bool): i32 {
+            let a: i32;
+            if c {
+                if d {
+                    a = 1;
+                } else {
+                    a = 2;
+                }
+            } else {
+                a = 3;
+            }
+            return a;
+        }
+   "#
+)]
+#[case::deferred_initialization_returning_branch(
+    r#"
+        function f(c: bool): i32 {
+            let a: i32;
+            if c {
+                return 0;
+            } else {
+                a = 2;
+            }
+            return a;
+        }
+   "#
+)]
+#[case::deferred_initialization_through_pointer(
+    r#"
+        function init(p: ptr i32) {
+            deref p = 42;
+        }
+        function f(): i32 {
+            let a: i32;
+            init(addrof a);
+            return a;
+        }
+   "#
+)]
+#[case::alignof(
+    r#"
+        struct Point { x: i32, y: i64 }
+        function f(): u64 {
+            let a: u64 = alignof(i32);
+            return a + alignof(Point);
+        }
+   "#
+)]
+#[case::alignof_array(
+    r#"
+        const SIZE: u32 = 2 + 2;
+        function f(): u64 {
+            return alignof([SIZE; i8]) + alignof([4; i64]);
+        }
+   "#
+)]
+#[case::pointer_comparison(
+    r#"
+        function f(p: ptr i32, q: ptr i32, v: ptr void): bool {
+            return p == q or p != null or null == q or p == v;
+        }
+   "#
+)]
+#[case::tuple_return(
+    r#"
+        function divmod(a: i32, b: i32): (i32, i32) {
+            return (a / b, a % b);
+        }
+        function f(): i32 {
+            let (q, r) = divmod(17, 5);
+            return q * 10 + r;
+        }
+   "#
+)]
+#[case::tuple_of_integer_literals(
+    r#"
+        function f(): (u8, i64) {
+            return (0, 1);
+        }
+        function g(): i64 {
+            let (a, b) = f();
+            return b;
+        }
+   "#
+)]
+#[case::array_destructuring(
+    r#"
+        function f(): i32 {
+            let values: [3; i32] = [1, 2, 3];
+            let [a, b, c] = values;
+            let [x, y] = [true, false];
+            return a + b + c;
+        }
+   "#
+)]
+#[case::static_counter(
+    r#"
+        function counter(): i32 {
+            static count: i32 = 0;
+            count = count + 1;
+            return count;
+        }
+   "#
+)]
+#[case::static_float(
+    r#"
+        function f(): float {
+            static x: float = 1.5;
+            return x;
+        }
+   "#
+)]
+#[case::static_string(
+    r#"
+        function f(): string {
+            static message: string = "hello";
+            return message;
+        }
+   "#
+)]
+#[case::static_null(
+    r#"
+        function f(): ptr i32 {
+            static p: ptr i32 = null;
+            return p;
+        }
+   "#
+)]
+#[case::c_variadic_function(
+    r#"
+        extern "C" function printf(format: string, ...): i32;
+        function f(): i32 {
+            return printf("%d", 42);
+        }
+   "#
+)]
+#[case::dispatch_table(
+    r#"
+        function inc(x: i32): i32 { return x + 1; }
+        function dec(x: i32): i32 { return x - 1; }
+        function f(i: u8): i32 {
+            let table: [2; ptr function(i32): i32] = [inc, dec];
+            return table[i](41);
+        }
+   "#
+)]
+#[case::tuple_variable(
+    r#"
+        function f(flag: bool): (i32, bool) {
+            let t: (i32, bool) = (1, flag);
+            return t;
+        }
+   "#
+)]
+#[case::try_null(
+    r#"
+        extern function malloc(size: u64): ptr void;
+        function alloc_pair(): ptr void {
+            let first: ptr void = malloc(8)?;
+            let second = malloc(8)?;
+            return second;
+        }
+   "#
+)]
+#[case::try_null_other_pointer_type(
+    r#"
+        function next(p: ptr i32): ptr u8 {
+            let value: i32 = (deref p?) + 1;
+            return null;
+        }
+   "#
+)]
+#[case::cfg_attribute(
+    r#"
+        @cfg(DEBUG) @noinline
+        function log(value: i32) {}
+   "#
+)]
+#[case::integer_expression_statement(
+    r#"
+        function f() {
+            1 + 2;
+            if true {
+                3;
+            }
+        }
+   "#
+)]
+#[case::deref_in_inferred_operation(
+    r#"
+        function f(p: ptr i32) {
+            deref p = (deref p) + 1;
+        }
+   "#
+)]
+#[case::uninferred_deferred_integer(
+    r#"
+        function f() {
+            defer 42;
+        }
+   "#
+)]
 fn type_checker_valid(#[case] code: &str) {
     let result = run_type_checker(code);
     assert!(
@@ -420,7 +989,10 @@ fn type_checker_valid(#[case] code: &str) {
            while 32.0 { 42 }
        }
    "#,
-    TypeCheckerError::NonBoolCondition(type_system::Type::Float)
+    TypeCheckerError::NonBoolCondition {
+        ty: type_system::Type::Float,
+        location: ast::TokenLocation::new(0, 0),
+    }
 )]
 #[case::extern_function_declaration_call_bad_args(
     r#"
@@ -439,7 +1011,10 @@ fn type_checker_valid(#[case] code: &str) {
            if 32.4 { 32 }
        }
    "#,
-    TypeCheckerError::NonBoolCondition(type_system::Type::Float)
+    TypeCheckerError::NonBoolCondition {
+        ty: type_system::Type::Float,
+        location: ast::TokenLocation::new(0, 0),
+    }
 )]
 #[case::condition_not_bool_if_else(
     r#"
@@ -447,7 +1022,10 @@ fn type_checker_valid(#[case] code: &str) {
            if 32.4 { 32 } else { 51 }
        }
    "#,
-    TypeCheckerError::NonBoolCondition(type_system::Type::Float)
+    TypeCheckerError::NonBoolCondition {
+        ty: type_system::Type::Float,
+        location: ast::TokenLocation::new(0, 0),
+    }
 )]
 #[case::condition_not_bool_for_continue_expr(
     r#"
@@ -455,7 +1033,10 @@ fn type_checker_valid(#[case] code: &str) {
            for i = 0; 32.0; i = i + 1 { 32 }
        }
    "#,
-    TypeCheckerError::NonBoolCondition(type_system::Type::Float)
+    TypeCheckerError::NonBoolCondition {
+        ty: type_system::Type::Float,
+        location: ast::TokenLocation::new(0, 0),
+    }
 )]
 #[case::bad_parameters_missing_args(
     r#"
@@ -506,7 +1087,11 @@ fn type_checker_valid(#[case] code: &str) {
            return 42;
        }
    "#,
-    TypeCheckerError::ReturnTypeMismatch { got: type_system::Type::Int, expected: type_system::Type::Void }
+    TypeCheckerError::ReturnTypeMismatch {
+        got: type_system::Type::Int,
+        expected: type_system::Type::Void,
+        location: ast::TokenLocation::new(0, 0),
+    }
 )]
 #[case::wrong_int_type_return(
     r#"
@@ -515,7 +1100,11 @@ fn type_checker_valid(#[case] code: &str) {
            return a;
        }
    "#,
-    TypeCheckerError::ReturnTypeMismatch { got: type_system::Type::U32, expected: type_system::Type::I32 }
+    TypeCheckerError::ReturnTypeMismatch {
+        got: type_system::Type::U32,
+        expected: type_system::Type::I32,
+        location: ast::TokenLocation::new(0, 0),
+    }
 )]
 #[case::return_type_mismatch(
     r#"
@@ -523,7 +1112,11 @@ fn type_checker_valid(#[case] code: &str) {
            return 42;
        }
    "#,
-    TypeCheckerError::ReturnTypeMismatch { got: type_system::Type::Int, expected: type_system::Type::Float }
+    TypeCheckerError::ReturnTypeMismatch {
+        got: type_system::Type::Int,
+        expected: type_system::Type::Float,
+        location: ast::TokenLocation::new(0, 0),
+    }
 )]
 #[case::let_string_type_hint_bad_init(
     r#"
@@ -548,7 +1141,11 @@ fn type_checker_valid(#[case] code: &str) {
            return "hello";
        }
    "#,
-    TypeCheckerError::ReturnTypeMismatch { got: type_system::Type::String, expected: type_system::Type::I32 }
+    TypeCheckerError::ReturnTypeMismatch {
+        got: type_system::Type::String,
+        expected: type_system::Type::I32,
+        location: ast::TokenLocation::new(0, 0),
+    }
 )]
 #[case::for_int_inference_without_hint(
     r#"
@@ -647,8 +1244,23 @@ fn type_checker_valid(#[case] code: &str) {
     }"#,
     TypeCheckerError::BadParameter {
         name: "arr".to_string(),
-        expected_type: type_system::Type::Array { size: 4, array_type: Box::new(type_system::Type::U32) },
-        got: type_system::Type::Array { size: 4, array_type: Box::new(type_system::Type::Bool) } }
+        expected_type: type_system::Type::Array { size: 4, array_type: Box::new(type_system::Type::Bool) },
+        got: type_system::Type::Array { size: 3, array_type: Box::new(type_system::Type::Bool) } }
+)]
+#[case::inferred_array_as_function_parameter_wrong_size(
+    r#"
+    function f(arr: [4; bool]) {
+        arr[0];
+    }
+    function main(): i32 {
+        let arr = [false, false, false];
+        f(arr);
+        return 0;
+    }"#,
+    TypeCheckerError::BadParameter {
+        name: "arr".to_string(),
+        expected_type: type_system::Type::Array { size: 4, array_type: Box::new(type_system::Type::Bool) },
+        got: type_system::Type::Array { size: 3, array_type: Box::new(type_system::Type::Bool) } }
 )]
 #[case::array_assign_bad_type(
     r#"
@@ -682,7 +1294,8 @@ fn type_checker_valid(#[case] code: &str) {
     }"#,
     TypeCheckerError::ReturnTypeMismatch {
         got: type_system::Type::I32,
-        expected: type_system::Type::Ptr(Box::new(type_system::Type::I32))
+        expected: type_system::Type::Ptr(Box::new(type_system::Type::I32)),
+        location: ast::TokenLocation::new(0, 0),
     } ,
 )]
 #[case::bad_addrof_var_init(
@@ -722,6 +1335,7 @@ fn type_checker_valid(#[case] code: &str) {
     TypeCheckerError::ReturnTypeMismatch {
         got: type_system::Type::Ptr(Box::new(type_system::Type::I32)),
         expected: type_system::Type::I32,
+        location: ast::TokenLocation::new(0, 0),
     },
 )]
 #[case::deref_non_ptr(
@@ -733,13 +1347,1345 @@ fn type_checker_valid(#[case] code: &str) {
     }"#,
     TypeCheckerError::DerefNonPointer(type_system::Type::I32)
 )]
-fn type_checker_invalid(#[case] code: &str, #[case] expected_error: TypeCheckerError) {
-    let result = run_type_checker(code);
-
-    assert!(result.is_err(), "Result should be an error");
-    let err = result.unwrap_err();
-    assert_eq!(
-        err, expected_error,
-        "got: {err:?} expected: {expected_error:?}"
-    );
+#[case::unsigned_literal_out_of_range(
+    r#"
+    function main(): i32 {
+        let x: u8 = 256;
+        return 0;
+    }"#,
+    TypeCheckerError::IntegerLiteralOutOfRange {
+        value: 256,
+        ty: type_system::Type::U8,
+    }
+)]
+#[case::signed_literal_out_of_range(
+    r#"
+    function main(): i32 {
+        let x: i8 = 128;
+        return 0;
+    }"#,
+    TypeCheckerError::IntegerLiteralOutOfRange {
+        value: 128,
+        ty: type_system::Type::I8,
+    }
+)]
+#[case::u64_literal_out_of_range(
+    r#"
+    function main(): i32 {
+        let x: u64 = 18446744073709551616;
+        return 0;
+    }"#,
+    TypeCheckerError::IntegerLiteralOutOfRange {
+        value: 18446744073709551616,
+        ty: type_system::Type::U64,
+    }
+)]
+#[case::const_non_constant_initializer(
+    r#"
+    function g(): i32 {
+        return 42;
+    }
+    const MAX: i32 = g();
+    function main(): i32 {
+        return 0;
+    }"#,
+    TypeCheckerError::NonConstantInitializer {
+        name: "MAX".to_string(),
+    }
+)]
+#[case::const_folded_value_out_of_range(
+    r#"
+    const MAX: u8 = 200 + 100;
+    function main(): i32 {
+        return 0;
+    }"#,
+    TypeCheckerError::IntegerLiteralOutOfRange {
+        value: 300,
+        ty: type_system::Type::U8,
+    }
+)]
+#[case::const_assignment(
+    r#"
+    const MAX: i32 = 100;
+    function main(): i32 {
+        MAX = 3;
+        return 0;
+    }"#,
+    TypeCheckerError::ConstantAssignment {
+        name: "MAX".to_string(),
+    }
+)]
+#[case::negative_array_index(
+    r#"
+    function main(): i32 {
+        let arr: [3; i32] = [1, 2, 3];
+        return arr[-1];
+    }"#,
+    TypeCheckerError::NegativeArrayIndex {
+        value: -1,
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::negative_constant_array_index(
+    r#"
+    const OFFSET: i64 = 2;
+    function main(): i32 {
+        let arr: [3; i32] = [1, 2, 3];
+        arr[1 - OFFSET] = 4;
+        return 0;
+    }"#,
+    TypeCheckerError::NegativeArrayIndex {
+        value: -1,
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::method_bad_argument(
+    r#"
+    struct Point { x: i32 }
+    function Point.scale(self, factor: i32): i32 { return factor; }
+    function main(): i32 {
+        let p: Point;
+        return p.scale(true);
+    }"#,
+    TypeCheckerError::BadParameter {
+        name: "factor".to_string(),
+        expected_type: type_system::Type::I32,
+        got: type_system::Type::Bool,
+    }
+)]
+#[case::enum_arithmetic(
+    r#"
+    enum Color { Red, Green }
+    function main(): i32 {
+        let c: Color = Color.Red + Color.Green;
+        return 0;
+    }"#,
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Plus,
+        left_ty: type_system::Type::Void,
+        right_ty: type_system::Type::Void,
+    }
+)]
+#[case::enum_compared_to_integer(
+    r#"
+    enum Color { Red, Green }
+    function main(): i32 {
+        let is_zero = Color.Red == 0;
+        return 0;
+    }"#,
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Equal,
+        left_ty: type_system::Type::Void,
+        right_ty: type_system::Type::Void,
+    }
+)]
+#[case::different_enums_compared(
+    r#"
+    enum Color { Red }
+    enum Shape { Circle }
+    function main(): i32 {
+        let same = Color.Red == Shape.Circle;
+        return 0;
+    }"#,
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Equal,
+        left_ty: type_system::Type::Void,
+        right_ty: type_system::Type::Void,
+    }
+)]
+#[case::enum_initialized_with_integer(
+    r#"
+    enum Color { Red }
+    function main(): i32 {
+        let c: Color = 0;
+        return 0;
+    }"#,
+    TypeCheckerError::BadInit {
+        left: type_system::Type::Void,
+        right: type_system::Type::Void,
+    }
+)]
+#[case::bool_plus(
+    "function f(): bool { return false + true; }",
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Plus,
+        left_ty: type_system::Type::Bool,
+        right_ty: type_system::Type::Bool,
+    }
+)]
+#[case::bool_minus(
+    "function f(): bool { return false - true; }",
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Minus,
+        left_ty: type_system::Type::Bool,
+        right_ty: type_system::Type::Bool,
+    }
+)]
+#[case::bool_multiply(
+    "function f(): bool { return false * true; }",
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Multiply,
+        left_ty: type_system::Type::Bool,
+        right_ty: type_system::Type::Bool,
+    }
+)]
+#[case::bool_divide(
+    "function f(): bool { return false / true; }",
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Divide,
+        left_ty: type_system::Type::Bool,
+        right_ty: type_system::Type::Bool,
+    }
+)]
+#[case::bool_modulo(
+    "function f(): bool { return false % true; }",
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Modulo,
+        left_ty: type_system::Type::Bool,
+        right_ty: type_system::Type::Bool,
+    }
+)]
+#[case::while_else_bad_condition(
+    "function f() { while 1 { 42; } else { 43; } }",
+    TypeCheckerError::NonBoolCondition {
+        ty: type_system::Type::Void,
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::while_else_skipped_by_break(
+    r#"
+    function f(): i32 {
+        let value: i32;
+        while true {
+            break;
+        } else {
+            value = 1;
+        }
+        return value;
+    }"#,
+    TypeCheckerError::UseOfUninitialized {
+        name: "value".to_string(),
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::non_constant_array_size(
+    r#"
+    function main(): i32 {
+        let size: u32 = 3;
+        let arr: [size; i32] = [1, 2, 3];
+        return 0;
+    }"#,
+    TypeCheckerError::NonConstantArraySize(ast::TokenLocation::new(0, 0))
+)]
+#[case::negative_array_size(
+    r#"
+    function main(): i32 {
+        let arr: [1 - 2; i32] = [1, 2, 3];
+        return 0;
+    }"#,
+    TypeCheckerError::NonConstantArraySize(ast::TokenLocation::new(0, 0))
+)]
+#[case::array_size_mismatch_with_const(
+    r#"
+    const SIZE: u32 = 2;
+    function main(): i32 {
+        let arr: [SIZE; i32] = [1, 2, 3];
+        return 0;
+    }"#,
+    TypeCheckerError::BadInit {
+        left: type_system::Type::Void,
+        right: type_system::Type::Void,
+    }
+)]
+#[case::slice_non_array(
+    r#"
+    function main(): i32 {
+        let a: i32 = 3;
+        let s = a[0..1];
+        return 0;
+    }"#,
+    TypeCheckerError::NonSubscriptable {
+        ty: type_system::Type::I32
+    }
+)]
+#[case::slice_bound_not_integer(
+    r#"
+    function main(): i32 {
+        let arr: [2; i32] = [1, 2];
+        let s = arr[0..true];
+        return 0;
+    }"#,
+    TypeCheckerError::IndexNotInteger {
+        got: type_system::Type::Bool
+    }
+)]
+#[case::extern_variable_bad_assignment(
+    r#"
+    extern let errno: i32;
+    function main(): i32 {
+        errno = true;
+        return 0;
+    }"#,
+    TypeCheckerError::BadAssigment {
+        left: type_system::Type::I32,
+        right: type_system::Type::Bool,
+    }
+)]
+#[case::variadic_call_missing_fixed_parameter(
+    r#"
+    extern function printf(msg: string, ...): i32;
+    function main(): i32 {
+        printf();
+        return 0;
+    }"#,
+    TypeCheckerError::BadParameterCount { expected: 1, got: 0 }
+)]
+#[case::variadic_call_bad_fixed_parameter(
+    r#"
+    extern function printf(msg: string, ...): i32;
+    function main(): i32 {
+        printf(1, 2);
+        return 0;
+    }"#,
+    TypeCheckerError::BadParameter {
+        name: "msg".to_string(),
+        expected_type: type_system::Type::String,
+        got: type_system::Type::Int,
+    }
+)]
+#[case::negative_literal_out_of_range(
+    r#"
+    function main(): i32 {
+        let x: i8 = -129;
+        return 0;
+    }"#,
+    TypeCheckerError::IntegerLiteralOutOfRange {
+        value: -129,
+        ty: type_system::Type::I8,
+    }
+)]
+#[case::negative_group_literal_out_of_range(
+    r#"
+    function main(): i32 {
+        let x: i8 = -(129);
+        return 0;
+    }"#,
+    TypeCheckerError::IntegerLiteralOutOfRange {
+        value: -129,
+        ty: type_system::Type::I8,
+    }
+)]
+#[case::negative_unsigned_literal(
+    r#"
+    function main(): i32 {
+        let x: u32 = -1;
+        return 0;
+    }"#,
+    TypeCheckerError::IntegerLiteralOutOfRange {
+        value: -1,
+        ty: type_system::Type::U32,
+    }
+)]
+#[case::assert_not_bool_condition(
+    r#"
+       function f(a: i64) {
+           assert(a);
+       }
+   "#,
+    TypeCheckerError::NonBoolCondition {
+        ty: type_system::Type::I64,
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::assert_with_incompatible_printf(
+    r#"
+       extern function printf(msg: string, value: i32): i32;
+       function f(a: i32) {
+           printf("%d\n", a);
+           assert(a > 0);
+       }
+   "#,
+    TypeCheckerError::IncompatibleAssertFunction {
+        name: String::new(),
+        expected: String::new(),
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::local_array_too_large(
+    r#"
+       extern function f(): [1000000000; i64];
+       function g() {
+           let a: [1000000000; i64] = f();
+       }
+   "#,
+    TypeCheckerError::StackAllocationTooLarge { name: String::new(), size: 0, max: 0 }
+)]
+#[case::local_array_zero_size(
+    r#"
+       function f() {
+           let a: [0; i32] = [];
+       }
+   "#,
+    TypeCheckerError::ZeroSizeArray { name: String::new() }
+)]
+#[case::local_inferred_array_too_large(
+    r#"
+       extern function f(): [200000; i64];
+       function g() {
+           let a = f();
+       }
+   "#,
+    TypeCheckerError::StackAllocationTooLarge { name: String::new(), size: 0, max: 0 }
+)]
+#[case::array_argument_decay_wrong_element_type(
+    r#"
+       function first(p: ptr i32): i32 { return deref p; }
+       function f(): i32 {
+           let arr: [3; i64] = [1, 2, 3];
+           return first(arr);
+       }
+   "#,
+    TypeCheckerError::BadParameter {
+        name: "p".to_string(),
+        expected_type: type_system::Type::Ptr(Box::new(type_system::Type::I32)),
+        got: type_system::Type::Array {
+            size: 3,
+            array_type: Box::new(type_system::Type::I64),
+        },
+    }
+)]
+#[case::array_literal_argument_no_decay(
+    r#"
+       function first(p: ptr i32): i32 { return deref p; }
+       function f(): i32 {
+           return first([1, 2, 3]);
+       }
+   "#,
+    TypeCheckerError::BadParameter {
+        name: "p".to_string(),
+        expected_type: type_system::Type::Ptr(Box::new(type_system::Type::I32)),
+        got: type_system::Type::Array {
+            size: 3,
+            array_type: Box::new(type_system::Type::Int),
+        },
+    }
+)]
+#[case::addrof_array_no_implicit_decay(
+    r#"
+       function f() {
+           let arr: [3; i32] = [1, 2, 3];
+           let p: ptr i32 = addrof arr;
+       }
+   "#,
+    TypeCheckerError::BadInit {
+        left: type_system::Type::Ptr(Box::new(type_system::Type::I32)),
+        right: type_system::Type::Ptr(Box::new(type_system::Type::Array {
+            size: 3,
+            array_type: Box::new(type_system::Type::I32),
+        })),
+    }
+)]
+#[case::function_pointer_mismatch(
+    r#"
+       extern let g: ptr function(i32): i32;
+       function f() {
+           let callback: ptr function(bool): i32 = g;
+       }
+   "#,
+    TypeCheckerError::BadInit {
+        left: type_system::Type::Void,
+        right: type_system::Type::Void,
+    }
+)]
+#[case::empty_i32_function(
+    r#"
+        function f(): i32 { }
+   "#,
+    TypeCheckerError::MissingReturn {
+        name: "f".to_string(),
+    }
+)]
+#[case::return_only_in_then_branch(
+    r#"
+        function f(c: bool): i32 {
+            if c {
+                return 1;
+            }
+        }
+   "#,
+    TypeCheckerError::MissingReturn {
+        name: "f".to_string(),
+    }
+)]
+#[case::return_only_in_loop(
+    r#"
+        function f(c: bool): i32 {
+            while c {
+                return 1;
+            }
+        }
+   "#,
+    TypeCheckerError::MissingReturn {
+        name: "f".to_string(),
+    }
+)]
+#[case::min_different_types(
+    r#"
+        function f(a: i32, b: i64): i32 {
+            return min(a, b);
+        }
+   "#,
+    TypeCheckerError::BadBuiltinArguments {
+        name: "min".to_string(),
+        left: type_system::Type::I32,
+        right: type_system::Type::I64,
+    }
+)]
+#[case::max_non_numeric(
+    r#"
+        function f(a: bool, b: bool): bool {
+            return max(a, b);
+        }
+   "#,
+    TypeCheckerError::BadBuiltinArguments {
+        name: "max".to_string(),
+        left: type_system::Type::Bool,
+        right: type_system::Type::Bool,
+    }
+)]
+#[case::max_bad_argument_count(
+    r#"
+        function f(a: i32): i32 {
+            return max(a);
+        }
+   "#,
+    TypeCheckerError::BadParameterCount {
+        expected: 2,
+        got: 1,
+    }
+)]
+#[case::is_null_non_pointer(
+    r#"
+        function f(a: i64): bool {
+            return is_null(a);
+        }
+   "#,
+    TypeCheckerError::ExpectedPointerArgument {
+        name: "is_null".to_string(),
+        ty: type_system::Type::I64,
+    }
+)]
+#[case::is_null_bad_argument_count(
+    r#"
+        function f(a: ptr i64, b: ptr i64): bool {
+            return is_null(a, b);
+        }
+   "#,
+    TypeCheckerError::BadParameterCount {
+        expected: 1,
+        got: 2,
+    }
+)]
+#[case::len_non_literal(
+    r#"
+        function f(s: string): u64 {
+            return len(s);
+        }
+   "#,
+    TypeCheckerError::ExpectedStringLiteral {
+        name: "len".to_string(),
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::call_variable(
+    r#"
+        function f() {
+            let a = 2;
+            a();
+        }
+   "#,
+    TypeCheckerError::NotCallable {
+        name: "a".to_string(),
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::call_struct(
+    r#"
+        struct Point { x: i32 }
+        function f() {
+            Point();
+        }
+   "#,
+    TypeCheckerError::NotCallable {
+        name: "Point".to_string(),
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::min_result_type_mismatch(
+    r#"
+        function f(a: i64, b: i64): i32 {
+            return min(a, b);
+        }
+   "#,
+    TypeCheckerError::ReturnTypeMismatch {
+        got: type_system::Type::I64,
+        expected: type_system::Type::I32,
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::mix_64_and_128_bits(
+    r#"
+        function f(a: i128, b: i64): i128 {
+            return a + b;
+        }
+   "#,
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Plus,
+        left_ty: type_system::Type::I128,
+        right_ty: type_system::Type::I64,
+    }
+)]
+#[case::read_uninitialized(
+    r#"
+        function f(): i32 {
+            let a: i32;
+            return a;
+        }
+   "#,
+    TypeCheckerError::UseOfUninitialized {
+        name: "a".to_string(),
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::assigned_in_one_branch(
+    r#"
+        function f(c: bool): i32 {
+            let a: i32;
+            if c {
+                a = 1;
+            }
+            return a;
+        }
+   "#,
+    TypeCheckerError::UseOfUninitialized {
+        name: "a".to_string(),
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::assigned_in_loop_body(
+    r#"
+        function f(c: bool): i32 {
+            let a: i32;
+            while c {
+                a = 1;
+            }
+            return a;
+        }
+   "#,
+    TypeCheckerError::UseOfUninitialized {
+        name: "a".to_string(),
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::read_in_own_assignment(
+    r#"
+        function f(): i32 {
+            let a: i32;
+            a = a + 1;
+            return a;
+        }
+   "#,
+    TypeCheckerError::UseOfUninitialized {
+        name: "a".to_string(),
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::string_concatenation(
+    r#"
+        function f(): string {
+            return "a" + "b";
+        }
+   "#,
+    TypeCheckerError::StringConcatenation
+)]
+#[case::string_subtraction(
+    r#"
+        function f(a: string, b: string): string {
+            return a - b;
+        }
+   "#,
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Minus,
+        left_ty: type_system::Type::String,
+        right_ty: type_system::Type::String,
+    }
+)]
+#[case::alignof_void(
+    r#"
+        function f(): u64 {
+            return alignof(void);
+        }
+   "#,
+    TypeCheckerError::NoAlignment {
+        ty: type_system::Type::Void,
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::pointer_ordering(
+    r#"
+        function f(p: ptr i32, q: ptr i32): bool {
+            return p < q;
+        }
+   "#,
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Less,
+        left_ty: type_system::Type::Ptr(Box::new(type_system::Type::I32)),
+        right_ty: type_system::Type::Ptr(Box::new(type_system::Type::I32)),
+    }
+)]
+#[case::pointer_comparison_mismatch(
+    r#"
+        function f(p: ptr i32, q: ptr bool): bool {
+            return p == q;
+        }
+   "#,
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Equal,
+        left_ty: type_system::Type::Ptr(Box::new(type_system::Type::I32)),
+        right_ty: type_system::Type::Ptr(Box::new(type_system::Type::Bool)),
+    }
+)]
+#[case::null_comparison(
+    r#"
+        function f(): bool {
+            return null == null;
+        }
+   "#,
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Equal,
+        left_ty: type_system::Type::Null { concrete_type: None },
+        right_ty: type_system::Type::Null { concrete_type: None },
+    }
+)]
+#[case::fast_variadic_function(
+    r#"
+        extern "fast" function log(format: string, ...): i32;
+   "#,
+    TypeCheckerError::NonCVariadicFunction {
+        name: "log".to_string(),
+        abi: "fast",
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::unknown_attribute(
+    r#"
+        @fast
+        function f() {}
+   "#,
+    TypeCheckerError::UnknownAttribute {
+        name: "fast".to_string(),
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::try_null_non_pointer(
+    r#"
+        function f(a: i32): ptr i32 {
+            let b = a?;
+            return null;
+        }
+   "#,
+    TypeCheckerError::TryNullNonPointer {
+        ty: type_system::Type::I32,
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::try_null_in_non_pointer_function(
+    r#"
+        function f(p: ptr i32): i32 {
+            return deref p?;
+        }
+   "#,
+    TypeCheckerError::TryNullOutsidePointerFunction {
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::cfg_without_symbol(
+    r#"
+        @cfg
+        function f() {}
+   "#,
+    TypeCheckerError::UnknownAttribute {
+        name: "cfg".to_string(),
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::conflicting_attributes(
+    r#"
+        @inline @noinline
+        function f() {}
+   "#,
+    TypeCheckerError::ConflictingAttributes {
+        name: "f".to_string(),
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::destructuring_arity_mismatch(
+    r#"
+        function f(): (i32, i32) {
+            return (1, 2);
+        }
+        function g() {
+            let (a, b, c) = f();
+        }
+   "#,
+    TypeCheckerError::BadDestructuring {
+        expected: 3,
+        ty: type_system::Type::Void,
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::destructuring_non_tuple(
+    r#"
+        function g(x: i32) {
+            let (a, b) = x;
+        }
+   "#,
+    TypeCheckerError::BadDestructuring {
+        expected: 2,
+        ty: type_system::Type::Void,
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::array_destructuring_length_mismatch(
+    r#"
+        function g() {
+            let values: [2; i32] = [1, 2];
+            let [a, b, c] = values;
+        }
+   "#,
+    TypeCheckerError::BadDestructuring {
+        expected: 3,
+        ty: type_system::Type::Void,
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::array_destructuring_tuple(
+    r#"
+        function g(t: (i32, i32)) {
+            let [a, b] = t;
+        }
+   "#,
+    TypeCheckerError::BadDestructuring {
+        expected: 2,
+        ty: type_system::Type::Void,
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::static_non_constant(
+    r#"
+        function g(x: i32) {
+            static count: i32 = x;
+        }
+   "#,
+    TypeCheckerError::NonConstantStaticInitializer {
+        name: String::new(),
+    }
+)]
+#[case::indirect_call_non_function(
+    r#"
+        function g() {
+            let values: [2; i32] = [1, 2];
+            values[0]();
+        }
+   "#,
+    TypeCheckerError::NotAFunctionPointer {
+        ty: type_system::Type::Void,
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::fast_function_pointer(
+    r#"
+        extern "fast" function inc(x: i32): i32 { return x + 1; }
+        function g(): i32 {
+            let table: [1; ptr function(i32): i32] = [inc];
+            return table[0](41);
+        }
+   "#,
+    TypeCheckerError::NonCFunctionPointer {
+        name: String::new(),
+        abi: "fast",
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::indirect_call_bad_parameter(
+    r#"
+        function inc(x: i32): i32 { return x + 1; }
+        function g() {
+            let table: [1; ptr function(i32): i32] = [inc];
+            table[0](true);
+        }
+   "#,
+    TypeCheckerError::BadParameter {
+        name: String::new(),
+        expected_type: type_system::Type::Void,
+        got: type_system::Type::Void,
+    }
+)]
+#[case::indirect_call_bad_parameter_count(
+    r#"
+        function inc(x: i32): i32 { return x + 1; }
+        function g() {
+            let table: [1; ptr function(i32): i32] = [inc];
+            table[0]();
+        }
+   "#,
+    TypeCheckerError::BadParameterCount {
+        expected: 1,
+        got: 0,
+    }
+)]
+#[case::function_assignment(
+    r#"
+        function inc(x: i32): i32 { return x + 1; }
+        function dec(x: i32): i32 { return x - 1; }
+        function g() {
+            inc = dec;
+        }
+   "#,
+    TypeCheckerError::ConstantAssignment {
+        name: String::new(),
+    }
+)]
+#[case::tuple_addition(
+    r#"
+        function f(a: (i32, i32), b: (i32, i32)): i32 {
+            a + b;
+            return 0;
+        }
+   "#,
+    TypeCheckerError::IncompatibleOperationType {
+        operator: ast::OpType::Plus,
+        left_ty: type_system::Type::Void,
+        right_ty: type_system::Type::Void,
+    }
+)]
+#[case::destructuring_integer_literals(
+    r#"
+        function g() {
+            let (a, b) = (1, 2);
+        }
+   "#,
+    TypeCheckerError::InferenceError(ast::TokenLocation::new(0, 0))
+)]
+#[case::tuple_return_type_mismatch(
+    r#"
+        function f(): (i32, i32) {
+            return (1, true);
+        }
+   "#,
+    TypeCheckerError::ReturnTypeMismatch {
+        got: type_system::Type::Void,
+        expected: type_system::Type::Void,
+        location: ast::TokenLocation::new(0, 0),
+    }
+)]
+#[case::defer_bad_argument(
+    r#"
+        extern function free(p: ptr void): void;
+        function f() {
+            defer free(true);
+        }
+   "#,
+    TypeCheckerError::BadParameter {
+        name: String::new(),
+        expected_type: type_system::Type::Void,
+        got: type_system::Type::Void,
+    }
+)]
+fn type_checker_invalid(#[case] code: &str, #[case] expected_error: TypeCheckerError) {
+    let result = run_type_checker(code);
+
+    assert!(result.is_err(), "Result should be an error");
+    let err = result.unwrap_err();
+    assert_eq!(
+        err, expected_error,
+        "got: {err:?} expected: {expected_error:?}"
+    );
+}
+
+#[rstest]
+#[case::pointer_to_function(
+    "extern let f: ptr function(i32, bool): void;",
+    type_system::Type::Ptr(Box::new(type_system::Type::Function {
+        parameters: vec![
+            (type_system::Type::I32, String::new()),
+            (type_system::Type::Bool, String::new()),
+        ],
+        return_type: Box::new(type_system::Type::Void),
+    }))
+)]
+#[case::function_returning_pointer(
+    "extern let f: ptr function(): ptr i64;",
+    type_system::Type::Ptr(Box::new(type_system::Type::Function {
+        parameters: vec![],
+        return_type: Box::new(type_system::Type::Ptr(Box::new(type_system::Type::I64))),
+    }))
+)]
+#[case::parenthesized_type(
+    "extern let p: ptr (ptr i32);",
+    type_system::Type::Ptr(Box::new(type_system::Type::Ptr(Box::new(type_system::Type::I32))))
+)]
+fn type_checker_declared_type(#[case] code: &str, #[case] expected_type: type_system::Type) {
+    let stmts = type_check_code(code).expect("Type checker failed");
+
+    match stmts.first() {
+        Some(ast::GlobalStatement::Let(let_stmt)) => {
+            assert_eq!(let_stmt.get_type(), &expected_type)
+        }
+        stmt => panic!("Expected a let statement but got {stmt:?}"),
+    }
+}
+
+#[rstest]
+#[case::struct_variable("extern let p: Point;")]
+#[case::pointer_to_struct("extern let p: ptr Point;")]
+#[case::array_of_structs("extern let p: [2; Point];")]
+fn type_checker_struct_variable_fields(#[case] declaration: &str) {
+    let code = format!("struct Point {{ x: i32, y: bool }}\n{declaration}");
+    let stmts = type_check_code(&code).expect("Type checker failed");
+
+    let mut ty = match stmts.get(1) {
+        Some(ast::GlobalStatement::Let(let_stmt)) => let_stmt.get_type(),
+        stmt => panic!("Expected a let statement but got {stmt:?}"),
+    };
+    while let type_system::Type::Ptr(inner)
+    | type_system::Type::Array {
+        array_type: inner, ..
+    } = ty
+    {
+        ty = inner.as_ref();
+    }
+
+    match ty {
+        type_system::Type::Struct { name, fields } => {
+            assert_eq!(name, "Point");
+            assert_eq!(
+                fields,
+                &[
+                    (type_system::Type::I32, "x".to_string()),
+                    (type_system::Type::Bool, "y".to_string())
+                ]
+            );
+        }
+        ty => panic!("Expected a struct type but got {ty:?}"),
+    }
+}
+
+#[rstest]
+#[case::integer_placeholder(type_system::Type::Int, "{integer}")]
+#[case::pointer(type_system::Type::Ptr(Box::new(type_system::Type::I32)), "ptr i32")]
+#[case::array(
+    type_system::Type::Array { size: 3, array_type: Box::new(type_system::Type::I32) },
+    "[3; i32]"
+)]
+#[case::function(
+    type_system::Type::Function {
+        parameters: vec![(type_system::Type::U8, "a".to_string()), (type_system::Type::Bool, "b".to_string())],
+        return_type: Box::new(type_system::Type::Void),
+    },
+    "function(u8, bool): void"
+)]
+#[case::untyped_null(type_system::Type::Null { concrete_type: None }, "null")]
+#[case::typed_null(
+    type_system::Type::Null {
+        concrete_type: Some(Box::new(type_system::Type::Ptr(Box::new(type_system::Type::U8)))),
+    },
+    "ptr u8"
+)]
+#[case::struct_name(type_system::Type::Struct { name: "Point".to_string(), fields: vec![] }, "Point")]
+#[case::tuple(
+    type_system::Type::Tuple(vec![type_system::Type::I32, type_system::Type::Bool]),
+    "(i32, bool)"
+)]
+fn type_display(#[case] ty: type_system::Type, #[case] expected: &str) {
+    assert_eq!(ty.display_name(), expected);
+}
+
+#[rstest]
+#[case::integer_placeholder_hidden(
+    "function f(): i32 { return 2 * false; }",
+    "Cannot apply Multiply between {integer} and bool"
+)]
+#[case::source_type_names(
+    r#"
+       function f() {
+           let a: ptr i32 = true;
+       }
+   "#,
+    "ptr i32 cannot be initialized with bool"
+)]
+#[case::use_of_uninitialized(
+    "function f(): i32 { let value: i32; return value; }",
+    "Variable 'value' may be used before being initialized"
+)]
+#[case::string_concatenation(
+    r#"function f(): string { return "a" + "b"; }"#,
+    "Strings can't be concatenated with '+' as there is no allocator to store the result"
+)]
+#[case::negative_array_index(
+    "function f(arr: [2; i32]): i32 { return arr[-2]; }",
+    "Array index -2 is negative"
+)]
+#[case::null_init(
+    "function f() { let a: i32 = null; }",
+    "i32 cannot be initialized with null"
+)]
+#[case::function_pointer_init(
+    "extern let g: ptr function(i32): bool; function f() { let a: i32 = g; }",
+    "i32 cannot be initialized with ptr function(i32): bool"
+)]
+#[case::struct_return(
+    "struct Point { x: i32 } function f(p: Point): i32 { return p; }",
+    "Function return type is i32 but a Point type is returned"
+)]
+#[case::array_parameter(
+    "function g(a: [2; u8]) {} function f(p: ptr u8) { g(p); }",
+    "Expected type [2; u8] as parameter 'a' but got ptr u8"
+)]
+#[case::array_parameter_wrong_size(
+    "function g(a: [4; bool], b: i32) {} function f() { let a = [true, false, true]; g(a, 1); }",
+    "Expected type [4; bool] as parameter 'a' but got [3; bool]"
+)]
+#[case::call_variable("function f() { let a = 2; a(); }", "`a` is not a function")]
+#[case::destructuring_arity_mismatch(
+    "function f(t: (i32, bool)) { let (a, b, c) = t; }",
+    "Cannot destructure (i32, bool) into 3 variables"
+)]
+#[case::array_destructuring_length_mismatch(
+    "function f(a: [2; i32]) { let [x, y, z] = a; }",
+    "Cannot destructure [2; i32] into 3 variables"
+)]
+#[case::static_non_constant(
+    "function f(x: i32) { static count: i32 = x; }",
+    "Static variable 'count' must be initialized with a constant expression"
+)]
+#[case::indirect_call_non_function(
+    "function f(values: [2; i32]) { values[0](); }",
+    "i32 is not a function pointer"
+)]
+#[case::try_null_non_pointer(
+    "function f(a: bool): ptr bool { let b = a?; return null; }",
+    "'?' expects a pointer but got bool"
+)]
+#[case::try_null_in_void_function(
+    "function f(p: ptr i32) { p?; }",
+    "'?' can only be used in a function returning a pointer"
+)]
+#[case::attribute_with_unexpected_argument(
+    "@inline(DEBUG) function f() {}",
+    "Unknown attribute '@inline(DEBUG)'"
+)]
+#[case::pointer_condition(
+    "function f(p: ptr i32) { if p { return; } }",
+    "condition should be of type bool but is ptr i32, use `not is_null(...)` to check that a pointer isn't null"
+)]
+#[case::integer_condition(
+    "function f() { if 5 { return; } }",
+    "condition should be of type bool but is {integer}, use `... != 0` to check that an integer isn't zero"
+)]
+#[case::float_condition(
+    "function f(x: float) { while x { return; } }",
+    "condition should be of type bool but is float"
+)]
+#[case::assert_with_incompatible_abort(
+    "extern function abort(code: i32): void; function f(a: bool) { assert(a); }",
+    "'assert' can't report failures, 'abort' must be declared as `function abort(): void`"
+)]
+fn type_checker_error_message(#[case] code: &str, #[case] expected_message: &str) {
+    let err = run_type_checker(code).expect_err("Result should be an error");
+    assert_eq!(err.to_string(), expected_message);
+}
+
+#[test]
+fn non_bool_condition_location() {
+    let code = "function f(p: ptr i32) { while p { return; } }";
+
+    match run_type_checker(code) {
+        Err(TypeCheckerError::NonBoolCondition { ty, location }) => {
+            assert_eq!(ty, type_system::Type::Ptr(Box::new(type_system::Type::I32)));
+            assert_eq!(&code[location.begin..location.end], "p");
+        }
+        result => panic!("Expected a NonBoolCondition error, got {:?}", result),
+    }
+}
+
+#[rstest]
+#[case::init_declaration(
+    "function f(): i32 { for i = 0; true; i = 1 { return 0; } return 1; }",
+    "i = 0"
+)]
+#[case::modify_expression(
+    "function f(p: ptr i32): i32 { for i: i32 = 0; i < 3; p? { return 0; } return 1; }",
+    "p?"
+)]
+fn for_error_location(#[case] code: &str, #[case] expected_source: &str) {
+    let location = match run_type_checker(code) {
+        Err(TypeCheckerError::InferenceError(location))
+        | Err(TypeCheckerError::TryNullOutsidePointerFunction { location }) => location,
+        result => panic!("Expected an error with a location, got {:?}", result),
+    };
+
+    assert_eq!(&code[location.begin..location.end], expected_source);
+}
+
+#[test]
+fn return_type_mismatch_location() {
+    let code = "function f(c: bool): i32 { if c { return; } return 0; }";
+
+    match run_type_checker(code) {
+        Err(TypeCheckerError::ReturnTypeMismatch {
+            got,
+            expected,
+            location,
+        }) => {
+            assert_eq!(got, type_system::Type::Void);
+            assert_eq!(expected, type_system::Type::I32);
+            assert_eq!(&code[location.begin..location.end], "return;");
+        }
+        result => panic!("Expected a ReturnTypeMismatch error, got {:?}", result),
+    }
+}
+
+#[rstest]
+#[case::nested_integer_groups("let a: i32 = ((((1))));", type_system::Type::I32)]
+#[case::nested_bool_groups("let a = ((((true))));", type_system::Type::Bool)]
+#[case::nested_binary_groups("let a: u8 = ((1 + (2 * (3))));", type_system::Type::U8)]
+fn type_checker_group_type(#[case] code: &str, #[case] expected_type: type_system::Type) {
+    let stmts = type_check_code(code).expect("Type checker failed");
+
+    match stmts.first() {
+        Some(ast::GlobalStatement::Let(ast::LetStatement {
+            init_exp: Some(init_exp),
+            ..
+        })) => {
+            assert!(matches!(init_exp.as_ref(), ast::Expression::Group(_)));
+            assert_eq!(init_exp.get_type(), &expected_type)
+        }
+        stmt => panic!("Expected an initialized let statement but got {stmt:?}"),
+    }
+}
+
+#[rstest]
+#[case::empty_struct(
+    type_system::Type::Struct { name: "Empty".to_string(), fields: vec![] },
+    0
+)]
+#[case::struct_fields(
+    type_system::Type::Struct {
+        name: "A".to_string(),
+        fields: vec![(type_system::Type::I32, "a".to_string()), (type_system::Type::U8, "b".to_string())],
+    },
+    8
+)]
+#[case::struct_padding_between_fields(
+    type_system::Type::Struct {
+        name: "B".to_string(),
+        fields: vec![
+            (type_system::Type::I32, "a".to_string()),
+            (type_system::Type::I64, "b".to_string()),
+            (type_system::Type::I32, "c".to_string()),
+        ],
+    },
+    24
+)]
+#[case::array_of_empty_struct(
+    type_system::Type::Array {
+        size: 4,
+        array_type: Box::new(type_system::Type::Struct { name: "Empty".to_string(), fields: vec![] }),
+    },
+    0
+)]
+#[case::enum_stored_as_i32(
+    type_system::Type::Enum { name: "Color".to_string(), variants: vec![("Red".to_string(), 0)] },
+    4
+)]
+#[case::tuple(
+    type_system::Type::Tuple(vec![type_system::Type::I32, type_system::Type::U8]),
+    8
+)]
+fn type_size(#[case] ty: type_system::Type, #[case] expected_size: u64) {
+    assert_eq!(ty.size_in_bytes(), expected_size);
+}
+
+#[rstest]
+#[case::struct_without_fields(
+    type_system::Type::Struct { name: "A".to_string(), fields: vec![] },
+    type_system::Type::Struct { name: "A".to_string(), fields: vec![(type_system::Type::I32, "a".to_string())] },
+    true
+)]
+#[case::struct_different_names(
+    type_system::Type::Struct { name: "A".to_string(), fields: vec![] },
+    type_system::Type::Struct { name: "B".to_string(), fields: vec![] },
+    false
+)]
+#[case::pointer_to_struct(
+    type_system::Type::Ptr(Box::new(type_system::Type::Struct { name: "A".to_string(), fields: vec![] })),
+    type_system::Type::Ptr(Box::new(type_system::Type::Struct {
+        name: "A".to_string(),
+        fields: vec![(type_system::Type::I32, "a".to_string())],
+    })),
+    true
+)]
+#[case::enum_without_variants(
+    type_system::Type::Enum { name: "Color".to_string(), variants: vec![] },
+    type_system::Type::Enum { name: "Color".to_string(), variants: vec![("Red".to_string(), 0)] },
+    true
+)]
+#[case::different_integers(type_system::Type::I32, type_system::Type::U32, false)]
+#[case::same_arrays(
+    type_system::Type::Array { size: 2, array_type: Box::new(type_system::Type::I8) },
+    type_system::Type::Array { size: 2, array_type: Box::new(type_system::Type::I8) },
+    true
+)]
+#[case::function_parameter_names(
+    type_system::Type::Function {
+        parameters: vec![(type_system::Type::I32, "a".to_string())],
+        return_type: Box::new(type_system::Type::Void),
+    },
+    type_system::Type::Function {
+        parameters: vec![(type_system::Type::I32, "b".to_string())],
+        return_type: Box::new(type_system::Type::Void),
+    },
+    true
+)]
+#[case::function_parameter_types(
+    type_system::Type::Function {
+        parameters: vec![(type_system::Type::I32, "a".to_string())],
+        return_type: Box::new(type_system::Type::Void),
+    },
+    type_system::Type::Function {
+        parameters: vec![(type_system::Type::I64, "a".to_string())],
+        return_type: Box::new(type_system::Type::Void),
+    },
+    false
+)]
+#[case::function_parameter_count(
+    type_system::Type::Function {
+        parameters: vec![(type_system::Type::I32, "a".to_string())],
+        return_type: Box::new(type_system::Type::Void),
+    },
+    type_system::Type::Function {
+        parameters: vec![],
+        return_type: Box::new(type_system::Type::Void),
+    },
+    false
+)]
+#[case::different_types(type_system::Type::Void, type_system::Type::Bool, false)]
+fn type_equality(
+    #[case] left: type_system::Type,
+    #[case] right: type_system::Type,
+    #[case] expected: bool,
+) {
+    assert_eq!(left == right, expected);
+    assert_eq!(right == left, expected);
 }
@@ -0,0 +1,64 @@
+use libbubble::type_system::TypeDumper;
+use rstest::rstest;
+
+use crate::assets::type_check_code;
+
+#[rstest]
+#[case::global_let(
+    "let a: i32 = 1 + (2);",
+    r#"let a: i32
+  1 + (2): i32
+    1: i32
+    (2): i32
+      2: i32
+"#
+)]
+#[case::function(
+    r#"
+    function f(x: u8): bool {
+        let y = x;
+        return y < 2;
+    }"#,
+    r#"function f: function(u8): bool
+  let x: u8
+  let y: u8
+    x: u8
+  y < 2: bool
+    y: u8
+    2: u8
+"#
+)]
+#[case::array_access_operand(
+    r#"
+    function f(): i32 {
+        let arr: [3; i32] = [1, 2, 3];
+        return (arr[0]) + 1;
+    }"#,
+    r#"function f: function(): i32
+  let arr: [3; i32]
+    [1, 2, 3]: [3; i32]
+      1: i32
+      2: i32
+      3: i32
+  (arr[0]) + 1: i32
+    (arr[0]): i32
+      arr[0]: i32
+    1: i32
+"#
+)]
+#[case::struct_fields(
+    "struct A { a: i64, b: ptr A }",
+    r#"struct A
+  a: i64
+  b: ptr A
+"#
+)]
+fn type_dumper_output(#[case] code: &str, #[case] expected: &str) {
+    let stmts = type_check_code(code).expect("Type checker failed");
+
+    let mut dumper = TypeDumper::new(Vec::new(), code);
+    dumper.dump(&stmts).expect("Failed to dump types");
+    let output = String::from_utf8(dumper.into_writer()).expect("Output is not utf-8");
+
+    assert_eq!(output, expected);
+}